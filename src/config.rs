@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use std::iter::Peekable;
 
 pub struct Config {
      arguments : HashSet<Argument>,
@@ -11,6 +12,24 @@ enum Argument {
     ShowElfHeader,
     ShowProgramHeaders,
     ShowSectionHeaders,
+    ShowSymbols,
+    ShowDynamic,
+    ShowRelocations,
+    ShowNotes,
+    ShowVersionInfo,
+    ShowHashStats,
+    ShowSectionGroups,
+    ShowDebugInfo,
+    Addr2Line(Vec<u64>),
+    Resolve(Vec<u64>),
+    StringDump(String),
+    HexDump(String),
+    HexDumpRange(u64, u64),
+    ShowDisassembly,
+    DisassembleSymbol(String),
+    ShowUnwind,
+    ShowCore,
+    ShowInitArrays,
     InspectedBinary(String),
     ThisBinary(String),
 }
@@ -47,6 +66,59 @@ impl Config {
         binary_name.expect("Inspected Binary name expected to be valid")
     }
 
+    // Addresses from every --addr2line occurrence, sorted so output does not depend on set ordering
+    pub fn get_addr2line_addresses(&self) -> Vec<u64> {
+        let mut addresses: Vec<u64> = self.arguments.iter().flat_map(|arg| {
+            if let Argument::Addr2Line(addresses) = arg {
+                addresses.clone()
+            } else {
+                Vec::new()
+            }
+        }).collect();
+        addresses.sort();
+        addresses.dedup();
+        addresses
+    }
+
+    // Section names or indices given to --string-dump, sorted so output does not depend on set ordering
+    pub fn get_string_dump_sections(&self) -> Vec<&String> {
+        let mut sections: Vec<&String> = self.arguments.iter().filter_map(|arg| {
+            if let Argument::StringDump(section) = arg {
+                Some(section)
+            } else {
+                None
+            }
+        }).collect();
+        sections.sort();
+        sections
+    }
+
+    // Section names or indices given to --hex-dump, sorted so output does not depend on set ordering
+    pub fn get_hex_dump_sections(&self) -> Vec<&String> {
+        let mut sections: Vec<&String> = self.arguments.iter().filter_map(|arg| {
+            if let Argument::HexDump(section) = arg {
+                Some(section)
+            } else {
+                None
+            }
+        }).collect();
+        sections.sort();
+        sections
+    }
+
+    // Virtual address ranges given to --hex-dump as [start, end) pairs
+    pub fn get_hex_dump_ranges(&self) -> Vec<(u64, u64)> {
+        let mut ranges: Vec<(u64, u64)> = self.arguments.iter().filter_map(|arg| {
+            if let Argument::HexDumpRange(start, end) = arg {
+                Some((*start, *end))
+            } else {
+                None
+            }
+        }).collect();
+        ranges.sort();
+        ranges
+    }
+
     pub fn should_display_elf_header(&self) -> bool {
         self.arguments.contains(&Argument::ShowElfHeader) || self.are_all_sections_turned_off()
     }
@@ -59,8 +131,112 @@ impl Config {
         self.arguments.contains(&Argument::ShowSectionHeaders) || self.are_all_sections_turned_off()
     }
 
+    pub fn should_display_symbols(&self) -> bool {
+        self.arguments.contains(&Argument::ShowSymbols)
+    }
+
+    pub fn should_display_dynamic(&self) -> bool {
+        self.arguments.contains(&Argument::ShowDynamic)
+    }
+
+    pub fn should_display_relocations(&self) -> bool {
+        self.arguments.contains(&Argument::ShowRelocations)
+    }
+
+    pub fn should_display_notes(&self) -> bool {
+        self.arguments.contains(&Argument::ShowNotes)
+    }
+
+    pub fn should_display_version_info(&self) -> bool {
+        self.arguments.contains(&Argument::ShowVersionInfo)
+    }
+
+    pub fn should_display_hash_stats(&self) -> bool {
+        self.arguments.contains(&Argument::ShowHashStats)
+    }
+
+    pub fn should_display_section_groups(&self) -> bool {
+        self.arguments.contains(&Argument::ShowSectionGroups)
+    }
+
+    // Values from every --resolve occurrence, sorted so output does not depend on set ordering
+    pub fn get_resolve_values(&self) -> Vec<u64> {
+        let mut values: Vec<u64> = self.arguments.iter().flat_map(|arg| {
+            if let Argument::Resolve(values) = arg {
+                values.clone()
+            } else {
+                Vec::new()
+            }
+        }).collect();
+        values.sort();
+        values.dedup();
+        values
+    }
+
+    pub fn should_display_debug_info(&self) -> bool {
+        self.arguments.contains(&Argument::ShowDebugInfo)
+    }
+
+    pub fn should_display_addr2line(&self) -> bool {
+        self.arguments.iter().any(|arg| matches!(arg, Argument::Addr2Line(_)))
+    }
+
+    pub fn should_display_unwind(&self) -> bool {
+        self.arguments.contains(&Argument::ShowUnwind)
+    }
+
+    pub fn should_display_core(&self) -> bool {
+        self.arguments.contains(&Argument::ShowCore)
+    }
+
+    pub fn should_display_disassembly(&self) -> bool {
+        self.arguments.iter().any(|arg| matches!(arg, Argument::ShowDisassembly | Argument::DisassembleSymbol(_)))
+    }
+
+    // Symbols given to --disassemble, an empty list means every executable section
+    pub fn get_disassemble_symbols(&self) -> Vec<&String> {
+        let mut symbols: Vec<&String> = self.arguments.iter().filter_map(|arg| {
+            if let Argument::DisassembleSymbol(symbol) = arg {
+                Some(symbol)
+            } else {
+                None
+            }
+        }).collect();
+        symbols.sort();
+        symbols
+    }
+
+    pub fn should_display_init_arrays(&self) -> bool {
+        self.arguments.contains(&Argument::ShowInitArrays)
+    }
+
     fn are_all_sections_turned_off(&self) -> bool {
-        !self.arguments.contains(&Argument::ShowElfHeader) && !self.arguments.contains(&Argument::ShowProgramHeaders) && !self.arguments.contains(&Argument::ShowSectionHeaders)
+        !self.arguments.iter().any(Self::is_display_argument)
+    }
+
+    fn is_display_argument(argument: &Argument) -> bool {
+        matches!(argument,
+            Argument::ShowElfHeader |
+            Argument::ShowProgramHeaders |
+            Argument::ShowSectionHeaders |
+            Argument::ShowSymbols |
+            Argument::ShowDynamic |
+            Argument::ShowRelocations |
+            Argument::ShowNotes |
+            Argument::ShowVersionInfo |
+            Argument::ShowHashStats |
+            Argument::ShowSectionGroups |
+            Argument::ShowDebugInfo |
+            Argument::Addr2Line(_) |
+            Argument::Resolve(_) |
+            Argument::StringDump(_) |
+            Argument::HexDump(_) |
+            Argument::HexDumpRange(_, _) |
+            Argument::ShowDisassembly |
+            Argument::DisassembleSymbol(_) |
+            Argument::ShowUnwind |
+            Argument::ShowCore |
+            Argument::ShowInitArrays)
     }
 
     pub fn print_help(&self) {
@@ -71,9 +247,26 @@ impl Config {
         println!("    -e --elf-header\t\tDisplay ELF header");
         println!("    -p --program-headers\tDisplay program headers");
         println!("    -s --section-headers\tDisplay section headers");
+        println!("    -y --symbols\t\tDisplay symbol tables");
+        println!("    -d --dynamic\t\tDisplay dynamic section");
+        println!("    -r --relocs\t\t\tDisplay relocations");
+        println!("    -n --notes\t\t\tDisplay notes");
+        println!("    -V --version-info\t\tDisplay symbol versioning sections");
+        println!("    -I --hash-stats\t\tDisplay hash table bucket histograms");
+        println!("    -g --section-groups\t\tDisplay section groups");
+        println!("    --debug-info\t\tDisplay DWARF compilation units");
+        println!("    --addr2line <addr,...>\tDisplay source file, line and function of hex addresses");
+        println!("    --unwind\t\t\tDisplay .eh_frame CFA tables and check .eh_frame_hdr");
+        println!("    --core\t\t\tDisplay threads, registers and mapped files of core dumps");
+        println!("    --string-dump <name|index>\tDisplay the strings of a section");
+        println!("    --hex-dump <name|index>\tDisplay the bytes of a section in hex");
+        println!("    --hex-dump <0xstart-0xend>\tDisplay the bytes of a virtual address range, 0xstart+size also works");
+        println!("    --disassemble[=symbol]\tDisassemble executable sections or a single x86-64 function");
+        println!("    --init-arrays\t\tDisplay constructor and destructor arrays in execution order");
+        println!("    --resolve <value,...>\tDisplay segment, section and symbol of hex addresses or file offsets");
     }
 
-    fn process_argument(parameter: String) -> Result<Argument, String> {
+    fn process_argument(parameter: String, args: &mut Peekable<impl Iterator<Item = String>>) -> Result<Argument, String> {
         if !parameter.starts_with("-") {
             return Ok(Argument::InspectedBinary(parameter))
         }
@@ -94,6 +287,81 @@ impl Config {
             return Ok(Argument::ShowSectionHeaders)
         }
 
+        if parameter == "-y" || parameter == "--symbols" {
+            return Ok(Argument::ShowSymbols)
+        }
+
+        if parameter == "-d" || parameter == "--dynamic" {
+            return Ok(Argument::ShowDynamic)
+        }
+
+        if parameter == "-r" || parameter == "--relocs" {
+            return Ok(Argument::ShowRelocations)
+        }
+
+        if parameter == "-n" || parameter == "--notes" {
+            return Ok(Argument::ShowNotes)
+        }
+
+        if parameter == "-V" || parameter == "--version-info" {
+            return Ok(Argument::ShowVersionInfo)
+        }
+
+        if parameter == "-I" || parameter == "--hash-stats" {
+            return Ok(Argument::ShowHashStats)
+        }
+
+        if parameter == "-g" || parameter == "--section-groups" {
+            return Ok(Argument::ShowSectionGroups)
+        }
+
+        if parameter == "--debug-info" {
+            return Ok(Argument::ShowDebugInfo)
+        }
+
+        if parameter == "--addr2line" {
+            let value = args.next().ok_or(format!("Missing value for argument: {}", parameter))?;
+            return Ok(Argument::Addr2Line(parse_addresses(&value)?))
+        }
+
+        if parameter == "--unwind" {
+            return Ok(Argument::ShowUnwind)
+        }
+
+        if parameter == "--core" {
+            return Ok(Argument::ShowCore)
+        }
+
+        if parameter == "--string-dump" {
+            let value = args.next().ok_or(format!("Missing value for argument: {}", parameter))?;
+            return Ok(Argument::StringDump(value))
+        }
+
+        if parameter == "--hex-dump" {
+            let value = args.next().ok_or(format!("Missing value for argument: {}", parameter))?;
+            return match parse_address_range(&value)? {
+                Some((start, end)) => Ok(Argument::HexDumpRange(start, end)),
+                None => Ok(Argument::HexDump(value)),
+            }
+        }
+
+        if parameter == "--disassemble" {
+            return Ok(Argument::ShowDisassembly)
+        }
+
+        if let Some(symbol) = parameter.strip_prefix("--disassemble=") {
+            return Ok(Argument::DisassembleSymbol(symbol.to_string()))
+        }
+
+        if parameter == "--init-arrays" {
+            return Ok(Argument::ShowInitArrays)
+        }
+
+        if parameter == "--resolve" {
+            let value = args.next().ok_or(format!("Missing value for argument: {}", parameter))?;
+            return Ok(Argument::Resolve(parse_addresses(&value)?))
+        }
+
         if parameter == "-h" || parameter == "--help" {
             return Ok(Argument::ShowHelp)
         } 
@@ -101,7 +369,8 @@ impl Config {
         Err(format!("Unrecognized argument: {}", parameter))
     }
 
-    pub fn build (mut args: impl Iterator<Item = String>) -> Result<Config, String> {
+    pub fn build (args: impl Iterator<Item = String>) -> Result<Config, String> {
+        let mut args = args.peekable();
 
         let mut processed_arguments = HashSet::new();
 
@@ -115,7 +384,7 @@ impl Config {
 
             match argument {
                 Some(arg) => { 
-                    match Self::process_argument(arg) {
+                    match Self::process_argument(arg, &mut args) {
                         Ok(a) => processed_arguments.insert(a),
                         Err(error) => return Err(error),
                     };
@@ -135,6 +404,34 @@ impl Config {
 
 }
 
+fn parse_addresses(value: &str) -> Result<Vec<u64>, String> {
+    value.split(',').map(|address| {
+        let digits = address.trim_start_matches("0x").trim_start_matches("0X");
+        u64::from_str_radix(digits, 16).map_err(|_| format!("Invalid address: {}", address))
+    }).collect()
+}
+
+// Ranges are told apart from section names by a leading 0x and either an end address or a size
+fn parse_address_range(value: &str) -> Result<Option<(u64, u64)>, String> {
+    if !value.starts_with("0x") {
+        return Ok(None)
+    }
+
+    let (start, end, is_size) = match value.find(['-', '+']) {
+        Some(position) => (&value[..position], &value[position+1..], value.as_bytes()[position] == b'+'),
+        None => return Ok(None),
+    };
+
+    let start = parse_addresses(start)?[0];
+    let end = parse_addresses(end)?[0];
+    let end = if is_size { start.checked_add(end).ok_or(format!("Invalid address range: {}", value))? } else { end };
+
+    if end <= start {
+        return Err(format!("Invalid address range: {}", value))
+    }
+    Ok(Some((start, end)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,4 +523,255 @@ mod tests {
         assert!(!result.should_display_section_headers());
     }
 
+    #[test]
+    fn test_config_should_support_symbols_parameters() {
+        let params = [String::from("bin_name"), String::from("-y")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_symbols());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("--symbols")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_symbols());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_symbols());
+    }
+
+    #[test]
+    fn test_config_should_support_dynamic_parameters() {
+        let params = [String::from("bin_name"), String::from("-d")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_dynamic());
+        assert!(!result.should_display_section_headers());
+
+        let params = [String::from("bin_name"), String::from("--dynamic")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_dynamic());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_dynamic());
+    }
+
+    #[test]
+    fn test_config_should_support_relocations_parameters() {
+        let params = [String::from("bin_name"), String::from("-r")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_relocations());
+        assert!(!result.should_display_program_headers());
+
+        let params = [String::from("bin_name"), String::from("--relocs")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_relocations());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_relocations());
+    }
+
+    #[test]
+    fn test_config_should_support_notes_parameters() {
+        let params = [String::from("bin_name"), String::from("-n")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_notes());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("--notes")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_notes());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_notes());
+    }
+
+    #[test]
+    fn test_config_should_support_version_info_parameters() {
+        let params = [String::from("bin_name"), String::from("-V")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_version_info());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("--version-info")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_version_info());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_version_info());
+    }
+
+    #[test]
+    fn test_config_should_support_hash_stats_parameters() {
+        let params = [String::from("bin_name"), String::from("-I")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_hash_stats());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("--hash-stats")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_hash_stats());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_hash_stats());
+    }
+
+    #[test]
+    fn test_config_should_support_section_groups_parameters() {
+        let params = [String::from("bin_name"), String::from("-g")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_section_groups());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("--section-groups")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_section_groups());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_section_groups());
+    }
+
+    #[test]
+    fn test_config_should_support_debug_info_parameters() {
+        let params = [String::from("bin_name"), String::from("--debug-info")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_debug_info());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_debug_info());
+    }
+
+    #[test]
+    fn test_config_should_support_addr2line_parameters() {
+        let params = [String::from("bin_name"), String::from("--addr2line"), String::from("0x1139,40")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_addr2line());
+        assert!(!result.should_display_elf_header());
+        assert_eq!(result.get_addr2line_addresses(), vec![0x40, 0x1139]);
+
+        let params = [String::from("bin_name"), String::from("--addr2line")];
+        assert!(Config::build(params.into_iter()).is_err());
+
+        let params = [String::from("bin_name"), String::from("--addr2line"), String::from("main")];
+        assert!(Config::build(params.into_iter()).is_err());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_addr2line());
+    }
+
+    #[test]
+    fn test_config_should_support_unwind_parameters() {
+        let params = [String::from("bin_name"), String::from("--unwind")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_unwind());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_unwind());
+    }
+
+    #[test]
+    fn test_config_should_support_core_parameters() {
+        let params = [String::from("bin_name"), String::from("--core")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_core());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_core());
+    }
+
+    #[test]
+    fn test_config_should_support_string_dump_parameters() {
+        let params = [String::from("bin_name"), String::from("--string-dump"), String::from(".rodata"),
+            String::from("--string-dump"), String::from(".comment")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert_eq!(result.get_string_dump_sections(), vec![".comment", ".rodata"]);
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("--string-dump")];
+        assert!(Config::build(params.into_iter()).is_err());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.get_string_dump_sections().is_empty());
+    }
+
+    #[test]
+    fn test_config_should_support_hex_dump_parameters() {
+        let params = [String::from("bin_name"), String::from("--hex-dump"), String::from(".text"),
+            String::from("--hex-dump"), String::from("0x1000-0x1040"), String::from("--hex-dump"), String::from("0x2000+10")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert_eq!(result.get_hex_dump_sections(), vec![".text"]);
+        assert_eq!(result.get_hex_dump_ranges(), vec![(0x1000, 0x1040), (0x2000, 0x2010)]);
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("--hex-dump"), String::from("0x1040-0x1000")];
+        assert!(Config::build(params.into_iter()).is_err());
+
+        let params = [String::from("bin_name"), String::from("--hex-dump")];
+        assert!(Config::build(params.into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_config_should_support_disassemble_parameters() {
+        let params = [String::from("bin_name"), String::from("--disassemble")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_disassembly());
+        assert!(result.get_disassemble_symbols().is_empty());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("--disassemble=main"), String::from("--disassemble=_start")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_disassembly());
+        assert_eq!(result.get_disassemble_symbols(), vec!["_start", "main"]);
+
+        let params = [String::from("bin_name"), String::from("--disassemble=main"), String::from("binary")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert_eq!(result.get_disassemble_symbols(), vec!["main"]);
+        assert_eq!(result.get_inspected_binary_name(), "binary");
+
+        // The argument following a bare --disassemble is never taken as a symbol
+        let params = [String::from("bin_name"), String::from("--disassemble"), String::from("main")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_disassembly());
+        assert!(result.get_disassemble_symbols().is_empty());
+        assert_eq!(result.get_inspected_binary_name(), "main");
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_disassembly());
+    }
+
+    #[test]
+    fn test_config_should_support_init_arrays_parameters() {
+        let params = [String::from("bin_name"), String::from("--init-arrays")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(result.should_display_init_arrays());
+        assert!(!result.should_display_elf_header());
+
+        let params = [String::from("bin_name"), String::from("")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_init_arrays());
+    }
+
+    #[test]
+    fn test_config_should_support_resolve_parameters() {
+        let params = [String::from("bin_name"), String::from("--resolve"), String::from("0x1139,40"), String::from("--resolve"), String::from("0x40")];
+        let result = Config::build(params.into_iter()).expect("Should yield proper result config");
+        assert!(!result.should_display_elf_header());
+        assert_eq!(result.get_resolve_values(), vec![0x40, 0x1139]);
+
+        let params = [String::from("bin_name"), String::from("--resolve"), String::from("main")];
+        assert!(Config::build(params.into_iter()).is_err());
+    }
 }
@@ -13,6 +13,12 @@ pub const EI_NIDENT: usize = 16;
 // Padding size is a result of whole identifier minus padding offset
 pub const IDENT_PADDING_BYTES: usize = EI_NIDENT - EI_PAD;
 
+pub const VERSION_START: usize = 0x14;
+pub const VERSION_END: usize = VERSION_START + 0x4;
+
+pub const ENTRY32_START: usize = 0x18;
+pub const ENTRY32_END: usize = ENTRY32_START + 0x4;
+
 pub const PHOFF32_START: usize = 0x1C;
 pub const PHOFF32_END: usize = PHOFF32_START + 0x4;
 
@@ -40,6 +46,9 @@ pub const SHENTNUM32_END: usize = SHENTNUM32_START + 0x2;
 pub const SHSTRNDX32_START: usize = 0x32;
 pub const SHSTRNDX32_END: usize = SHSTRNDX32_START + 0x2;
 
+pub const ENTRY64_START: usize = 0x18;
+pub const ENTRY64_END: usize = ENTRY64_START + 0x8;
+
 pub const PHOFF64_START: usize = 0x20;
 pub const PHOFF64_END: usize = PHOFF64_START + 0x8;
 
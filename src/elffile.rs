@@ -0,0 +1,267 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::File;
+use std::ops::Deref;
+
+use memmap2::Mmap;
+
+use crate::structs::elfheader::ElfHeader;
+use crate::structs::programheader::{ProgramHeader, ProgramHeaderEntry, ProgramHeaderInfo};
+use crate::structs::sectionheader::{SectionHeader, SectionHeaderEntry, SectionHeaderInfo};
+use crate::structs::sectionheadertype::SectionHeaderType;
+
+pub(crate) const PROGRAM_HEADER32_SIZE: u64 = 0x20;
+pub(crate) const PROGRAM_HEADER64_SIZE: u64 = 0x38;
+pub(crate) const SECTION_HEADER32_SIZE: u64 = 0x28;
+pub(crate) const SECTION_HEADER64_SIZE: u64 = 0x40;
+
+// Bytes of a file on disk, memory mapped so that parsing large files does not copy them.
+// Files that cannot be mapped, like pipes, are read into memory instead
+pub enum FileData {
+    Mapped(Mmap),
+    Read(Vec<u8>),
+}
+
+impl FileData {
+    pub fn open(path: &str) -> Result<FileData, String> {
+        let file = File::open(path).map_err(|error| error.to_string())?;
+        match FileData::map(&file) {
+            Some(mapping) => Ok(mapping),
+            None => std::fs::read(path).map(FileData::Read).map_err(|error| error.to_string()),
+        }
+    }
+
+    // None when the file cannot be mapped, the caller decides whether to read or stream it
+    pub fn map(file: &File) -> Option<FileData> {
+        // SAFETY: the mapping is read-only, a file truncated by another process while mapped is outside what we guard against
+        unsafe { Mmap::map(file) }.ok().map(FileData::Mapped)
+    }
+}
+
+impl Deref for FileData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileData::Mapped(mapping) => mapping,
+            FileData::Read(bytes) => bytes,
+        }
+    }
+}
+
+// Streamed input the lazily decoded tables fall back on, for the few bytes outside the tables they need
+pub trait ReadAt {
+    fn size(&self) -> u64;
+    fn read_at(&self, offset: u64, length: u64) -> Result<Vec<u8>, String>;
+}
+
+// Where the bytes a table points at come from: the file in memory, or a stream read on demand
+#[derive(Clone, Copy)]
+pub enum Source<'data> {
+    Memory(&'data [u8]),
+    Stream(&'data dyn ReadAt),
+}
+
+impl<'data> Source<'data> {
+    pub fn size(&self) -> u64 {
+        match self {
+            Source::Memory(data) => data.len() as u64,
+            Source::Stream(stream) => stream.size(),
+        }
+    }
+
+    // Fetches [offset, offset + length), borrowed from memory or copied out of the stream
+    pub fn read_at(&self, offset: u64, length: u64) -> Result<Cow<'data, [u8]>, String> {
+        match self {
+            Source::Memory(data) => offset.checked_add(length)
+                .and_then(|end| data.get(usize::try_from(offset).ok()?..usize::try_from(end).ok()?))
+                .map(Cow::Borrowed)
+                .ok_or_else(|| outside_file(offset, length, self.size())),
+            Source::Stream(stream) => stream.read_at(offset, length).map(Cow::Owned),
+        }
+    }
+}
+
+impl fmt::Debug for Source<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Memory(data) => write!(f, "Memory({} bytes)", data.len()),
+            Source::Stream(stream) => write!(f, "Stream({} bytes)", stream.size()),
+        }
+    }
+}
+
+pub(crate) fn outside_file(offset: u64, length: u64, file_size: u64) -> String {
+    format!("Range 0x{:x}-0x{:x} lies outside the file (size 0x{:x})", offset, offset.wrapping_add(length), file_size)
+}
+
+// ELF file viewed in place: only the ELF header is decoded up front, table entries are decoded when asked for
+// and section names and data are handed out as slices of the input
+#[derive(Debug)]
+pub struct ElfFile<'data> {
+    data: &'data [u8],
+    header: ElfHeader,
+    program_header: ProgramHeader<'data>,
+    section_header: SectionHeader<'data>,
+}
+
+impl<'data> ElfFile<'data> {
+    pub fn build(data: &'data [u8]) -> Result<ElfFile<'data>, String> {
+        match ElfHeader::build(data) {
+            Ok(header) => ElfFile::from_header(data, header),
+            Err(error) => Err(format!("Failed parsing elf header due to \"{}\"", error)),
+        }
+    }
+
+    // For callers that already decoded the ELF header, only the table bounds are left to check
+    pub fn from_header(data: &'data [u8], header: ElfHeader) -> Result<ElfFile<'data>, String> {
+        let program_header_info = ProgramHeaderInfo {
+            offset: header.program_header_offset(),
+            entries: header.program_header_entries(),
+            size: header.program_header_size()};
+        let program_header = ProgramHeader::build(data, &program_header_info, header.machine(), header.is32_bit(), header.is_little_endian())
+            .map_err(|error| format!("Failed parsing program header due to \"{}\"", error))?;
+
+        let section_header_info = SectionHeaderInfo {
+            offset: header.section_header_offset(),
+            entries: header.section_header_entries(),
+            size: header.section_header_size(),
+            names_index: header.section_names_index()};
+        let section_header = SectionHeader::build(data, &section_header_info, header.machine(), header.is32_bit(), header.is_little_endian())
+            .map_err(|error| format!("Failed parsing section header due to \"{}\"", error))?;
+
+        Ok(ElfFile { data, header, program_header, section_header })
+    }
+
+    pub fn header(&self) -> &ElfHeader {
+        &self.header
+    }
+
+    pub fn data(&self) -> &'data [u8] {
+        self.data
+    }
+
+    pub fn program_header(&self) -> &ProgramHeader<'data> {
+        &self.program_header
+    }
+
+    pub fn section_header(&self) -> &SectionHeader<'data> {
+        &self.section_header
+    }
+
+    pub fn segment(&self, index: usize) -> Option<ProgramHeaderEntry> {
+        self.program_header.get(index)
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = ProgramHeaderEntry> + 'data {
+        self.program_header.entries()
+    }
+
+    // Bytes the segment occupies in the file, which can be fewer than it occupies in memory
+    pub fn segment_data(&self, segment: &ProgramHeaderEntry) -> Result<&'data [u8], &'static str> {
+        let start = segment.offset().to_u64()? as usize;
+        let end = start.checked_add(segment.file_size().to_u64()? as usize).ok_or("Segment size overflows")?;
+        self.data.get(start..end).ok_or("Segment lies outside the file")
+    }
+
+    pub fn section(&self, index: usize) -> Option<Section<'data>> {
+        let header = self.section_header.get(index)?;
+        Some(Section { index, header, data: self.data })
+    }
+
+    pub fn sections(&self) -> impl Iterator<Item = Section<'data>> + '_ {
+        (0..self.section_header.len()).filter_map(|index| self.section(index))
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<Section<'data>> {
+        self.section_header.index_by_name(name).and_then(|index| self.section(index))
+    }
+
+    // Decompressed contents of a section of this file, empty for NOBITS sections
+    pub fn section_data(&self, section: &SectionHeaderEntry) -> Result<Cow<'data, [u8]>, &'static str> {
+        section.contents(self.data)
+    }
+}
+
+// Section decoded from an ElfFile, its name and data borrow the file instead of copying it.
+// The remaining header fields are available through SectionHeaderEntry
+#[derive(Debug)]
+pub struct Section<'data> {
+    index: usize,
+    header: SectionHeaderEntry<'data>,
+    data: &'data [u8],
+}
+
+impl<'data> Section<'data> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    // Bytes as stored in the file, still compressed for compressed sections and empty for NOBITS ones
+    pub fn data(&self) -> Result<&'data [u8], &'static str> {
+        match self.header.section_type() {
+            SectionHeaderType::ShtNobits => Ok(&[]),
+            _ => self.header.raw_contents(self.data),
+        }
+    }
+
+    // Bytes as seen by consumers, only compressed sections are copied to decompress them
+    pub fn contents(&self) -> Result<Cow<'data, [u8]>, &'static str> {
+        self.header.contents(self.data)
+    }
+}
+
+impl<'data> Deref for Section<'data> {
+    type Target = SectionHeaderEntry<'data>;
+
+    fn deref(&self) -> &SectionHeaderEntry<'data> {
+        &self.header
+    }
+}
+
+// Entries are decoded on demand, so the whole table has to be checked once up front
+pub(crate) fn check_table(file_size: u64, offset: u64, entry_size: u16, minimum_entry_size: u64, entries: u32) -> Result<(), &'static str> {
+    if entries == 0 {
+        return Ok(());
+    }
+    if (entry_size as u64) < minimum_entry_size {
+        return Err("Table entries are too small");
+    }
+    match (entry_size as u64).checked_mul(entries as u64).and_then(|size| size.checked_add(offset)) {
+        Some(end) if end <= file_size => Ok(()),
+        _ => Err("Table lies outside the file"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::segmenttype::SegmentType;
+
+    #[test]
+    fn build_should_borrow_names_and_data_from_input() {
+        let payload = FileData::open(std::env::current_exe().unwrap().to_str().unwrap()).unwrap();
+        let file = ElfFile::build(&payload).expect("Test binary should parse");
+
+        assert!(file.segments().any(|segment| matches!(segment.segment_type(), SegmentType::PtLoad)));
+        let text = file.section_by_name(".text").expect("Test binary should have code");
+        assert!(text.is_executable());
+        let data = text.data().unwrap();
+        assert_eq!(data.as_ptr(), payload[text.offset().to_u64().unwrap() as usize..].as_ptr());
+        assert_eq!(file.sections().filter(|section| section.name() == ".text").count(), 1);
+        assert_eq!(file.section_data(&text).unwrap().len() as u64, text.size().to_u64().unwrap());
+    }
+
+    #[test]
+    fn build_should_reject_truncated_files() {
+        assert!(ElfFile::build(&[0x7F, 0x45, 0x4C]).is_err());
+    }
+
+    #[test]
+    fn check_table_should_reject_tables_outside_the_file() {
+        assert!(check_table(0x100, 0x40, 0x38, PROGRAM_HEADER64_SIZE, 3).is_ok());
+        assert!(check_table(0x100, 0x40, 0x38, PROGRAM_HEADER64_SIZE, 4).is_err());
+        assert!(check_table(0x100, 0x40, 0x10, PROGRAM_HEADER64_SIZE, 1).is_err());
+        assert!(check_table(0x100, u64::MAX, 0x38, PROGRAM_HEADER64_SIZE, 0).is_ok());
+    }
+}
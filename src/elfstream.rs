@@ -0,0 +1,155 @@
+use std::cell::RefCell;
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::elffile::{check_table, outside_file, ReadAt, Source, PROGRAM_HEADER32_SIZE, PROGRAM_HEADER64_SIZE, SECTION_HEADER32_SIZE, SECTION_HEADER64_SIZE};
+use crate::structs::elfheader::ElfHeader;
+use crate::structs::programheader::{ProgramHeader, ProgramHeaderEntry};
+use crate::structs::sectionheader::{SectionHeader, SectionHeaderEntry};
+use crate::structs::sectionheadertype::SectionHeaderType;
+
+// ELF file read from any seekable source: the ELF header, both tables and the section names are fetched once,
+// section and segment bytes only when asked for, so files larger than memory can be inspected
+#[derive(Debug)]
+pub struct ElfStream<R: Read + Seek> {
+    source: RefCell<R>,
+    size: u64,
+    header: ElfHeader,
+    program_table: Vec<u8>,
+    section_table: Vec<u8>,
+    section_names: Vec<u8>,
+}
+
+impl<R: Read + Seek> ElfStream<R> {
+    pub fn build(mut source: R) -> Result<ElfStream<R>, String> {
+        let size = source.seek(SeekFrom::End(0)).map_err(|error| error.to_string())?;
+        let source = RefCell::new(source);
+        let read_at = |offset: u64, length: u64| read_exact_at(&mut *source.borrow_mut(), size, offset, length);
+
+        let header_bytes = read_at(0, size.min(0x40))?;
+        let header = match ElfHeader::build_with_first_section(&header_bytes, |offset, length| read_at(offset, length as u64).ok()) {
+            Ok(value) => value,
+            Err(error) => return Err(format!("Failed parsing elf header due to \"{}\"", error)),
+        };
+
+        let (program_entry_size, section_entry_size) = match header.is32_bit() {
+            true => (PROGRAM_HEADER32_SIZE, SECTION_HEADER32_SIZE),
+            false => (PROGRAM_HEADER64_SIZE, SECTION_HEADER64_SIZE),
+        };
+
+        let program_table = read_table(read_at, size, header.program_header_offset().to_u64()?, header.program_header_size(), program_entry_size, header.program_header_entries())
+            .map_err(|error| format!("Failed parsing program header due to \"{}\"", error))?;
+        let section_table = read_table(read_at, size, header.section_header_offset().to_u64()?, header.section_header_size(), section_entry_size, header.section_header_entries())
+            .map_err(|error| format!("Failed parsing section header due to \"{}\"", error))?;
+
+        let mut stream = ElfStream { source, size, header, program_table, section_table, section_names: Vec::new() };
+        let section_names = match stream.section_header().get(stream.header.section_names_index() as usize) {
+            Some(section) if matches!(section.section_type(), SectionHeaderType::ShtStrtab) => {
+                stream.read_at(section.offset().to_u64()?, section.size().to_u64()?)
+                    .map_err(|error| format!("Failed parsing section names due to \"{}\"", error))?
+            },
+            _ => Vec::new(),
+        };
+        stream.section_names = section_names;
+        Ok(stream)
+    }
+
+    pub fn header(&self) -> &ElfHeader {
+        &self.header
+    }
+
+    pub fn program_header(&self) -> ProgramHeader<'_> {
+        ProgramHeader::from_table(&self.program_table, self.header.program_header_size(), Source::Stream(self),
+            self.header.machine(), self.header.is32_bit(), self.header.is_little_endian())
+    }
+
+    pub fn section_header(&self) -> SectionHeader<'_> {
+        SectionHeader::from_table(&self.section_table, self.header.section_header_size(), &self.section_names, Source::Stream(self),
+            self.header.machine(), self.header.is32_bit(), self.header.is_little_endian())
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    // Fetches [offset, offset + length) from the source, failing unless all of it lies inside the file
+    pub fn read_at(&self, offset: u64, length: u64) -> Result<Vec<u8>, String> {
+        read_exact_at(&mut *self.source.borrow_mut(), self.size, offset, length)
+    }
+
+    // Bytes the segment occupies in the file, which can be fewer than it occupies in memory
+    pub fn segment_data(&self, segment: &ProgramHeaderEntry) -> Result<Vec<u8>, String> {
+        self.read_at(segment.offset().to_u64()?, segment.file_size().to_u64()?)
+    }
+
+    // Section bytes as seen by consumers, decompressing SHF_COMPRESSED and .zdebug sections
+    pub fn section_contents(&self, section: &SectionHeaderEntry) -> Result<Vec<u8>, String> {
+        if matches!(section.section_type(), SectionHeaderType::ShtNobits) {
+            return Ok(Vec::new());
+        }
+
+        let data = self.read_at(section.offset().to_u64()?, section.size().to_u64()?)?;
+        match section.compression() {
+            Some(Ok(header)) => Ok(header.decompress(&data)?),
+            Some(Err(error)) => Err(error.to_string()),
+            None => Ok(data),
+        }
+    }
+}
+
+impl<R: Read + Seek> ReadAt for ElfStream<R> {
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn read_at(&self, offset: u64, length: u64) -> Result<Vec<u8>, String> {
+        ElfStream::read_at(self, offset, length)
+    }
+}
+
+fn read_exact_at<R: Read + Seek>(source: &mut R, file_size: u64, offset: u64, length: u64) -> Result<Vec<u8>, String> {
+    if offset.checked_add(length).is_none_or(|end| end > file_size) {
+        return Err(outside_file(offset, length, file_size));
+    }
+
+    let mut buffer = vec![0u8; length as usize];
+    source.seek(SeekFrom::Start(offset)).map_err(|error| error.to_string())?;
+    source.read_exact(&mut buffer).map_err(|error| error.to_string())?;
+    Ok(buffer)
+}
+
+fn read_table(read_at: impl Fn(u64, u64) -> Result<Vec<u8>, String>, file_size: u64, offset: u64, entry_size: u16, minimum_entry_size: u64, entries: u32) -> Result<Vec<u8>, String> {
+    if entries == 0 {
+        return Ok(Vec::new());
+    }
+    check_table(file_size, offset, entry_size, minimum_entry_size, entries)?;
+    read_at(offset, entry_size as u64 * entries as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn build_should_match_parsing_from_memory() {
+        let payload = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let elf = crate::parse(&payload).expect("Test binary should parse");
+        let stream = ElfStream::build(Cursor::new(&payload)).expect("Test binary should stream");
+
+        assert_eq!(stream.size(), payload.len() as u64);
+        assert_eq!(stream.program_header().len(), elf.program_header().len());
+        let names: Vec<String> = stream.section_header().entries().map(|section| section.name().to_string()).collect();
+        assert_eq!(names, elf.sections().map(|section| section.name().to_string()).collect::<Vec<_>>());
+
+        let text = stream.section_header().entries().find(|section| section.name() == ".text").unwrap();
+        assert_eq!(stream.section_contents(&text).unwrap(), elf.section_by_name(".text").unwrap().contents().unwrap().as_ref());
+    }
+
+    #[test]
+    fn read_at_should_reject_ranges_outside_the_file() {
+        let mut source = Cursor::new(vec![1u8, 2, 3, 4]);
+        assert_eq!(read_exact_at(&mut source, 4, 1, 3), Ok(vec![2, 3, 4]));
+        assert!(read_exact_at(&mut source, 4, 2, 3).is_err());
+        assert!(read_exact_at(&mut source, 4, u64::MAX, 2).is_err());
+    }
+}
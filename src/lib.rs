@@ -1,21 +1,72 @@
-use std::fs;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::collections::hash_map::Entry;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
 
 pub mod config;
 pub mod termcolors; 
 mod bits;
 mod consts;
-mod types;
-mod structs;
+pub mod types;
+pub mod structs;
+pub mod elffile;
+pub mod elfstream;
+
+pub use elffile::{ElfFile, FileData};
+pub use elfstream::ElfStream;
 
 use structs::elfheader::ElfHeader;
+use elffile::Source;
 use config::Config;
 
-use crate::structs::{programheader::{ProgramHeader, ProgramHeaderInfo}, sectionheader::{SectionHeader, SectionHeaderInfo}};
+use crate::structs::{programheader::ProgramHeader, sectionheader::{SectionHeader, SectionHeaderEntry}};
+use crate::structs::sectionheadertype::SectionHeaderType;
+use crate::structs::symbols::{SymbolEntry, SymbolTable};
+use crate::structs::symboltype::SymbolType;
+use crate::structs::dynamic::DynamicSection;
+use crate::structs::segmenttype::SegmentType;
+use crate::structs::segmentmapping::SegmentMapping;
+use crate::structs::resolution::Resolution;
+use crate::structs::relocation::RelocationTable;
+use crate::structs::notes::NoteSection;
+use crate::structs::hash::HashTable;
+use crate::structs::groups::SectionGroup;
+use crate::structs::dwarf::{DebugInfo, DwarfSections};
+use crate::structs::debugline::{LineProgram, SourceLocation};
+use crate::structs::ehframe::{EhFrame, EhFrameHdr};
+use crate::structs::coredump::CoreDump;
+use crate::structs::hexdump::print_hex_dump;
+use crate::structs::disassembly::Disassembly;
+use crate::structs::initarray::{self, InitArray};
+use crate::structs::type_::Type;
+use crate::structs::machine::Machine;
+use crate::structs::versions::{VersionDefinitionSection, VersionNeedSection, VersionSymbolSection};
+
+// Parsed ELF file for library users, the headers are decoded lazily from the borrowed bytes without printing anything
+pub type Elf<'data> = ElfFile<'data>;
+
+// Library entry point: checks the headers of an ELF image, a memory mapped FileData works as well as a Vec
+pub fn parse(payload: &[u8]) -> Result<Elf<'_>, String> {
+    ElfFile::build(payload)
+}
+
+// Files that cannot be mapped and are larger than this are streamed rather than read into memory:
+// only the headers, tables and the sections a view needs are read
+const STREAMING_THRESHOLD: u64 = 4 << 30;
 
 pub fn analyze(config: &Config) -> Result<(), String> {
-    let payload = match fs::read(config.get_inspected_binary_name()) {
-        Ok(bytes) => bytes,
-        Err(error) => return Err(error.to_string()),
+    let mut file = File::open(config.get_inspected_binary_name()).map_err(|error| error.to_string())?;
+    let payload = match FileData::map(&file) {
+        Some(mapping) => mapping,
+        None if file.metadata().map_err(|error| error.to_string())?.len() > STREAMING_THRESHOLD => {
+            return analyze_streamed(config, BufReader::new(file), STREAMING_THRESHOLD);
+        },
+        None => {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes).map_err(|error| error.to_string())?;
+            FileData::Read(bytes)
+        },
     };
 
     let elf_header = match ElfHeader::build(&payload) {
@@ -31,42 +82,859 @@ pub fn analyze(config: &Config) -> Result<(), String> {
     let is_32bit = elf_header.is32_bit();
     let is_little_endian = elf_header.is_little_endian();
 
-    let program_header_info= ProgramHeaderInfo {
-        offset: elf_header.program_header_offset(),
-        entries: elf_header.program_header_entries(),
-        size: elf_header.program_header_size()};
-        
-    let program_header = match ProgramHeader::build(&payload, &program_header_info, is_32bit, is_little_endian) {
-        Ok(value) => value,
-        Err(error) => return Err(format!("Failed parsing program header due to \"{}\"", error)),
-    };
+    let file = ElfFile::from_header(&payload, elf_header)?;
+    let source = Source::Memory(&payload);
+    let elf_header = file.header();
+    let program_header = file.program_header();
 
     if config.should_display_program_headers() {
         program_header.print();
         println!("");
     }
     
-    let section_header_info = SectionHeaderInfo {
-        offset: elf_header.section_header_offset(),
-        entries: elf_header.section_header_entries(),
-        size: elf_header.section_header_size(),
-        names_index: elf_header.section_names_index()};
+    let section_header = file.section_header();
 
-    let section_header = match SectionHeader::build(&payload, &section_header_info, is_32bit, is_little_endian) {
-        Ok(value) => value,
-        Err(error) => return Err(format!("Failed parsing section header due to \"{}\"", error)),
-    };
+    if config.should_display_program_headers() && !program_header.is_empty() && !section_header.is_empty() {
+        SegmentMapping::build(program_header, section_header).print();
+        println!();
+    }
+
+    if config.should_display_section_headers() {
+        section_header.print();
+    }
+
+    if config.should_display_symbols() {
+        print_symbols(source, section_header, is_32bit, is_little_endian)?;
+    }
+
+    if config.should_display_dynamic() {
+        print_dynamic(source, program_header, section_header, is_32bit, is_little_endian)?;
+    }
+
+    if config.should_display_relocations() {
+        let mut relocation_sections = section_header.entries()
+            .filter(|s| matches!(s.section_type(), SectionHeaderType::ShtRel | SectionHeaderType::ShtRela))
+            .peekable();
+
+        if relocation_sections.peek().is_none() {
+            println!("There are no relocations in this file.");
+            println!();
+        }
+
+        let mut symbol_tables = SymbolTables::new();
+        for section in relocation_sections {
+            let symbol_table = build_linked_symbol_table(source, section_header, &section, &mut symbol_tables, is_32bit, is_little_endian)?;
+            let relocation_table = match RelocationTable::build(&payload, &section, symbol_table, elf_header.machine(), is_32bit, is_little_endian) {
+                Ok(value) => value,
+                Err(error) => return Err(format!("Failed parsing relocation table due to \"{}\"", error)),
+            };
+            relocation_table.print();
+            println!();
+        }
+    }
+
+    if config.should_display_notes() {
+        print_notes(source, elf_header, program_header, section_header)?;
+    }
+
+    if config.should_display_version_info() {
+        print_version_info(source, section_header, is_little_endian)?;
+    }
+
+    if config.should_display_hash_stats() {
+        let mut hash_sections = section_header.entries()
+            .filter(|s| matches!(s.section_type(), SectionHeaderType::ShtHash | SectionHeaderType::ShtGnuHash))
+            .peekable();
+
+        if hash_sections.peek().is_none() {
+            println!("There are no hash tables in this file.");
+            println!();
+        }
+
+        for section in hash_sections {
+            let hash_table = build_hash_table(&payload, section_header, &section, is_32bit, is_little_endian)?;
+            hash_table.print_histogram();
+            println!();
+        }
+    }
+
+    if config.should_display_section_groups() {
+        let group_indices: Vec<usize> = section_header.entries().enumerate()
+            .filter(|(_, section)| matches!(section.section_type(), SectionHeaderType::ShtGroup))
+            .map(|(index, _)| index)
+            .collect();
+
+        if group_indices.is_empty() {
+            println!("There are no section groups in this file.");
+            println!();
+        }
+
+        let mut symbol_tables = SymbolTables::new();
+        for index in group_indices {
+            let section = section_header.get(index).unwrap();
+            let symbol_table = build_linked_symbol_table(source, section_header, &section, &mut symbol_tables, is_32bit, is_little_endian)?;
+            let section_group = match SectionGroup::build(&payload, index, section_header, symbol_table, is_little_endian) {
+                Ok(value) => value,
+                Err(error) => return Err(format!("Failed parsing section group due to \"{}\"", error)),
+            };
+            section_group.print();
+            println!();
+        }
+    }
+
+    if config.should_display_debug_info() {
+        let dwarf_sections = build_dwarf_sections(&payload, section_header, elf_header.machine(), is_32bit, is_little_endian)?;
+
+        if dwarf_sections.debug_info.is_empty() {
+            println!("There is no .debug_info section in this file.");
+        } else {
+            let debug_info = match DebugInfo::build(&dwarf_sections, is_little_endian) {
+                Ok(value) => value,
+                Err(error) => return Err(format!("Failed parsing .debug_info due to \"{}\"", error)),
+            };
+            debug_info.print();
+        }
+        println!();
+    }
+
+    if config.should_display_addr2line() {
+        let dwarf_sections = build_dwarf_sections(&payload, section_header, elf_header.machine(), is_32bit, is_little_endian)?;
+
+        if dwarf_sections.debug_info.is_empty() {
+            println!("There is no .debug_info section in this file.");
+        } else {
+            let debug_info = match DebugInfo::build(&dwarf_sections, is_little_endian) {
+                Ok(value) => value,
+                Err(error) => return Err(format!("Failed parsing .debug_info due to \"{}\"", error)),
+            };
+
+            let mut line_programs: Vec<LineProgram> = Vec::new();
+            for unit in debug_info.units().iter() {
+                if let Some(offset) = unit.stmt_list() {
+                    match LineProgram::build(&dwarf_sections, offset, unit.comp_dir(), unit.name(), is_little_endian) {
+                        Ok(value) => line_programs.push(value),
+                        Err(error) => return Err(format!("Failed parsing .debug_line due to \"{}\"", error)),
+                    };
+                }
+            }
+
+            for address in config.get_addr2line_addresses() {
+                SourceLocation::build(address, &debug_info, &line_programs).print();
+            }
+        }
+        println!();
+    }
+
+    if config.should_display_unwind() {
+        let find_section = |name: &str| section_header.index_by_name(name);
+
+        match find_section(".eh_frame") {
+            None => println!("There is no .eh_frame section in this file."),
+            Some(index) => {
+                let address = section_header.get(index).unwrap().address().to_u64()?;
+                let contents = relocated_section_contents(&payload, section_header, index, &mut SymbolTables::new(), elf_header.machine(), is_32bit, is_little_endian)?;
+                let eh_frame = match EhFrame::build(&contents, address, elf_header.machine(), is_32bit, is_little_endian) {
+                    Ok(value) => value,
+                    Err(error) => return Err(format!("Failed parsing .eh_frame due to \"{}\"", error)),
+                };
+                eh_frame.print();
+
+                if let Some(hdr_index) = find_section(".eh_frame_hdr") {
+                    let hdr_section = section_header.get(hdr_index).unwrap();
+                    let contents = match hdr_section.contents(&payload) {
+                        Ok(value) => value,
+                        Err(error) => return Err(format!("Failed reading .eh_frame_hdr due to \"{}\"", error)),
+                    };
+                    let eh_frame_hdr = match EhFrameHdr::build(&contents, hdr_section.address().to_u64()?, is_32bit, is_little_endian) {
+                        Ok(value) => value,
+                        Err(error) => return Err(format!("Failed parsing .eh_frame_hdr due to \"{}\"", error)),
+                    };
+                    println!();
+                    eh_frame_hdr.print(&eh_frame_hdr.verify(&eh_frame));
+                }
+            },
+        }
+        println!();
+    }
+
+    if config.should_display_core() {
+        print_core(source, elf_header, program_header)?;
+    }
+
+    for name in config.get_string_dump_sections() {
+        match find_section_index(section_header, name) {
+            None => println!("Section '{}' was not dumped because it does not exist.", name),
+            Some(index) => {
+                let section = section_header.get(index).unwrap();
+                section.print_strings(&payload)
+                    .map_err(|error| format!("Failed dumping section {} due to \"{}\"", section.name(), error))?;
+            },
+        }
+        println!();
+    }
+
+    for name in config.get_hex_dump_sections() {
+        match find_section_index(section_header, name) {
+            None => println!("Section '{}' was not dumped because it does not exist.", name),
+            Some(index) => {
+                let section = section_header.get(index).unwrap();
+                let contents = section.contents(&payload)
+                    .map_err(|error| format!("Failed dumping section {} due to \"{}\"", section.name(), error))?;
+                // Allocated sections are shown at their virtual addresses, everything else at offsets within the section
+                let address = if section.is_allocated() { section.address().to_u64().unwrap() } else { 0 };
+                if contents.is_empty() {
+                    println!("Section '{}' has no data to dump.", section.name());
+                } else {
+                    print_hex_dump(&format!("section '{}'", section.name()), address, &contents);
+                }
+            },
+        }
+        println!();
+    }
+
+    for (start, end) in config.get_hex_dump_ranges() {
+        match program_header.virtual_range_to_offset(start, end) {
+            Some(offset) if offset.checked_add(end - start).is_some_and(|range_end| range_end <= payload.len() as u64) => {
+                print_hex_dump(&format!("range 0x{:x}-0x{:x}", start, end), start, &payload[offset as usize..(offset + end - start) as usize]);
+            },
+            _ => println!("Range 0x{:x}-0x{:x} was not dumped because no loadable segment maps all of it from the file.", start, end),
+        }
+        println!();
+    }
+
+    if config.should_display_disassembly() {
+        if !matches!(elf_header.machine(), Machine::EMX8664) {
+            println!("Disassembly is only supported for x86-64 files.");
+            println!();
+        } else {
+            let symbol_table = build_address_symbol_table(source, section_header, is_32bit, is_little_endian)?;
+            let plt_labels = build_plt_labels(&payload, section_header, elf_header.machine(), is_32bit, is_little_endian)?;
+            let names = config.get_disassemble_symbols();
+            let mut found = Vec::new();
+
+            // Grouped by section once, objects built with -ffunction-sections have as many sections as functions
+            let defined_symbols: Vec<&SymbolEntry> = symbol_table.iter().flat_map(|table| table.entries())
+                .filter(|symbol| symbol.defined_section().is_some() && !symbol.name().is_empty())
+                .collect();
+            let mut section_symbols: HashMap<usize, Vec<&SymbolEntry>> = HashMap::new();
+            for symbol in defined_symbols.iter() {
+                section_symbols.entry(symbol.defined_section().unwrap()).or_default().push(symbol);
+            }
+            let is_relocatable = matches!(elf_header.file_type(), Type::EtRel);
+            let file_labels = match is_relocatable {
+                true => BTreeMap::new(),
+                false => {
+                    let mut labels = symbol_labels(&defined_symbols);
+                    labels.extend(plt_labels.iter().cloned());
+                    labels
+                },
+            };
+
+            for (index, section) in section_header.entries().enumerate() {
+                if !section.is_executable() || matches!(section.section_type(), SectionHeaderType::ShtNobits) {
+                    continue;
+                }
+                let own_symbols = section_symbols.get(&index).map(Vec::as_slice).unwrap_or_default();
+                if !names.is_empty() && !own_symbols.iter().any(|symbol| names.contains(&symbol.name())) {
+                    continue;
+                }
+                let contents = section.contents(&payload)
+                    .map_err(|error| format!("Failed reading section {} due to \"{}\"", section.name(), error))?;
+                let address = section.address().to_u64().unwrap();
+                let section_end = address + contents.len() as u64;
+
+                // Sections of relocatable files all start at zero, so only their own symbols can label them
+                let labels = match is_relocatable {
+                    true => Cow::Owned(symbol_labels(own_symbols)),
+                    false => Cow::Borrowed(&file_labels),
+                };
+
+                let mut ranges = Vec::new();
+                if names.is_empty() {
+                    ranges.push((address, section_end));
+                }
+                for symbol in own_symbols {
+                    if !names.contains(&symbol.name()) {
+                        continue;
+                    }
+                    let start = symbol.value().to_u64().unwrap();
+                    let end = match symbol.size().to_u64().unwrap() {
+                        0 => labels.range(start + 1..).next().map(|(label, _)| *label).unwrap_or(section_end),
+                        size => start + size,
+                    };
+                    let range = (start, end.min(section_end));
+                    if !ranges.contains(&range) {
+                        ranges.push(range);
+                    }
+                    found.push(symbol.name().clone());
+                }
+
+                for range in ranges {
+                    Disassembly::build(section.name(), address, &contents, range, &labels).print();
+                    println!();
+                }
+            }
+
+            for name in names.iter().filter(|name| !found.contains(name)) {
+                println!("Symbol '{}' was not disassembled because it does not exist.", name);
+                println!();
+            }
+        }
+    }
+
+    if config.should_display_init_arrays() {
+        let arrays = build_init_arrays(&payload, section_header, elf_header.machine(), matches!(elf_header.file_type(), Type::EtRel), is_32bit, is_little_endian)?;
+        if arrays.is_empty() {
+            println!("There are no initialization or termination arrays in this file.");
+        }
+        for array in arrays.iter() {
+            array.print();
+        }
+        println!();
+    }
+
+    let resolve_values = config.get_resolve_values();
+    if !resolve_values.is_empty() {
+        let symbol_table = build_address_symbol_table(source, section_header, is_32bit, is_little_endian)?;
+        for value in resolve_values {
+            Resolution::build(value, program_header, section_header, symbol_table.as_ref(), matches!(elf_header.file_type(), Type::EtRel)).print();
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+// Views for files too large to read that cannot be mapped either: every read goes through the stream,
+// so views that need whole groups of sections or relocated contents are skipped
+fn analyze_streamed<R: Read + Seek>(config: &Config, reader: R, threshold: u64) -> Result<(), String> {
+    let stream = ElfStream::build(reader)?;
+    let source = Source::Stream(&stream);
+    let elf_header = stream.header();
+    let program_header = stream.program_header();
+    let section_header = stream.section_header();
+    let is_32bit = elf_header.is32_bit();
+    let is_little_endian = elf_header.is_little_endian();
+
+    let skipped: Vec<&str> = [
+        (config.should_display_relocations(), "relocations"),
+        (config.should_display_hash_stats(), "hash table statistics"),
+        (config.should_display_section_groups(), "section groups"),
+        (config.should_display_debug_info(), "debug info"),
+        (config.should_display_addr2line(), "addr2line"),
+        (config.should_display_unwind(), "unwind info"),
+        (config.should_display_disassembly(), "disassembly"),
+        (config.should_display_init_arrays(), "init arrays"),
+    ].into_iter().filter_map(|(requested, view)| requested.then_some(view)).collect();
+    if !skipped.is_empty() {
+        println!("{}File cannot be mapped and is larger than {} bytes, so it is streamed, skipping: {}{}", termcolors::yellow(), threshold, skipped.join(", "), termcolors::default());
+        println!();
+    }
+
+    if config.should_display_elf_header() {
+        elf_header.print();
+        println!();
+    }
+
+    if config.should_display_program_headers() {
+        program_header.print();
+        println!();
+        if !program_header.is_empty() && !section_header.is_empty() {
+            SegmentMapping::build(&program_header, &section_header).print();
+            println!();
+        }
+    }
 
     if config.should_display_section_headers() {
         section_header.print();
     }
 
+    if config.should_display_symbols() {
+        print_symbols(source, &section_header, is_32bit, is_little_endian)?;
+    }
+
+    if config.should_display_dynamic() {
+        print_dynamic(source, &program_header, &section_header, is_32bit, is_little_endian)?;
+    }
+
+    if config.should_display_notes() {
+        print_notes(source, elf_header, &program_header, &section_header)?;
+    }
+
+    if config.should_display_version_info() {
+        print_version_info(source, &section_header, is_little_endian)?;
+    }
+
+    if config.should_display_core() {
+        print_core(source, elf_header, &program_header)?;
+    }
+
+    for name in config.get_string_dump_sections() {
+        match find_section_index(&section_header, name) {
+            None => println!("Section '{}' was not dumped because it does not exist.", name),
+            Some(index) => {
+                let section = section_header.get(index).unwrap();
+                let contents = stream.section_contents(&section)
+                    .map_err(|error| format!("Failed dumping section {} due to \"{}\"", section.name(), error))?;
+                section.print_strings_from(&contents);
+            },
+        }
+        println!();
+    }
+
+    for name in config.get_hex_dump_sections() {
+        match find_section_index(&section_header, name) {
+            None => println!("Section '{}' was not dumped because it does not exist.", name),
+            Some(index) => {
+                let section = section_header.get(index).unwrap();
+                let contents = stream.section_contents(&section)
+                    .map_err(|error| format!("Failed dumping section {} due to \"{}\"", section.name(), error))?;
+                let address = if section.is_allocated() { section.address().to_u64().unwrap() } else { 0 };
+                if contents.is_empty() {
+                    println!("Section '{}' has no data to dump.", section.name());
+                } else {
+                    print_hex_dump(&format!("section '{}'", section.name()), address, &contents);
+                }
+            },
+        }
+        println!();
+    }
+
+    for (start, end) in config.get_hex_dump_ranges() {
+        match program_header.virtual_range_to_offset(start, end).and_then(|offset| stream.read_at(offset, end - start).ok()) {
+            Some(data) => print_hex_dump(&format!("range 0x{:x}-0x{:x}", start, end), start, &data),
+            None => println!("Range 0x{:x}-0x{:x} was not dumped because no loadable segment maps all of it from the file.", start, end),
+        }
+        println!();
+    }
+
+    let resolve_values = config.get_resolve_values();
+    if !resolve_values.is_empty() {
+        let symbol_table = build_address_symbol_table(source, &section_header, is_32bit, is_little_endian)?;
+        for value in resolve_values {
+            Resolution::build(value, &program_header, &section_header, symbol_table.as_ref(), matches!(elf_header.file_type(), Type::EtRel)).print();
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+// Views shared with analyze_streamed, every section or segment they need is read through source
+fn print_symbols(source: Source, section_header: &SectionHeader, is_32bit: bool, is_little_endian: bool) -> Result<(), String> {
+    let symbol_sections = section_header.entries().enumerate()
+        .filter(|(_, s)| matches!(s.section_type(), SectionHeaderType::ShtSymtab | SectionHeaderType::ShtDynsym));
+    for (index, _) in symbol_sections {
+        let symbol_table = build_symbol_table(source, section_header, index, is_32bit, is_little_endian)?;
+        symbol_table.print();
+        println!();
+    }
     Ok(())
 }
 
+fn print_dynamic(source: Source, program_header: &ProgramHeader, section_header: &SectionHeader, is_32bit: bool, is_little_endian: bool) -> Result<(), String> {
+    // Prefer PT_DYNAMIC so the section can be found even when section headers were stripped
+    let dynamic_location = program_header.entries()
+        .find(|segment| matches!(segment.segment_type(), SegmentType::PtDynamic))
+        .map(|segment| (segment.offset().to_u64().unwrap(), segment.file_size().to_u64().unwrap()))
+        .or_else(|| section_header.entries()
+            .find(|section| matches!(section.section_type(), SectionHeaderType::ShtDynamic))
+            .map(|section| (section.offset().to_u64().unwrap(), section.size().to_u64().unwrap())));
+
+    match dynamic_location {
+        Some((offset, size)) => {
+            let dynamic_section = match DynamicSection::build(source, offset, size, program_header, is_32bit, is_little_endian) {
+                Ok(value) => value,
+                Err(error) => return Err(format!("Failed parsing dynamic section due to \"{}\"", error)),
+            };
+            dynamic_section.print();
+        },
+        None => println!("There is no dynamic section in this file."),
+    }
+    println!();
+    Ok(())
+}
+
+fn print_notes(source: Source, elf_header: &ElfHeader, program_header: &ProgramHeader, section_header: &SectionHeader) -> Result<(), String> {
+    let note_locations = find_note_locations(program_header, section_header);
+    if note_locations.is_empty() {
+        println!("There are no notes in this file.");
+        println!();
+    }
+
+    for (name, offset, size, alignment) in note_locations {
+        let note_section = match NoteSection::build(source, name, offset, size, alignment, elf_header.is_little_endian()) {
+            Ok(value) => value,
+            Err(error) => return Err(format!("Failed parsing notes due to \"{}\"", error)),
+        };
+        note_section.print(elf_header.machine(), elf_header.is32_bit(), elf_header.is_little_endian());
+        println!();
+    }
+    Ok(())
+}
+
+fn print_version_info(source: Source, section_header: &SectionHeader, is_little_endian: bool) -> Result<(), String> {
+    let (versym, definitions, needs) = build_version_sections(source, section_header, is_little_endian)?;
+
+    if versym.is_none() && definitions.is_none() && needs.is_none() {
+        println!("No version information found in this file.");
+        println!();
+    }
+
+    if let Some(versym) = &versym {
+        versym.print(definitions.as_ref(), needs.as_ref());
+        println!();
+    }
+
+    if let Some(definitions) = &definitions {
+        definitions.print();
+        println!();
+    }
+
+    if let Some(needs) = &needs {
+        needs.print();
+        println!();
+    }
+    Ok(())
+}
+
+fn print_core(source: Source, elf_header: &ElfHeader, program_header: &ProgramHeader) -> Result<(), String> {
+    if !matches!(elf_header.file_type(), Type::EtCore) {
+        println!("This is not a core file.");
+    } else {
+        // Core files have no section headers, their notes live in PT_NOTE segments
+        let mut note_sections: Vec<NoteSection> = Vec::new();
+        for segment in program_header.entries().filter(|segment| matches!(segment.segment_type(), SegmentType::PtNote)) {
+            let offset = segment.offset().to_u64()?;
+            let size = segment.file_size().to_u64()?;
+            match NoteSection::build(source, String::new(), offset, size, segment.alignment().to_u64()?, elf_header.is_little_endian()) {
+                Ok(value) => note_sections.push(value),
+                Err(error) => return Err(format!("Failed parsing notes due to \"{}\"", error)),
+            };
+        }
+
+        let notes: Vec<_> = note_sections.iter().flat_map(|section| section.entries().iter()).collect();
+        let core_dump = match CoreDump::build(&notes, program_header, elf_header.machine(), elf_header.is32_bit(), elf_header.is_little_endian()) {
+            Ok(value) => value,
+            Err(error) => return Err(format!("Failed parsing core notes due to \"{}\"", error)),
+        };
+        core_dump.print();
+    }
+    println!();
+    Ok(())
+}
+
+// Where the notes are, falling back to PT_NOTE segments when there are no section headers to name them.
+// Each location is a name, file offset, size and alignment
+fn find_note_locations(program_header: &ProgramHeader, section_header: &SectionHeader) -> Vec<(String, u64, u64, u64)> {
+    let note_locations: Vec<(String, u64, u64, u64)> = section_header.entries()
+        .filter(|section| matches!(section.section_type(), SectionHeaderType::ShtNote))
+        .map(|section| (section.name().to_string(), section.offset().to_u64().unwrap(), section.size().to_u64().unwrap(), section.alignment().to_u64().unwrap()))
+        .collect();
+    if !note_locations.is_empty() {
+        return note_locations;
+    }
+
+    program_header.entries()
+        .filter(|segment| matches!(segment.segment_type(), SegmentType::PtNote))
+        .map(|segment| {
+            let offset = segment.offset().to_u64().unwrap();
+            let size = segment.file_size().to_u64().unwrap();
+            (format!("segment at offset 0x{:x} of length 0x{:x}", offset, size), offset, size, segment.alignment().to_u64().unwrap())
+        })
+        .collect()
+}
+
+// Functions win over other symbols at the same address, otherwise the first symbol in the table does
+fn symbol_labels(symbols: &[&SymbolEntry]) -> BTreeMap<u64, String> {
+    let mut labels = BTreeMap::new();
+    for symbol in symbols.iter().filter(|symbol| !matches!(symbol.symbol_type(), SymbolType::SttSection | SymbolType::SttFile)) {
+        let address = symbol.value().to_u64().unwrap();
+        if matches!(symbol.symbol_type(), SymbolType::SttFunc) || !labels.contains_key(&address) {
+            labels.insert(address, symbol.name().clone());
+        }
+    }
+    labels
+}
+
+// The full symbol table names local functions too, stripped files only keep the dynamic one
+fn build_address_symbol_table(source: Source, section_header: &SectionHeader, is_32bit: bool, is_little_endian: bool) -> Result<Option<SymbolTable>, String> {
+    section_header.entries()
+        .position(|section| matches!(section.section_type(), SectionHeaderType::ShtSymtab))
+        .or_else(|| section_header.entries().position(|section| matches!(section.section_type(), SectionHeaderType::ShtDynsym)))
+        .map(|index| build_symbol_table(source, section_header, index, is_32bit, is_little_endian))
+        .transpose()
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DynamicSymbol {
+    pub index: usize,
+    pub value: u64,
+    pub size: u64,
+}
+
+// Resolves a defined dynamic symbol through the hash tables, preferring the GNU one like ld.so does
+pub fn lookup_dynamic_symbol(payload: &[u8], name: &str) -> Result<Option<DynamicSymbol>, String> {
+    let file = ElfFile::build(payload)?;
+    let is_32bit = file.header().is32_bit();
+    let is_little_endian = file.header().is_little_endian();
+    let section_header = file.section_header();
+
+    let hash_section = section_header.entries()
+        .find(|section| matches!(section.section_type(), SectionHeaderType::ShtGnuHash))
+        .or_else(|| section_header.entries().find(|section| matches!(section.section_type(), SectionHeaderType::ShtHash)))
+        .ok_or("There are no hash tables in this file")?;
+
+    let symbols = build_symbol_table(Source::Memory(payload), section_header, hash_section.link() as usize, is_32bit, is_little_endian)?;
+    let hash_table = build_hash_table(payload, section_header, &hash_section, is_32bit, is_little_endian)?;
+
+    Ok(hash_table.lookup(name, &symbols).map(|index| {
+        let symbol = &symbols.entries()[index];
+        DynamicSymbol{index, value: symbol.value().to_u64().unwrap(), size: symbol.size().to_u64().unwrap()}
+    }))
+}
+
+// Returns the contents of the named section, decompressed when it is SHF_COMPRESSED or a legacy .zdebug section
+pub fn section_contents(payload: &[u8], name: &str) -> Result<Vec<u8>, String> {
+    let file = ElfFile::build(payload)?;
+    let section = file.section_by_name(name)
+        .ok_or(format!("There is no section named {} in this file", name))?;
+
+    match section.contents() {
+        Ok(contents) => Ok(contents.into_owned()),
+        Err(error) => Err(format!("Failed reading section {} due to \"{}\"", name, error)),
+    }
+}
+
+// Arrays in the order the dynamic linker walks them, with relocations applied so PIE and shared objects show real targets
+fn build_init_arrays(payload: &[u8], section_header: &SectionHeader, machine: Machine, is_relocatable: bool, is_32bit: bool, is_little_endian: bool) -> Result<Vec<InitArray>, String> {
+    let symbol_table = build_address_symbol_table(Source::Memory(payload), section_header, is_32bit, is_little_endian)?;
+
+    let mut arrays = Vec::new();
+    let mut symbol_tables = SymbolTables::new();
+    for (index, section) in section_header.entries().enumerate() {
+        if !matches!(section.section_type(), SectionHeaderType::ShtInitArray | SectionHeaderType::ShtFiniArray | SectionHeaderType::ShtPreinitArray) {
+            continue;
+        }
+
+        let contents = section.contents(payload)
+            .map_err(|error| format!("Failed reading section {} due to \"{}\"", section.name(), error))?;
+        let mut array = InitArray::build(&section, &contents, is_32bit, is_little_endian)
+            .map_err(|error| format!("Failed parsing section {} due to \"{}\"", section.name(), error))?;
+
+        // Relocatable files carry the section's own relocations, linked files patch it through dynamic ones
+        let relocation_sections = section_header.entries()
+            .filter(|s| matches!(s.section_type(), SectionHeaderType::ShtRel | SectionHeaderType::ShtRela))
+            .filter(|s| !is_relocatable || s.info() as usize == index);
+        for relocation_section in relocation_sections {
+            let symbols = build_linked_symbol_table(Source::Memory(payload), section_header, &relocation_section, &mut symbol_tables, is_32bit, is_little_endian)?;
+            let relocation_table = match RelocationTable::build(payload, &relocation_section, symbols, machine, is_32bit, is_little_endian) {
+                Ok(value) => value,
+                Err(error) => return Err(format!("Failed parsing relocations due to \"{}\"", error)),
+            };
+            let base = if is_relocatable { 0 } else { section.address().to_u64().unwrap() };
+            array.apply_relocations(&relocation_table, symbols, base);
+        }
+
+        if let Some(symbol_table) = &symbol_table {
+            array.resolve_symbols(symbol_table);
+        }
+        arrays.push(array);
+    }
+
+    initarray::sort_by_execution_order(&mut arrays);
+    Ok(arrays)
+}
+
+// objdump style name@plt labels: jump slot relocations are in the same order as the PLT entries, which
+// start after the reserved first entry in .plt unless IBT moved them into .plt.sec
+fn build_plt_labels(payload: &[u8], section_header: &SectionHeader, machine: Machine, is_32bit: bool, is_little_endian: bool) -> Result<Vec<(u64, String)>, String> {
+    // Only the x86 layouts are known: a lazy .plt starts with one header entry, .plt.sec of IBT enabled files has none
+    if !matches!(machine, Machine::EMX8664 | Machine::EM386) {
+        return Ok(Vec::new());
+    }
+
+    let find = |name: &str| section_header.index_by_name(name).and_then(|index| section_header.get(index));
+    let relocation_section = match find(".rela.plt").or_else(|| find(".rel.plt")) {
+        Some(section) => section,
+        None => return Ok(Vec::new()),
+    };
+    let (plt, first_entry) = match (find(".plt.sec"), find(".plt")) {
+        (Some(plt), _) => (plt, 0),
+        (None, Some(plt)) => (plt, 1),
+        _ => return Ok(Vec::new()),
+    };
+    let entry_size = match plt.entry_size().to_u64().unwrap() {
+        0 => 16,
+        size => size,
+    };
+
+    let mut symbol_tables = SymbolTables::new();
+    let symbol_table = build_linked_symbol_table(Source::Memory(payload), section_header, &relocation_section, &mut symbol_tables, is_32bit, is_little_endian)?;
+    let relocation_table = match RelocationTable::build(payload, &relocation_section, symbol_table, machine, is_32bit, is_little_endian) {
+        Ok(value) => value,
+        Err(error) => return Err(format!("Failed parsing relocations due to \"{}\"", error)),
+    };
+
+    // A PLT too small for one entry per relocation has a layout we do not know, better no labels than wrong ones
+    let entries = relocation_table.entries().len() as u64 + first_entry;
+    if entries.checked_mul(entry_size).is_none_or(|size| size > plt.size().to_u64().unwrap()) {
+        return Ok(Vec::new());
+    }
+
+    Ok(relocation_table.entries().iter().enumerate().filter_map(|(index, entry)| {
+        let name = entry.symbol_name()?.split('@').next()?;
+        Some((plt.address().to_u64().unwrap() + entry_size * (index as u64 + first_entry), format!("{}@plt", name)))
+    }).collect())
+}
+
+// Sections are selected either by index or by name, as in readelf -p
+fn find_section_index(section_header: &SectionHeader, name_or_index: &str) -> Option<usize> {
+    match name_or_index.parse::<usize>() {
+        Ok(index) => (index < section_header.len()).then_some(index),
+        Err(_) => section_header.index_by_name(name_or_index),
+    }
+}
+
+fn build_dwarf_sections(payload: &[u8], section_header: &SectionHeader, machine: Machine, is_32bit: bool, is_little_endian: bool) -> Result<DwarfSections, String> {
+    let mut symbol_tables = SymbolTables::new();
+    let mut load = |name: &str| -> Result<Vec<u8>, String> {
+        // Legacy compressed sections replace the leading ".debug" with ".zdebug"
+        let legacy_name = name.replacen(".debug", ".zdebug", 1);
+        match section_header.index_by_name(name).or_else(|| section_header.index_by_name(&legacy_name)) {
+            Some(index) => relocated_section_contents(payload, section_header, index, &mut symbol_tables, machine, is_32bit, is_little_endian),
+            None => Ok(Vec::new()),
+        }
+    };
+
+    Ok(DwarfSections{
+        debug_info: load(".debug_info")?,
+        debug_abbrev: load(".debug_abbrev")?,
+        debug_str: load(".debug_str")?,
+        debug_str_offsets: load(".debug_str_offsets")?,
+        debug_line_str: load(".debug_line_str")?,
+        debug_line: load(".debug_line")?,
+        debug_addr: load(".debug_addr")?,
+        debug_ranges: load(".debug_ranges")?,
+        debug_rnglists: load(".debug_rnglists")?})
+}
+
+// Section contents with the relocations of relocatable objects applied, e.g. string offsets in .debug_info
+fn relocated_section_contents(payload: &[u8], section_header: &SectionHeader, index: usize, symbol_tables: &mut SymbolTables, machine: Machine, is_32bit: bool, is_little_endian: bool) -> Result<Vec<u8>, String> {
+    let section = section_header.get(index).ok_or("Section index out of range")?;
+    let mut contents = match section.contents(payload) {
+        Ok(value) => value.into_owned(),
+        Err(error) => return Err(format!("Failed reading section {} due to \"{}\"", section.name(), error)),
+    };
+
+    let relocation_sections = section_header.entries()
+        .filter(|s| matches!(s.section_type(), SectionHeaderType::ShtRel | SectionHeaderType::ShtRela) && s.info() as usize == index);
+
+    for relocation_section in relocation_sections {
+        let symbol_table = build_linked_symbol_table(Source::Memory(payload), section_header, &relocation_section, symbol_tables, is_32bit, is_little_endian)?;
+        let relocation_table = match RelocationTable::build(payload, &relocation_section, symbol_table, machine, is_32bit, is_little_endian) {
+            Ok(value) => value,
+            Err(error) => return Err(format!("Failed parsing relocation table due to \"{}\"", error)),
+        };
+        relocation_table.apply(&mut contents, machine, is_little_endian)
+            .and_then(|_| relocation_table.apply_pc_relative(&mut contents, section.address().to_u64()?, machine, is_little_endian))
+            .map_err(|error| format!("Failed applying relocations to {} due to \"{}\"", section.name(), error))?;
+    }
+
+    Ok(contents)
+}
+
+fn build_hash_table(payload: &[u8], section_header: &SectionHeader, section: &SectionHeaderEntry, is_32bit: bool, is_little_endian: bool) -> Result<HashTable, String> {
+    // GNU hash chains cover the dynamic symbols from symoffset up to the end of the linked table
+    let symbols_section = section_header.get(section.link() as usize).ok_or("Hash table links to nonexistent symbol table")?;
+    let symbol_count = match symbols_section.entry_size().to_u64().unwrap() {
+        0 => 0,
+        entry_size => symbols_section.size().to_u64().unwrap() / entry_size,
+    };
+
+    match HashTable::build(payload, section, symbol_count as usize, is_32bit, is_little_endian) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(format!("Failed parsing hash table due to \"{}\"", error)),
+    }
+}
+
+type VersionSections = (Option<VersionSymbolSection>, Option<VersionDefinitionSection>, Option<VersionNeedSection>);
+
+fn build_version_sections(source: Source, section_header: &SectionHeader, is_little_endian: bool) -> Result<VersionSections, String> {
+    let mut versym = None;
+    let mut definitions = None;
+    let mut needs = None;
+
+    for section in section_header.entries() {
+        let string_table = || section_header.get(section.link() as usize).ok_or("Version section links to nonexistent string table");
+        match section.section_type() {
+            SectionHeaderType::ShtGnuVersym => versym = Some(VersionSymbolSection::build(source, &section, is_little_endian)),
+            SectionHeaderType::ShtGnuVerdef => definitions = Some(VersionDefinitionSection::build(source, &section, &string_table()?, is_little_endian)),
+            SectionHeaderType::ShtGnuVerneed => needs = Some(VersionNeedSection::build(source, &section, &string_table()?, is_little_endian)),
+            _ => (),
+        }
+    }
+
+    let failed = |error| format!("Failed parsing version section due to \"{}\"", error);
+    Ok((versym.transpose().map_err(failed)?, definitions.transpose().map_err(failed)?, needs.transpose().map_err(failed)?))
+}
+
+// Symbol tables by section index, objects built with -ffunction-sections have thousands of relocation sections sharing one
+type SymbolTables = HashMap<usize, Option<SymbolTable>>;
+
+fn build_linked_symbol_table<'a>(source: Source, section_header: &SectionHeader, section: &SectionHeaderEntry, symbol_tables: &'a mut SymbolTables, is_32bit: bool, is_little_endian: bool) -> Result<Option<&'a SymbolTable>, String> {
+    let index = section.link() as usize;
+    let entry = match symbol_tables.entry(index) {
+        Entry::Occupied(entry) => return Ok(entry.into_mut().as_ref()),
+        Entry::Vacant(entry) => entry,
+    };
+
+    let symbol_table = match section_header.get(index) {
+        Some(linked) if matches!(linked.section_type(), SectionHeaderType::ShtSymtab | SectionHeaderType::ShtDynsym) => {
+            let mut symbol_table = build_symbol_table(source, section_header, index, is_32bit, is_little_endian)?;
+            symbol_table.name_section_symbols(section_header);
+            Some(symbol_table)
+        },
+        _ => None,
+    };
+    Ok(entry.insert(symbol_table).as_ref())
+}
+
+fn build_symbol_table(source: Source, section_header: &SectionHeader, index: usize, is_32bit: bool, is_little_endian: bool) -> Result<SymbolTable, String> {
+    let section = section_header.get(index).ok_or("Symbol table index out of range")?;
+    let string_table = section_header.get(section.link() as usize).ok_or("Symbol table links to nonexistent string table")?;
+    let mut symbol_table = match SymbolTable::build(source, &section, &string_table, is_32bit, is_little_endian) {
+        Ok(value) => value,
+        Err(error) => return Err(format!("Failed parsing symbol table due to \"{}\"", error)),
+    };
+
+    let extended_indices = section_header.entries()
+        .find(|entry| matches!(entry.section_type(), SectionHeaderType::ShtSymtabShndx) && entry.link() as usize == index);
+
+    if let Some(extended_indices) = extended_indices {
+        symbol_table.apply_extended_indices(source, &extended_indices, is_little_endian)
+            .map_err(|error| format!("Failed parsing extended section indices due to \"{}\"", error))?;
+    }
+
+    // Only the dynamic symbol table is covered by .gnu.version
+    if !matches!(section.section_type(), SectionHeaderType::ShtDynsym) {
+        return Ok(symbol_table);
+    }
+
+    if let (Some(versym), definitions, needs) = build_version_sections(source, section_header, is_little_endian)? {
+        symbol_table.apply_versions(&versym, definitions.as_ref(), needs.as_ref());
+    }
+
+    Ok(symbol_table)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::structs::programheader::ProgramHeaderInfo;
+    use crate::structs::sectionheader::SectionHeaderInfo;
+    use std::fs;
+    use std::io::Cursor;
 
     #[test]
     fn test_elf_header_build_too_short() {
@@ -79,7 +947,7 @@ mod tests {
     fn test_program_header_build_too_short() {
         let payload = vec![0x7F, 0x45, 0x4C];
         let info = ProgramHeaderInfo {offset: structs::word::Word::Bits64(0x0), entries: 10, size: 500};
-        let header = ProgramHeader::build(&payload, &info, false, false);
+        let header = ProgramHeader::build(&payload, &info, Machine::EMX8664, false, false);
         assert!(header.is_err());
     }
 
@@ -87,7 +955,25 @@ mod tests {
     fn test_section_header_build_too_short() {
         let payload = vec![0x7F, 0x45, 0x4C];
         let info = SectionHeaderInfo {offset: structs::word::Word::Bits64(0x0), entries: 10, size: 500, names_index: 10};
-        let header = SectionHeader::build(&payload, &info, false, false);
+        let header = SectionHeader::build(&payload, &info, Machine::EMX8664, false, false);
         assert!(header.is_err());
     }
+
+    #[test]
+    fn analyze_streamed_should_read_symbols_dynamic_and_notes() {
+        let path = std::env::current_exe().unwrap();
+        let payload = fs::read(&path).unwrap();
+        let args = ["bin_name", path.to_str().unwrap(), "-n", "-y", "-d", "--core"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        assert_eq!(analyze_streamed(&config, Cursor::new(&payload), 0x1000), Ok(()));
+
+        let stream = ElfStream::build(Cursor::new(&payload)).unwrap();
+        let file = ElfFile::build(&payload).unwrap();
+        let (is_32bit, is_little_endian) = (file.header().is32_bit(), file.header().is_little_endian());
+        let index = file.sections().find(|section| matches!(section.section_type(), SectionHeaderType::ShtSymtab)).unwrap().index();
+        let streamed = build_symbol_table(Source::Stream(&stream), &stream.section_header(), index, is_32bit, is_little_endian).unwrap();
+        let mapped = build_symbol_table(Source::Memory(&payload), file.section_header(), index, is_32bit, is_little_endian).unwrap();
+        let names = |table: &SymbolTable| table.entries().iter().map(|symbol| symbol.name().clone()).collect::<Vec<_>>();
+        assert_eq!(names(&streamed), names(&mapped));
+    }
 }
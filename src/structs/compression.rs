@@ -0,0 +1,193 @@
+use std::fmt;
+use std::io::Read;
+
+use crate::bits::*;
+
+pub const ELFCOMPRESS_ZLIB: u32 = 1;
+pub const ELFCOMPRESS_ZSTD: u32 = 2;
+
+const CHDR32_SIZE: usize = 0x0C;
+const CHDR64_SIZE: usize = 0x18;
+const ZDEBUG_MAGIC: &[u8; 4] = b"ZLIB";
+const ZDEBUG_HEADER_SIZE: usize = 0x0C;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompressionType {
+    Zlib,
+    Zstd,
+    GnuZlib,
+    Unknown(u32),
+}
+
+impl fmt::Display for CompressionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompressionType::Zlib       => write!(f, "ZLIB"),
+            CompressionType::Zstd       => write!(f, "ZSTD"),
+            CompressionType::GnuZlib    => write!(f, "ZLIB (legacy .zdebug)"),
+            CompressionType::Unknown(value) => write!(f, "<unknown: 0x{:x}>", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressionHeader {
+    ch_type: CompressionType,
+    ch_size: u64,
+    ch_addralign: u64,
+    header_size: usize,
+}
+
+impl CompressionHeader {
+    // Elf32_Chdr / Elf64_Chdr found at the start of SHF_COMPRESSED sections
+    pub fn build(data: &[u8], is_32bit: bool, is_little_endian: bool) -> Result<CompressionHeader, &'static str> {
+        let header_size = if is_32bit { CHDR32_SIZE } else { CHDR64_SIZE };
+        if data.len() < header_size {
+            return Err("Compression header too short");
+        }
+
+        let ch_type = match to_u32_from_slice(&data[0x00..0x04].try_into().unwrap(), is_little_endian) {
+            ELFCOMPRESS_ZLIB => CompressionType::Zlib,
+            ELFCOMPRESS_ZSTD => CompressionType::Zstd,
+            value => CompressionType::Unknown(value),
+        };
+
+        let (ch_size, ch_addralign) = if is_32bit {
+            (to_u32_from_slice(&data[0x04..0x08].try_into().unwrap(), is_little_endian) as u64,
+             to_u32_from_slice(&data[0x08..0x0C].try_into().unwrap(), is_little_endian) as u64)
+        } else {
+            (to_u64_from_slice(&data[0x08..0x10].try_into().unwrap(), is_little_endian),
+             to_u64_from_slice(&data[0x10..0x18].try_into().unwrap(), is_little_endian))
+        };
+
+        Ok(CompressionHeader{ch_type, ch_size, ch_addralign, header_size})
+    }
+
+    // Legacy .zdebug sections start with "ZLIB" followed by the big endian uncompressed size
+    pub fn build_gnu(data: &[u8]) -> Result<CompressionHeader, &'static str> {
+        if data.len() < ZDEBUG_HEADER_SIZE || &data[0x00..0x04] != ZDEBUG_MAGIC {
+            return Err("Missing ZLIB header in .zdebug section");
+        }
+
+        Ok(CompressionHeader{
+            ch_type: CompressionType::GnuZlib,
+            ch_size: to_u64_from_slice(&data[0x04..0x0C].try_into().unwrap(), false),
+            ch_addralign: 1,
+            header_size: ZDEBUG_HEADER_SIZE})
+    }
+
+    pub fn compression_type(&self) -> CompressionType {
+        self.ch_type
+    }
+
+    pub fn uncompressed_size(&self) -> u64 {
+        self.ch_size
+    }
+
+    pub fn uncompressed_alignment(&self) -> u64 {
+        self.ch_addralign
+    }
+
+    // Takes the whole section, header included, and returns exactly ch_size bytes
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let stream = data.get(self.header_size..).ok_or("Compressed section too short")?;
+        let size = usize::try_from(self.ch_size).map_err(|_| "Uncompressed section too large")?;
+
+        let result = match self.ch_type {
+            CompressionType::Zlib | CompressionType::GnuZlib =>
+                miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(stream, size)
+                    .map_err(|_| "Invalid zlib stream")?,
+            CompressionType::Zstd => {
+                let mut source = stream;
+                let decoder = ruzstd::decoding::StreamingDecoder::new(&mut source)
+                    .map_err(|_| "Invalid zstd frame")?;
+                // ch_size comes from the file, so it only bounds the output instead of sizing an allocation.
+                // One byte past it is enough to tell a stream that is too long
+                let mut result = Vec::new();
+                decoder.take((size as u64).saturating_add(1)).read_to_end(&mut result).map_err(|_| "Invalid zstd stream")?;
+                result
+            },
+            CompressionType::Unknown(_) => return Err("Unsupported compression type"),
+        };
+
+        if result.len() != size {
+            return Err("Decompressed size does not match compression header");
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chdr64(ch_type: u32, ch_size: u64) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(ch_type.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.extend(ch_size.to_le_bytes());
+        data.extend(1u64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn compression_header_should_decompress_zlib() {
+        let original = b"debug info debug info debug info".to_vec();
+        let mut data = chdr64(ELFCOMPRESS_ZLIB, original.len() as u64);
+        data.extend(miniz_oxide::deflate::compress_to_vec_zlib(&original, 6));
+
+        let header = CompressionHeader::build(&data, false, true).expect("Should parse header");
+        assert_eq!(header.compression_type(), CompressionType::Zlib);
+        assert_eq!(header.uncompressed_size(), original.len() as u64);
+        assert_eq!(header.decompress(&data), Ok(original));
+    }
+
+    #[test]
+    fn compression_header_should_decompress_zstd() {
+        let original = b"line table line table line table".to_vec();
+        let mut data = chdr64(ELFCOMPRESS_ZSTD, original.len() as u64);
+        data.extend(ruzstd::encoding::compress_to_vec(&original[..], ruzstd::encoding::CompressionLevel::Fastest));
+
+        let header = CompressionHeader::build(&data, false, true).expect("Should parse header");
+        assert_eq!(header.compression_type(), CompressionType::Zstd);
+        assert_eq!(header.decompress(&data), Ok(original));
+    }
+
+    #[test]
+    fn compression_header_should_decompress_legacy_zdebug() {
+        let original = b"abbreviations".to_vec();
+        let mut data = b"ZLIB".to_vec();
+        data.extend((original.len() as u64).to_be_bytes());
+        data.extend(miniz_oxide::deflate::compress_to_vec_zlib(&original, 6));
+
+        let header = CompressionHeader::build_gnu(&data).expect("Should parse header");
+        assert_eq!(header.compression_type(), CompressionType::GnuZlib);
+        assert_eq!(header.decompress(&data), Ok(original));
+        assert!(CompressionHeader::build_gnu(b"ZLIX\0\0\0\0\0\0\0\x01").is_err());
+    }
+
+    #[test]
+    fn compression_header_should_reject_size_mismatch() {
+        let original = b"0123456789".to_vec();
+        let mut data = chdr64(ELFCOMPRESS_ZLIB, 4);
+        data.extend(miniz_oxide::deflate::compress_to_vec_zlib(&original, 6));
+
+        let header = CompressionHeader::build(&data, false, true).expect("Should parse header");
+        assert!(header.decompress(&data).is_err());
+    }
+
+    #[test]
+    fn compression_header_should_bound_zstd_output_by_size() {
+        let original = vec![0u8; 0x10000];
+        let mut data = chdr64(ELFCOMPRESS_ZSTD, 0x10);
+        data.extend(ruzstd::encoding::compress_to_vec(&original[..], ruzstd::encoding::CompressionLevel::Fastest));
+        let header = CompressionHeader::build(&data, false, true).expect("Should parse header");
+        assert_eq!(header.decompress(&data), Err("Decompressed size does not match compression header"));
+
+        let mut data = chdr64(ELFCOMPRESS_ZSTD, u64::MAX);
+        data.extend(ruzstd::encoding::compress_to_vec(&original[..], ruzstd::encoding::CompressionLevel::Fastest));
+        let header = CompressionHeader::build(&data, false, true).expect("Should parse header");
+        assert!(header.decompress(&data).is_err());
+    }
+}
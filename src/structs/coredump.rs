@@ -0,0 +1,460 @@
+use crate::bits::*;
+use crate::structs::machine::Machine;
+use crate::structs::notes::*;
+use crate::structs::programheader::ProgramHeader;
+use crate::structs::sectionheader::string_until_null;
+use crate::termcolors;
+
+pub const AT_NULL: u64 = 0;
+pub const AT_PLATFORM: u64 = 15;
+pub const AT_BASE_PLATFORM: u64 = 24;
+pub const AT_EXECFN: u64 = 31;
+
+// PATH_MAX, AT_EXECFN is the longest of the strings the auxiliary vector points to
+const AUXV_STRING_LIMIT: u64 = 4096;
+
+const PRPSINFO64_SIZE: usize = 0x88;
+const PRPSINFO32_SIZE: usize = 0x7C;
+
+const X86_64_REGISTERS: [&str; 27] = ["r15", "r14", "r13", "r12", "rbp", "rbx", "r11", "r10", "r9", "r8",
+    "rax", "rcx", "rdx", "rsi", "rdi", "orig_rax", "rip", "cs", "eflags", "rsp", "ss",
+    "fs_base", "gs_base", "ds", "es", "fs", "gs"];
+
+#[derive(Debug)]
+pub struct ThreadStatus {
+    pid: i32,
+    ppid: i32,
+    signal: u16,
+    registers: Vec<(String, u64)>,
+}
+
+#[derive(Debug)]
+pub struct ProcessInfo {
+    state: u8,
+    sname: char,
+    nice: i8,
+    flags: u64,
+    uid: u32,
+    gid: u32,
+    pid: i32,
+    ppid: i32,
+    pgrp: i32,
+    sid: i32,
+    fname: String,
+    psargs: String,
+}
+
+#[derive(Debug)]
+pub struct SignalInfo {
+    signo: i32,
+    errno: i32,
+    code: i32,
+    address: Option<u64>,
+    sender: Option<(i32, u32)>,
+}
+
+#[derive(Debug)]
+pub struct MappedFile {
+    start: u64,
+    end: u64,
+    offset: u64,
+    path: String,
+}
+
+#[derive(Debug)]
+pub struct CoreDump {
+    address_width: usize,
+    process: Option<ProcessInfo>,
+    signal: Option<SignalInfo>,
+    threads: Vec<ThreadStatus>,
+    auxv: Vec<(u64, u64, Option<String>)>,
+    page_size: u64,
+    files: Vec<MappedFile>,
+}
+
+impl CoreDump {
+    // Decodes the CORE notes; strings referenced by the auxiliary vector are read back from the dumped memory
+    pub fn build(notes: &[&Note], program_header: &ProgramHeader, machine: Machine, is_32bit: bool, is_little_endian: bool) -> Result<CoreDump, &'static str> {
+        let fields = Fields{is_32bit, is_little_endian};
+        let mut core = CoreDump{
+            address_width: if is_32bit { 8 } else { 16 },
+            process: None,
+            signal: None,
+            threads: Vec::new(),
+            auxv: Vec::new(),
+            page_size: 0,
+            files: Vec::new()};
+
+        for note in notes.iter().filter(|note| note.name() == "CORE") {
+            let desc = note.descriptor();
+            match note.note_type() {
+                NT_PRSTATUS => core.threads.push(build_thread_status(desc, machine, &fields)?),
+                NT_PRPSINFO => core.process = Some(build_process_info(desc, &fields)?),
+                NT_SIGINFO => core.signal = Some(build_signal_info(desc, &fields)?),
+                NT_AUXV => {
+                    let entry_size = fields.word_size() * 2;
+                    for entry in desc.chunks_exact(entry_size) {
+                        let a_type = fields.word(entry, 0)?;
+                        let a_val = fields.word(entry, fields.word_size())?;
+                        if a_type == AT_NULL {
+                            break;
+                        }
+
+                        let string = match a_type {
+                            AT_PLATFORM | AT_BASE_PLATFORM | AT_EXECFN => program_header.read_virtual(a_val, AUXV_STRING_LIMIT)
+                                .map(|bytes| string_until_null(&bytes)),
+                            _ => None,
+                        };
+                        core.auxv.push((a_type, a_val, string));
+                    }
+                },
+                NT_FILE => {
+                    let (page_size, files) = build_mapped_files(desc, &fields)?;
+                    core.page_size = page_size;
+                    core.files = files;
+                },
+                _ => (),
+            }
+        }
+
+        Ok(core)
+    }
+
+    pub fn print(&self) {
+        let width = self.address_width;
+
+        if let Some(process) = &self.process {
+            println!("{}Core dump of process {} ({}){}", termcolors::purple(), process.pid, process.fname, termcolors::default());
+            println!("  {}Command line:\t\t{}{}", termcolors::white(), termcolors::green(), process.psargs);
+            println!("  {}State:\t\t{}{} ({}), nice {}, flags 0x{:x}", termcolors::white(), termcolors::green(), process.sname, process.state, process.nice, process.flags);
+            println!("  {}Process ids:\t\t{}pid {}, ppid {}, pgrp {}, sid {}", termcolors::white(), termcolors::green(), process.pid, process.ppid, process.pgrp, process.sid);
+            println!("  {}Credentials:\t\t{}uid {}, gid {}", termcolors::white(), termcolors::green(), process.uid, process.gid);
+        } else {
+            println!("{}Core dump without process information{}", termcolors::purple(), termcolors::default());
+        }
+
+        if let Some(signal) = &self.signal {
+            print!("  {}Signal:\t\t{}{} ({}), code {} ({})",
+                termcolors::white(),
+                termcolors::red(),
+                signal.signo,
+                signal_to_string(signal.signo),
+                signal.code,
+                signal_code_to_string(signal.signo, signal.code));
+            if signal.errno != 0 {
+                print!(", errno {}", signal.errno);
+            }
+            if let Some(address) = signal.address {
+                print!(", fault address 0x{:x}", address);
+            }
+            if let Some((pid, uid)) = signal.sender {
+                print!(", sent by pid {} uid {}", pid, uid);
+            }
+            println!();
+        }
+        println!();
+
+        println!("{}Threads: {}{}", termcolors::purple(), self.threads.len(), termcolors::default());
+        for thread in self.threads.iter() {
+            println!("  {}Thread {}{} (ppid {}), signal {} ({})",
+                termcolors::yellow(),
+                thread.pid,
+                termcolors::white(),
+                thread.ppid,
+                thread.signal,
+                signal_to_string(thread.signal as i32));
+
+            if thread.registers.is_empty() {
+                println!("    {}Registers are not decoded for this machine", termcolors::white());
+            }
+            for registers in thread.registers.chunks(3) {
+                print!("   ");
+                for (name, value) in registers.iter() {
+                    print!(" {}{:>8} {}0x{:0width$x}", termcolors::blue(), name, termcolors::white(), value);
+                }
+                println!();
+            }
+        }
+        print!("{}", termcolors::default());
+
+        if !self.auxv.is_empty() {
+            println!();
+            println!("{}Auxiliary vector contains {} entries:{}", termcolors::purple(), self.auxv.len(), termcolors::default());
+            for (a_type, a_val, string) in self.auxv.iter() {
+                print!("  {}{:<22}{}0x{:x}", termcolors::yellow(), auxv_type_to_string(*a_type), termcolors::white(), a_val);
+                if let Some(string) = string {
+                    print!(" ({})", string);
+                }
+                println!();
+            }
+        }
+
+        if !self.files.is_empty() {
+            println!();
+            println!("{}Mapped files: {} (page size {}){}", termcolors::purple(), self.files.len(), self.page_size, termcolors::default());
+            println!("  {}{:<w$}   {:<w$} {}Offset\t\t{}Path", termcolors::green(), "Start", "End", termcolors::blue(), termcolors::white(), w = width + 2);
+            for file in self.files.iter() {
+                println!("  {}0x{:0width$x} - 0x{:0width$x} {}0x{:08x}\t{}{}",
+                    termcolors::green(),
+                    file.start,
+                    file.end,
+                    termcolors::blue(),
+                    file.offset,
+                    termcolors::white(),
+                    file.path);
+            }
+        }
+        print!("{}", termcolors::default());
+    }
+}
+
+// Field accessors for the kernel structures, whose long fields follow the ELF class
+struct Fields {
+    is_32bit: bool,
+    is_little_endian: bool,
+}
+
+impl Fields {
+    fn word_size(&self) -> usize {
+        if self.is_32bit { 4 } else { 8 }
+    }
+
+    fn u16(&self, bytes: &[u8], offset: usize) -> Result<u16, &'static str> {
+        let field = bytes.get(offset..offset + 2).ok_or("Core note too short")?;
+        Ok(to_u16_from_slice(field.try_into().unwrap(), self.is_little_endian))
+    }
+
+    fn u32(&self, bytes: &[u8], offset: usize) -> Result<u32, &'static str> {
+        let field = bytes.get(offset..offset + 4).ok_or("Core note too short")?;
+        Ok(to_u32_from_slice(field.try_into().unwrap(), self.is_little_endian))
+    }
+
+    fn word(&self, bytes: &[u8], offset: usize) -> Result<u64, &'static str> {
+        if self.is_32bit {
+            return Ok(self.u32(bytes, offset)? as u64);
+        }
+        let field = bytes.get(offset..offset + 8).ok_or("Core note too short")?;
+        Ok(to_u64_from_slice(field.try_into().unwrap(), self.is_little_endian))
+    }
+}
+
+// struct elf_prstatus: pr_info, pr_cursig, pr_sigpend, pr_sighold, pids, four timevals, then pr_reg
+fn build_thread_status(desc: &[u8], machine: Machine, fields: &Fields) -> Result<ThreadStatus, &'static str> {
+    let pid_offset = if fields.is_32bit { 24 } else { 32 };
+    let registers_offset = pid_offset + 16 + 4 * 2 * fields.word_size();
+
+    let names: Vec<String> = match (machine, fields.is_32bit) {
+        (Machine::EMX8664, false) => X86_64_REGISTERS.iter().map(|name| String::from(*name)).collect(),
+        (Machine::EMAARCH64, false) => (0..31).map(|index| format!("x{}", index))
+            .chain(["sp", "pc", "pstate"].iter().map(|name| String::from(*name)))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut registers: Vec<(String, u64)> = Vec::new();
+    for (index, name) in names.into_iter().enumerate() {
+        registers.push((name, fields.word(desc, registers_offset + index * 8)?));
+    }
+
+    Ok(ThreadStatus{
+        pid: fields.u32(desc, pid_offset)? as i32,
+        ppid: fields.u32(desc, pid_offset + 4)? as i32,
+        signal: fields.u16(desc, 12)?,
+        registers})
+}
+
+// struct elf_prpsinfo, where the 32-bit layouts use 16-bit uid and gid fields
+fn build_process_info(desc: &[u8], fields: &Fields) -> Result<ProcessInfo, &'static str> {
+    let expected_size = if fields.is_32bit { PRPSINFO32_SIZE } else { PRPSINFO64_SIZE };
+    if desc.len() < expected_size {
+        return Err("NT_PRPSINFO note too short");
+    }
+
+    let (flags, uid, gid, pid_offset) = match fields.is_32bit {
+        true => (fields.u32(desc, 4)? as u64, fields.u16(desc, 8)? as u32, fields.u16(desc, 10)? as u32, 12),
+        false => (fields.word(desc, 8)?, fields.u32(desc, 16)?, fields.u32(desc, 20)?, 24),
+    };
+    let fname_offset = pid_offset + 16;
+    let psargs_offset = fname_offset + 16;
+
+    Ok(ProcessInfo{
+        state: desc[0],
+        sname: desc[1] as char,
+        nice: desc[3] as i8,
+        flags,
+        uid,
+        gid,
+        pid: fields.u32(desc, pid_offset)? as i32,
+        ppid: fields.u32(desc, pid_offset + 4)? as i32,
+        pgrp: fields.u32(desc, pid_offset + 8)? as i32,
+        sid: fields.u32(desc, pid_offset + 12)? as i32,
+        fname: string_until_null(&desc[fname_offset..psargs_offset]),
+        psargs: string_until_null(&desc[psargs_offset..expected_size]).trim_end().to_string()})
+}
+
+// siginfo_t: the union after si_signo, si_errno and si_code is word aligned
+fn build_signal_info(desc: &[u8], fields: &Fields) -> Result<SignalInfo, &'static str> {
+    let signo = fields.u32(desc, 0)? as i32;
+    let code = fields.u32(desc, 8)? as i32;
+    let union_offset = if fields.is_32bit { 12 } else { 16 };
+
+    // Faults carry the offending address, signals sent by processes (si_code <= 0) their sender
+    let address = match signo {
+        SIGILL | SIGTRAP | SIGBUS | SIGFPE | SIGSEGV if code > 0 => Some(fields.word(desc, union_offset)?),
+        _ => None,
+    };
+    let sender = match code <= 0 {
+        true => Some((fields.u32(desc, union_offset)? as i32, fields.u32(desc, union_offset + 4)?)),
+        false => None,
+    };
+
+    Ok(SignalInfo{signo, errno: fields.u32(desc, 4)? as i32, code, address, sender})
+}
+
+// NT_FILE: count and page size, count (start, end, page offset) triples, then count file names
+fn build_mapped_files(desc: &[u8], fields: &Fields) -> Result<(u64, Vec<MappedFile>), &'static str> {
+    let word_size = fields.word_size();
+    let count = fields.word(desc, 0)? as usize;
+    let page_size = fields.word(desc, word_size)?;
+
+    let names_offset = count.checked_mul(3 * word_size)
+        .and_then(|size| size.checked_add(2 * word_size))
+        .filter(|offset| *offset <= desc.len())
+        .ok_or("NT_FILE note too short")?;
+    let mut names = desc[names_offset..].split(|&b| b == 0);
+
+    let mut files: Vec<MappedFile> = Vec::new();
+    for index in 0..count {
+        let entry = 2 * word_size + index * 3 * word_size;
+        let name = names.next().ok_or("NT_FILE note has fewer names than mappings")?;
+        files.push(MappedFile{
+            start: fields.word(desc, entry)?,
+            end: fields.word(desc, entry + word_size)?,
+            offset: fields.word(desc, entry + 2 * word_size)?.wrapping_mul(page_size),
+            path: String::from_utf8_lossy(name).into_owned()});
+    }
+
+    Ok((page_size, files))
+}
+
+const SIGILL: i32 = 4;
+const SIGTRAP: i32 = 5;
+const SIGBUS: i32 = 7;
+const SIGFPE: i32 = 8;
+const SIGSEGV: i32 = 11;
+
+fn signal_to_string(signal: i32) -> String {
+    const NAMES: [&str; 31] = ["SIGHUP", "SIGINT", "SIGQUIT", "SIGILL", "SIGTRAP", "SIGABRT", "SIGBUS", "SIGFPE",
+        "SIGKILL", "SIGUSR1", "SIGSEGV", "SIGUSR2", "SIGPIPE", "SIGALRM", "SIGTERM", "SIGSTKFLT",
+        "SIGCHLD", "SIGCONT", "SIGSTOP", "SIGTSTP", "SIGTTIN", "SIGTTOU", "SIGURG", "SIGXCPU",
+        "SIGXFSZ", "SIGVTALRM", "SIGPROF", "SIGWINCH", "SIGIO", "SIGPWR", "SIGSYS"];
+
+    match signal {
+        0 => String::from("none"),
+        1..=31 => String::from(NAMES[signal as usize - 1]),
+        _ => format!("SIGRT{}", signal - 32),
+    }
+}
+
+fn signal_code_to_string(signal: i32, code: i32) -> &'static str {
+    match (signal, code) {
+        (_, 0) => "SI_USER",
+        (_, 0x80) => "SI_KERNEL",
+        (_, -1) => "SI_QUEUE",
+        (_, -6) => "SI_TKILL",
+        (SIGSEGV, 1) => "SEGV_MAPERR",
+        (SIGSEGV, 2) => "SEGV_ACCERR",
+        (SIGBUS, 1) => "BUS_ADRALN",
+        (SIGBUS, 2) => "BUS_ADRERR",
+        (SIGILL, 1) => "ILL_ILLOPC",
+        (SIGILL, 2) => "ILL_ILLOPN",
+        (SIGFPE, 1) => "FPE_INTDIV",
+        (SIGTRAP, 1) => "TRAP_BRKPT",
+        _ => "unknown",
+    }
+}
+
+fn auxv_type_to_string(a_type: u64) -> String {
+    let name = match a_type {
+        1 => "AT_IGNORE",
+        2 => "AT_EXECFD",
+        3 => "AT_PHDR",
+        4 => "AT_PHENT",
+        5 => "AT_PHNUM",
+        6 => "AT_PAGESZ",
+        7 => "AT_BASE",
+        8 => "AT_FLAGS",
+        9 => "AT_ENTRY",
+        10 => "AT_NOTELF",
+        11 => "AT_UID",
+        12 => "AT_EUID",
+        13 => "AT_GID",
+        14 => "AT_EGID",
+        AT_PLATFORM => "AT_PLATFORM",
+        16 => "AT_HWCAP",
+        17 => "AT_CLKTCK",
+        23 => "AT_SECURE",
+        AT_BASE_PLATFORM => "AT_BASE_PLATFORM",
+        25 => "AT_RANDOM",
+        26 => "AT_HWCAP2",
+        27 => "AT_RSEQ_FEATURE_SIZE",
+        28 => "AT_RSEQ_ALIGN",
+        29 => "AT_HWCAP3",
+        30 => "AT_HWCAP4",
+        AT_EXECFN => "AT_EXECFN",
+        32 => "AT_SYSINFO",
+        33 => "AT_SYSINFO_EHDR",
+        51 => "AT_MINSIGSTKSZ",
+        _ => return format!("<unknown: {}>", a_type),
+    };
+    String::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_mapped_files_should_decode_nt_file() {
+        let mut desc: Vec<u8> = Vec::new();
+        for value in [2u64, 0x1000, 0x400000, 0x401000, 0, 0x7f0000, 0x7f2000, 3] {
+            desc.extend(value.to_le_bytes());
+        }
+        desc.extend(b"/bin/true\0/lib/libc.so.6\0");
+
+        let (page_size, files) = build_mapped_files(&desc, &Fields{is_32bit: false, is_little_endian: true}).expect("Should parse NT_FILE");
+        assert_eq!(page_size, 0x1000);
+        assert_eq!(files.len(), 2);
+        assert_eq!((files[1].start, files[1].end, files[1].offset), (0x7f0000, 0x7f2000, 0x3000));
+        assert_eq!(files[1].path, "/lib/libc.so.6");
+
+        assert!(build_mapped_files(&desc[..40], &Fields{is_32bit: false, is_little_endian: true}).is_err());
+    }
+
+    #[test]
+    fn build_thread_status_should_decode_x86_64_registers() {
+        let mut desc = vec![0u8; 0x150];
+        desc[12..14].copy_from_slice(&11u16.to_le_bytes());
+        desc[32..36].copy_from_slice(&1234u32.to_le_bytes());
+        // rip is the 17th register of user_regs_struct
+        desc[112 + 16 * 8..112 + 17 * 8].copy_from_slice(&0x401136u64.to_le_bytes());
+
+        let thread = build_thread_status(&desc, Machine::EMX8664, &Fields{is_32bit: false, is_little_endian: true}).expect("Should parse NT_PRSTATUS");
+        assert_eq!((thread.pid, thread.signal), (1234, 11));
+        assert_eq!(thread.registers.len(), 27);
+        assert_eq!(thread.registers[16], (String::from("rip"), 0x401136));
+    }
+
+    #[test]
+    fn build_signal_info_should_report_fault_address() {
+        let mut desc = vec![0u8; 0x80];
+        desc[0..4].copy_from_slice(&11u32.to_le_bytes());
+        desc[8..12].copy_from_slice(&1u32.to_le_bytes());
+        desc[16..24].copy_from_slice(&0xdeadu64.to_le_bytes());
+
+        let signal = build_signal_info(&desc, &Fields{is_32bit: false, is_little_endian: true}).expect("Should parse NT_SIGINFO");
+        assert_eq!(signal.address, Some(0xdead));
+        assert_eq!(signal.sender, None);
+        assert_eq!(signal_code_to_string(signal.signo, signal.code), "SEGV_MAPERR");
+    }
+}
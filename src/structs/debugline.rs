@@ -0,0 +1,353 @@
+use crate::structs::dwarf::*;
+use crate::structs::dwarfreader::DwarfReader;
+use crate::termcolors;
+
+pub const DW_LNS_COPY: u8 = 0x01;
+pub const DW_LNS_ADVANCE_PC: u8 = 0x02;
+pub const DW_LNS_ADVANCE_LINE: u8 = 0x03;
+pub const DW_LNS_SET_FILE: u8 = 0x04;
+pub const DW_LNS_SET_COLUMN: u8 = 0x05;
+pub const DW_LNS_NEGATE_STMT: u8 = 0x06;
+pub const DW_LNS_SET_BASIC_BLOCK: u8 = 0x07;
+pub const DW_LNS_CONST_ADD_PC: u8 = 0x08;
+pub const DW_LNS_FIXED_ADVANCE_PC: u8 = 0x09;
+pub const DW_LNS_SET_PROLOGUE_END: u8 = 0x0A;
+pub const DW_LNS_SET_EPILOGUE_BEGIN: u8 = 0x0B;
+pub const DW_LNS_SET_ISA: u8 = 0x0C;
+
+pub const DW_LNE_END_SEQUENCE: u8 = 0x01;
+pub const DW_LNE_SET_ADDRESS: u8 = 0x02;
+pub const DW_LNE_DEFINE_FILE: u8 = 0x03;
+
+pub const DW_LNCT_PATH: u64 = 0x1;
+pub const DW_LNCT_DIRECTORY_INDEX: u64 = 0x2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineRow {
+    address: u64,
+    file: u64,
+    line: u64,
+    end_sequence: bool,
+}
+
+#[derive(Debug)]
+pub struct LineProgram {
+    files: Vec<String>,
+    rows: Vec<LineRow>,
+}
+
+impl LineProgram {
+    // Decodes the line program at the given .debug_line offset, file names are joined with their directories
+    pub fn build(sections: &DwarfSections, offset: u64, comp_dir: Option<&String>, comp_name: Option<&String>, is_little_endian: bool) -> Result<LineProgram, &'static str> {
+        let offset = usize::try_from(offset).map_err(|_| "Line program offset out of range")?;
+        let mut reader = DwarfReader::new(&sections.debug_line, offset, is_little_endian);
+
+        let (unit_length, is_dwarf64) = reader.read_initial_length()?;
+        let unit_end = reader.offset().checked_add(usize::try_from(unit_length).map_err(|_| "Line program too large")?)
+            .filter(|end| *end <= sections.debug_line.len())
+            .ok_or("Line program extends past the end of .debug_line")?;
+
+        let version = reader.read_u16()?;
+        if !(2..=5).contains(&version) {
+            return Err("Unsupported line program version");
+        }
+
+        let mut address_size = 0;
+        if version >= 5 {
+            address_size = reader.read_u8()?;
+            let _segment_selector_size = reader.read_u8()?;
+        }
+
+        let header_length = reader.read_offset(is_dwarf64)?;
+        let program_start = usize::try_from(header_length).ok()
+            .and_then(|length| reader.offset().checked_add(length))
+            .filter(|start| *start <= unit_end)
+            .ok_or("Line program header extends past the end of the unit")?;
+
+        let minimum_instruction_length = reader.read_u8()? as u64;
+        if version >= 4 {
+            let _maximum_operations_per_instruction = reader.read_u8()?;
+        }
+        let _default_is_stmt = reader.read_u8()?;
+        let line_base = reader.read_u8()? as i8 as i64;
+        let line_range = reader.read_u8()? as u64;
+        let opcode_base = reader.read_u8()?;
+        if line_range == 0 {
+            return Err("Line program has a zero line range");
+        }
+
+        let standard_opcode_lengths = reader.read_bytes(opcode_base.saturating_sub(1) as usize)?;
+
+        let comp_dir = comp_dir.map(|dir| dir.as_str());
+        let mut files: Vec<String> = Vec::new();
+        let mut directories: Vec<String> = Vec::new();
+
+        if version >= 5 {
+            let format = UnitFormat{version, is_dwarf64, address_size, unit_offset: 0};
+
+            // Directory 0 is the compilation directory, the rest may be relative to it
+            for entry in read_entry_table(&mut reader, &format, sections, is_little_endian)? {
+                let directory = entry.path.ok_or("Line program directory without a path")?;
+                let base = directories.first().map(|dir| dir.as_str());
+                directories.push(join_path(base, &directory));
+            }
+
+            for entry in read_entry_table(&mut reader, &format, sections, is_little_endian)? {
+                let name = entry.path.ok_or("Line program file without a path")?;
+                let directory = directories.get(entry.directory_index as usize).map(|dir| dir.as_str());
+                files.push(join_path(directory, &name));
+            }
+        } else {
+            directories.push(comp_dir.unwrap_or_default().to_string());
+            loop {
+                let directory = reader.read_cstr()?;
+                if directory.is_empty() {
+                    break;
+                }
+                directories.push(join_path(comp_dir, &directory));
+            }
+
+            // File numbering starts at 1, slot 0 stands for the primary source file
+            files.push(join_path(comp_dir, comp_name.map(|name| name.as_str()).unwrap_or("??")));
+            loop {
+                let name = reader.read_cstr()?;
+                if name.is_empty() {
+                    break;
+                }
+                let directory_index = reader.read_uleb128()?;
+                let _modification_time = reader.read_uleb128()?;
+                let _length = reader.read_uleb128()?;
+                files.push(join_path(directories.get(directory_index as usize).map(|dir| dir.as_str()), &name));
+            }
+        }
+
+        reader.set_offset(program_start);
+
+        let mut rows: Vec<LineRow> = Vec::new();
+        let initial_row = LineRow{address: 0, file: 1, line: 1, end_sequence: false};
+        let mut row = initial_row;
+
+        while reader.offset() < unit_end {
+            let opcode = reader.read_u8()?;
+
+            if opcode >= opcode_base {
+                let adjusted = (opcode - opcode_base) as u64;
+                row.address = row.address.wrapping_add((adjusted / line_range) * minimum_instruction_length);
+                row.line = row.line.wrapping_add_signed(line_base + (adjusted % line_range) as i64);
+                rows.push(row);
+                continue;
+            }
+
+            match opcode {
+                0 => {
+                    let length = reader.read_uleb128()?;
+                    if length == 0 {
+                        continue;
+                    }
+                    let end = reader.offset().checked_add(usize::try_from(length).map_err(|_| "Extended opcode too long")?)
+                        .ok_or("Extended opcode too long")?;
+
+                    match reader.read_u8()? {
+                        DW_LNE_END_SEQUENCE => {
+                            row.end_sequence = true;
+                            rows.push(row);
+                            row = initial_row;
+                        },
+                        // Before DWARF 5 the address size is only known from the opcode length
+                        DW_LNE_SET_ADDRESS => row.address = reader.read_address((length - 1) as u8)?,
+                        DW_LNE_DEFINE_FILE => {
+                            let name = reader.read_cstr()?;
+                            let directory_index = reader.read_uleb128()?;
+                            files.push(join_path(directories.get(directory_index as usize).map(|dir| dir.as_str()), &name));
+                        },
+                        _ => (),
+                    }
+                    reader.set_offset(end);
+                },
+                DW_LNS_COPY => rows.push(row),
+                DW_LNS_ADVANCE_PC => row.address = row.address.wrapping_add(reader.read_uleb128()?.wrapping_mul(minimum_instruction_length)),
+                DW_LNS_ADVANCE_LINE => row.line = row.line.wrapping_add_signed(reader.read_sleb128()?),
+                DW_LNS_SET_FILE => row.file = reader.read_uleb128()?,
+                DW_LNS_SET_COLUMN => { reader.read_uleb128()?; },
+                // Statement and block boundaries do not matter for address lookups
+                DW_LNS_NEGATE_STMT | DW_LNS_SET_BASIC_BLOCK | DW_LNS_SET_PROLOGUE_END | DW_LNS_SET_EPILOGUE_BEGIN => (),
+                DW_LNS_CONST_ADD_PC => {
+                    let adjusted = (255 - opcode_base) as u64;
+                    row.address = row.address.wrapping_add((adjusted / line_range) * minimum_instruction_length);
+                },
+                DW_LNS_FIXED_ADVANCE_PC => row.address = row.address.wrapping_add(reader.read_u16()? as u64),
+                DW_LNS_SET_ISA => { reader.read_uleb128()?; },
+                // Unknown standard opcodes are skipped using the operand counts from the header
+                _ => {
+                    for _ in 0..standard_opcode_lengths[opcode as usize - 1] {
+                        reader.read_uleb128()?;
+                    }
+                },
+            }
+        }
+
+        Ok(LineProgram{files, rows})
+    }
+
+    // File and line of the row covering the address, the end of a sequence covers nothing
+    pub fn lookup(&self, address: u64) -> Option<(&String, u64)> {
+        self.rows.windows(2)
+            .find(|pair| !pair[0].end_sequence && pair[0].address <= address && address < pair[1].address)
+            .map(|pair| {
+                let file = self.files.get(pair[0].file as usize);
+                (file.unwrap_or(&self.files[0]), pair[0].line)
+            })
+    }
+}
+
+#[derive(Debug)]
+pub struct SourceLocation {
+    address: u64,
+    function: Option<String>,
+    file: Option<String>,
+    line: u64,
+}
+
+impl SourceLocation {
+    pub fn build(address: u64, debug_info: &DebugInfo, line_programs: &[LineProgram]) -> SourceLocation {
+        let location = line_programs.iter().find_map(|program| program.lookup(address));
+        SourceLocation{
+            address,
+            function: debug_info.function_name(address).cloned(),
+            file: location.map(|(file, _)| file.clone()),
+            line: location.map(|(_, line)| line).unwrap_or(0)}
+    }
+
+    pub fn print(&self) {
+        println!("{}0x{:x}{}: {}{} {}at {}{}:{}{}",
+            termcolors::red(),
+            self.address,
+            termcolors::white(),
+            termcolors::yellow(),
+            self.function.as_deref().unwrap_or("??"),
+            termcolors::white(),
+            termcolors::cyan(),
+            self.file.as_deref().unwrap_or("??"),
+            self.line,
+            termcolors::default());
+    }
+}
+
+struct FileEntry {
+    path: Option<String>,
+    directory_index: u64,
+}
+
+// DWARF 5 directory and file tables are described by (content type, form) pairs
+fn read_entry_table(reader: &mut DwarfReader, format: &UnitFormat, sections: &DwarfSections, is_little_endian: bool) -> Result<Vec<FileEntry>, &'static str> {
+    let format_count = reader.read_u8()?;
+    let mut entry_format: Vec<(u64, u64)> = Vec::new();
+    for _ in 0..format_count {
+        entry_format.push((reader.read_uleb128()?, reader.read_uleb128()?));
+    }
+
+    let count = reader.read_uleb128()?;
+    let mut entries: Vec<FileEntry> = Vec::new();
+    for _ in 0..count {
+        let mut entry = FileEntry{path: None, directory_index: 0};
+        for (content_type, form) in entry_format.iter() {
+            let value = read_attribute(reader, *form, 0, format)?;
+            match (*content_type, value) {
+                (DW_LNCT_PATH, value) => entry.path = string_value(sections, &value, is_little_endian),
+                (DW_LNCT_DIRECTORY_INDEX, AttributeValue::Unsigned(index)) => entry.directory_index = index,
+                _ => (),
+            }
+        }
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn join_path(directory: Option<&str>, name: &str) -> String {
+    match directory {
+        Some(directory) if !directory.is_empty() && !name.starts_with('/') =>
+            format!("{}/{}", directory.trim_end_matches('/'), name),
+        _ => String::from(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_sections(mut program: Vec<u8>) -> DwarfSections {
+        let length = program.len() as u32 - 4;
+        program[0..4].copy_from_slice(&length.to_le_bytes());
+        DwarfSections{debug_line: program, debug_line_str: b"/src\0".to_vec(), ..Default::default()}
+    }
+
+    fn set_header_length(program: &mut [u8], offset: usize) {
+        let header_length = (program.len() - offset - 4) as u32;
+        program[offset..offset + 4].copy_from_slice(&header_length.to_le_bytes());
+    }
+
+    // Shared program body: rows at 0x1000 line 3, 0x1004 line 4, 0x1010 line 2, end at 0x1018
+    const PROGRAM: [u8; 19] = [
+        0x00, 0x09, DW_LNE_SET_ADDRESS, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        DW_LNS_ADVANCE_LINE, 0x02, DW_LNS_COPY,
+        0x4B,
+        0xB8,
+        DW_LNS_ADVANCE_PC, 0x08,
+        0x00,
+    ];
+
+    #[test]
+    fn line_program_should_decode_dwarf4_tables() {
+        let mut program = vec![0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x01, 0x01, 0xFB, 0x0E, 0x0D,
+            0x00, 0x01, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x01];
+        program.extend(b"inc\0\0");
+        program.extend(b"main.c\0\0\0\0");
+        program.extend(b"util.h\0\x01\0\0");
+        program.push(0x00);
+        set_header_length(&mut program, 6);
+        program.extend(PROGRAM);
+        program.extend([0x01, DW_LNE_END_SEQUENCE]);
+
+        let sections = line_sections(program);
+        let comp_dir = String::from("/build");
+        let name = String::from("main.c");
+        let line_program = LineProgram::build(&sections, 0, Some(&comp_dir), Some(&name), true).expect("Should parse line program");
+
+        assert_eq!(line_program.files, vec!["/build/main.c", "/build/main.c", "/build/inc/util.h"]);
+        assert_eq!(line_program.lookup(0x1000), Some((&String::from("/build/main.c"), 3)));
+        assert_eq!(line_program.lookup(0x1007), Some((&String::from("/build/main.c"), 4)));
+        assert_eq!(line_program.lookup(0x1017), Some((&String::from("/build/main.c"), 2)));
+        assert_eq!(line_program.lookup(0x1018), None);
+        assert_eq!(line_program.lookup(0x0FFF), None);
+    }
+
+    #[test]
+    fn line_program_should_decode_dwarf5_tables() {
+        let mut program = vec![0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x01, 0x01, 0xFB, 0x0E, 0x0D,
+            0x00, 0x01, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x01];
+        // One directory given as a .debug_line_str offset
+        program.extend([0x01, DW_LNCT_PATH as u8, DW_FORM_LINE_STRP as u8, 0x01, 0x00, 0x00, 0x00, 0x00]);
+        // Two files with inline names, the second one in directory 0
+        program.extend([0x02, DW_LNCT_PATH as u8, DW_FORM_STRING as u8, DW_LNCT_DIRECTORY_INDEX as u8, DW_FORM_UDATA as u8, 0x02]);
+        program.extend(b"main.c\0\x00");
+        program.extend(b"lib/a.h\0\x00");
+        set_header_length(&mut program, 8);
+        program.extend(PROGRAM);
+        program.extend([0x01, DW_LNE_END_SEQUENCE]);
+
+        let sections = line_sections(program);
+        let line_program = LineProgram::build(&sections, 0, None, None, true).expect("Should parse line program");
+
+        assert_eq!(line_program.files, vec!["/src/main.c", "/src/lib/a.h"]);
+        assert_eq!(line_program.lookup(0x1004), Some((&String::from("/src/lib/a.h"), 4)));
+    }
+
+    #[test]
+    fn line_program_should_reject_truncated_unit() {
+        let sections = DwarfSections{debug_line: vec![0x40, 0x00, 0x00, 0x00, 0x04, 0x00], ..Default::default()};
+        assert!(LineProgram::build(&sections, 0, None, None, true).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use crate::structs::x86decoder::{decode, Instruction};
+use crate::termcolors;
+
+// objdump prints up to seven instruction bytes per line
+const BYTES_PER_LINE: usize = 7;
+
+#[derive(Debug)]
+pub struct Disassembly {
+    section_name: String,
+    instructions: Vec<(Instruction, Vec<u8>)>,
+    labels: BTreeMap<u64, String>,
+}
+
+impl Disassembly {
+    // Decodes [start, end) of a section loaded at address, labels map addresses to symbol names across the whole file
+    pub fn build(section_name: &str, address: u64, data: &[u8], range: (u64, u64), labels: &BTreeMap<u64, String>) -> Disassembly {
+        let start = range.0.saturating_sub(address).min(data.len() as u64) as usize;
+        let end = range.1.saturating_sub(address).min(data.len() as u64) as usize;
+
+        let mut instructions = Vec::new();
+        let mut position = start;
+        while position < end {
+            let instruction = decode(&data[position..end], address + position as u64);
+            let bytes = data[position..position + instruction.length].to_vec();
+            position += instruction.length;
+            instructions.push((instruction, bytes));
+        }
+
+        Disassembly {
+            section_name: section_name.to_string(),
+            instructions,
+            labels: labels.clone(),
+        }
+    }
+
+    // Nearest preceding label, rendered like objdump does
+    fn symbolize(&self, address: u64) -> String {
+        match self.labels.range(..=address).next_back() {
+            Some((label, name)) if *label == address => format!(" <{}>", name),
+            Some((label, name)) => format!(" <{}+0x{:x}>", name, address - label),
+            None => String::new(),
+        }
+    }
+
+    pub fn print(&self) {
+        println!("{}Disassembly of section {}:{}", termcolors::purple(), self.section_name, termcolors::default());
+
+        for (instruction, bytes) in self.instructions.iter() {
+            if let Some(name) = self.labels.get(&instruction.address) {
+                println!();
+                println!("{}{:016x} {}<{}>:{}", termcolors::green(), instruction.address, termcolors::yellow(), name, termcolors::default());
+            }
+
+            let mut operands = instruction.operands.clone();
+            if let Some(target) = instruction.target {
+                operands += &self.symbolize(target);
+            }
+            if let Some(reference) = instruction.reference {
+                operands += &format!("        # {:x}{}", reference, self.symbolize(reference));
+            }
+
+            let mut chunks = bytes.chunks(BYTES_PER_LINE);
+            let first = chunks.next().unwrap_or_default();
+            let text = format!("{:<6} {}", instruction.mnemonic, operands);
+            println!("{}{:>8x}:\t{}{:<21}\t{}{}{}",
+                termcolors::green(),
+                instruction.address,
+                termcolors::gray(),
+                format_bytes(first),
+                termcolors::white(),
+                text.trim_end(),
+                termcolors::default());
+
+            let mut address = instruction.address + first.len() as u64;
+            for chunk in chunks {
+                println!("{}{:>8x}:\t{}{}{}", termcolors::green(), address, termcolors::gray(), format_bytes(chunk).trim_end(), termcolors::default());
+                address += chunk.len() as u64;
+            }
+        }
+    }
+}
+
+fn format_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x} ", byte)).collect()
+}
@@ -0,0 +1,768 @@
+use std::collections::HashMap;
+
+use crate::structs::dwarfreader::DwarfReader;
+use crate::termcolors;
+
+pub const DW_UT_COMPILE: u8 = 0x01;
+pub const DW_UT_TYPE: u8 = 0x02;
+pub const DW_UT_PARTIAL: u8 = 0x03;
+pub const DW_UT_SKELETON: u8 = 0x04;
+pub const DW_UT_SPLIT_COMPILE: u8 = 0x05;
+pub const DW_UT_SPLIT_TYPE: u8 = 0x06;
+
+pub const DW_TAG_INLINED_SUBROUTINE: u64 = 0x1D;
+pub const DW_TAG_SUBPROGRAM: u64 = 0x2E;
+
+pub const DW_AT_NAME: u64 = 0x03;
+pub const DW_AT_STMT_LIST: u64 = 0x10;
+pub const DW_AT_LOW_PC: u64 = 0x11;
+pub const DW_AT_HIGH_PC: u64 = 0x12;
+pub const DW_AT_LANGUAGE: u64 = 0x13;
+pub const DW_AT_COMP_DIR: u64 = 0x1B;
+pub const DW_AT_PRODUCER: u64 = 0x25;
+pub const DW_AT_ABSTRACT_ORIGIN: u64 = 0x31;
+pub const DW_AT_SPECIFICATION: u64 = 0x47;
+pub const DW_AT_RANGES: u64 = 0x55;
+pub const DW_AT_LINKAGE_NAME: u64 = 0x6E;
+pub const DW_AT_STR_OFFSETS_BASE: u64 = 0x72;
+pub const DW_AT_ADDR_BASE: u64 = 0x73;
+pub const DW_AT_RNGLISTS_BASE: u64 = 0x74;
+pub const DW_AT_MIPS_LINKAGE_NAME: u64 = 0x2007;
+
+pub const DW_FORM_ADDR: u64 = 0x01;
+pub const DW_FORM_BLOCK2: u64 = 0x03;
+pub const DW_FORM_BLOCK4: u64 = 0x04;
+pub const DW_FORM_DATA2: u64 = 0x05;
+pub const DW_FORM_DATA4: u64 = 0x06;
+pub const DW_FORM_DATA8: u64 = 0x07;
+pub const DW_FORM_STRING: u64 = 0x08;
+pub const DW_FORM_BLOCK: u64 = 0x09;
+pub const DW_FORM_BLOCK1: u64 = 0x0A;
+pub const DW_FORM_DATA1: u64 = 0x0B;
+pub const DW_FORM_FLAG: u64 = 0x0C;
+pub const DW_FORM_SDATA: u64 = 0x0D;
+pub const DW_FORM_STRP: u64 = 0x0E;
+pub const DW_FORM_UDATA: u64 = 0x0F;
+pub const DW_FORM_REF_ADDR: u64 = 0x10;
+pub const DW_FORM_REF1: u64 = 0x11;
+pub const DW_FORM_REF2: u64 = 0x12;
+pub const DW_FORM_REF4: u64 = 0x13;
+pub const DW_FORM_REF8: u64 = 0x14;
+pub const DW_FORM_REF_UDATA: u64 = 0x15;
+pub const DW_FORM_INDIRECT: u64 = 0x16;
+pub const DW_FORM_SEC_OFFSET: u64 = 0x17;
+pub const DW_FORM_EXPRLOC: u64 = 0x18;
+pub const DW_FORM_FLAG_PRESENT: u64 = 0x19;
+pub const DW_FORM_STRX: u64 = 0x1A;
+pub const DW_FORM_ADDRX: u64 = 0x1B;
+pub const DW_FORM_REF_SUP4: u64 = 0x1C;
+pub const DW_FORM_STRP_SUP: u64 = 0x1D;
+pub const DW_FORM_DATA16: u64 = 0x1E;
+pub const DW_FORM_LINE_STRP: u64 = 0x1F;
+pub const DW_FORM_REF_SIG8: u64 = 0x20;
+pub const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+pub const DW_FORM_LOCLISTX: u64 = 0x22;
+pub const DW_FORM_RNGLISTX: u64 = 0x23;
+pub const DW_FORM_REF_SUP8: u64 = 0x24;
+pub const DW_FORM_STRX1: u64 = 0x25;
+pub const DW_FORM_STRX2: u64 = 0x26;
+pub const DW_FORM_STRX3: u64 = 0x27;
+pub const DW_FORM_STRX4: u64 = 0x28;
+pub const DW_FORM_ADDRX1: u64 = 0x29;
+pub const DW_FORM_ADDRX2: u64 = 0x2A;
+pub const DW_FORM_ADDRX3: u64 = 0x2B;
+pub const DW_FORM_ADDRX4: u64 = 0x2C;
+pub const DW_FORM_GNU_ADDR_INDEX: u64 = 0x1F01;
+pub const DW_FORM_GNU_STR_INDEX: u64 = 0x1F02;
+pub const DW_FORM_GNU_REF_ALT: u64 = 0x1F20;
+pub const DW_FORM_GNU_STRP_ALT: u64 = 0x1F21;
+
+pub const DW_RLE_END_OF_LIST: u8 = 0x00;
+pub const DW_RLE_BASE_ADDRESSX: u8 = 0x01;
+pub const DW_RLE_STARTX_ENDX: u8 = 0x02;
+pub const DW_RLE_STARTX_LENGTH: u8 = 0x03;
+pub const DW_RLE_OFFSET_PAIR: u8 = 0x04;
+pub const DW_RLE_BASE_ADDRESS: u8 = 0x05;
+pub const DW_RLE_START_END: u8 = 0x06;
+pub const DW_RLE_START_LENGTH: u8 = 0x07;
+
+// Contents of the debug sections, already decompressed and relocated; missing sections are empty
+#[derive(Debug, Default)]
+pub struct DwarfSections {
+    pub debug_info: Vec<u8>,
+    pub debug_abbrev: Vec<u8>,
+    pub debug_str: Vec<u8>,
+    pub debug_str_offsets: Vec<u8>,
+    pub debug_line_str: Vec<u8>,
+    pub debug_line: Vec<u8>,
+    pub debug_addr: Vec<u8>,
+    pub debug_ranges: Vec<u8>,
+    pub debug_rnglists: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    Address(u64),
+    AddressIndex(u64),
+    Reference(u64),
+    Unsigned(u64),
+    Signed(i64),
+    String(String),
+    DebugStr(u64),
+    DebugLineStr(u64),
+    StrIndex(u64),
+    RangeListIndex(u64),
+    Block(usize),
+    Other,
+}
+
+#[derive(Debug)]
+struct AttributeSpec {
+    name: u64,
+    form: u64,
+    implicit_const: i64,
+}
+
+#[derive(Debug)]
+struct Abbreviation {
+    tag: u64,
+    attributes: Vec<AttributeSpec>,
+}
+
+#[derive(Debug)]
+pub struct Function {
+    die_offset: u64,
+    low_pc: u64,
+    high_pc: u64,
+    name: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CompileUnit {
+    offset: u64,
+    version: u16,
+    is_dwarf64: bool,
+    unit_type: u8,
+    address_size: u8,
+    abbrev_offset: u64,
+    die_count: usize,
+    attributes: Vec<(u64, AttributeValue)>,
+    producer: Option<String>,
+    language: Option<u64>,
+    name: Option<String>,
+    comp_dir: Option<String>,
+    functions: Vec<Function>,
+}
+
+impl CompileUnit {
+    pub fn print(&self) {
+        println!("  {}Compilation unit @ offset 0x{:x}: {}DWARF {} ({}-bit), {}, address size {}, abbrev offset 0x{:x}, {} DIEs",
+            termcolors::green(),
+            self.offset,
+            termcolors::gray(),
+            self.version,
+            if self.is_dwarf64 { 64 } else { 32 },
+            unit_type_to_string(self.unit_type),
+            self.address_size,
+            self.abbrev_offset,
+            self.die_count);
+
+        let missing = String::from("<none>");
+        println!("    {}Producer:  {}{}", termcolors::yellow(), termcolors::white(), self.producer.as_ref().unwrap_or(&missing));
+        println!("    {}Language:  {}{}", termcolors::yellow(), termcolors::white(), self.language.map_or(missing.clone(), language_to_string));
+        println!("    {}Name:      {}{}", termcolors::yellow(), termcolors::white(), self.name.as_ref().unwrap_or(&missing));
+        println!("    {}Comp dir:  {}{}", termcolors::yellow(), termcolors::white(), self.comp_dir.as_ref().unwrap_or(&missing));
+    }
+
+    pub fn attribute(&self, name: u64) -> Option<&AttributeValue> {
+        self.attributes.iter().find(|(attribute, _)| *attribute == name).map(|(_, value)| value)
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn comp_dir(&self) -> Option<&String> {
+        self.comp_dir.as_ref()
+    }
+
+    pub fn stmt_list(&self) -> Option<u64> {
+        match self.attribute(DW_AT_STMT_LIST) {
+            Some(AttributeValue::Unsigned(offset)) => Some(*offset),
+            _ => None,
+        }
+    }
+
+    fn address(&self, value: &AttributeValue, sections: &DwarfSections, is_little_endian: bool) -> Option<u64> {
+        match value {
+            AttributeValue::Address(address) => Some(*address),
+            AttributeValue::AddressIndex(index) => {
+                // Without DW_AT_addr_base the table starts right after its own header
+                let base = match self.attribute(DW_AT_ADDR_BASE) {
+                    Some(AttributeValue::Unsigned(base)) => *base,
+                    _ => if self.is_dwarf64 { 16 } else { 8 },
+                };
+                let entry_offset = base.checked_add(index.checked_mul(self.address_size as u64)?)?;
+                DwarfReader::new(&sections.debug_addr, usize::try_from(entry_offset).ok()?, is_little_endian)
+                    .read_address(self.address_size).ok()
+            },
+            _ => None,
+        }
+    }
+
+    // Address ranges of a DW_AT_ranges value: an offset into .debug_ranges before DWARF 5, then an offset
+    // into .debug_rnglists or an index into its offset table. Entries are relative to the unit's DW_AT_low_pc
+    fn ranges(&self, value: &AttributeValue, sections: &DwarfSections, is_little_endian: bool) -> Option<Vec<(u64, u64)>> {
+        let mut base = self.attribute(DW_AT_LOW_PC).and_then(|low_pc| self.address(low_pc, sections, is_little_endian)).unwrap_or(0);
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+
+        if self.version < 5 {
+            let AttributeValue::Unsigned(offset) = value else { return None };
+            let mut reader = DwarfReader::new(&sections.debug_ranges, usize::try_from(*offset).ok()?, is_little_endian);
+            let base_selection = if self.address_size == 4 { u32::MAX as u64 } else { u64::MAX };
+            loop {
+                let start = reader.read_address(self.address_size).ok()?;
+                let end = reader.read_address(self.address_size).ok()?;
+                match (start, end) {
+                    (0, 0) => return Some(ranges),
+                    (start, end) if start == base_selection => base = end,
+                    (start, end) => ranges.push((base.checked_add(start)?, base.checked_add(end)?)),
+                }
+            }
+        }
+
+        let offset = match value {
+            AttributeValue::Unsigned(offset) => *offset,
+            AttributeValue::RangeListIndex(index) => {
+                // Without DW_AT_rnglists_base the offset table starts right after the .debug_rnglists header
+                let rnglists_base = match self.attribute(DW_AT_RNGLISTS_BASE) {
+                    Some(AttributeValue::Unsigned(rnglists_base)) => *rnglists_base,
+                    _ => if self.is_dwarf64 { 20 } else { 12 },
+                };
+                let entry_offset = rnglists_base.checked_add(index.checked_mul(if self.is_dwarf64 { 8 } else { 4 })?)?;
+                let relative_offset = DwarfReader::new(&sections.debug_rnglists, usize::try_from(entry_offset).ok()?, is_little_endian)
+                    .read_offset(self.is_dwarf64).ok()?;
+                rnglists_base.checked_add(relative_offset)?
+            },
+            _ => return None,
+        };
+
+        let mut reader = DwarfReader::new(&sections.debug_rnglists, usize::try_from(offset).ok()?, is_little_endian);
+        let address_at = |index: u64| self.address(&AttributeValue::AddressIndex(index), sections, is_little_endian);
+        loop {
+            match reader.read_u8().ok()? {
+                DW_RLE_END_OF_LIST => return Some(ranges),
+                DW_RLE_BASE_ADDRESSX => base = address_at(reader.read_uleb128().ok()?)?,
+                DW_RLE_BASE_ADDRESS => base = reader.read_address(self.address_size).ok()?,
+                DW_RLE_STARTX_ENDX => {
+                    let start = address_at(reader.read_uleb128().ok()?)?;
+                    ranges.push((start, address_at(reader.read_uleb128().ok()?)?));
+                },
+                DW_RLE_STARTX_LENGTH => {
+                    let start = address_at(reader.read_uleb128().ok()?)?;
+                    ranges.push((start, start.checked_add(reader.read_uleb128().ok()?)?));
+                },
+                DW_RLE_OFFSET_PAIR => {
+                    let start = base.checked_add(reader.read_uleb128().ok()?)?;
+                    ranges.push((start, base.checked_add(reader.read_uleb128().ok()?)?));
+                },
+                DW_RLE_START_END => {
+                    let start = reader.read_address(self.address_size).ok()?;
+                    ranges.push((start, reader.read_address(self.address_size).ok()?));
+                },
+                DW_RLE_START_LENGTH => {
+                    let start = reader.read_address(self.address_size).ok()?;
+                    ranges.push((start, start.checked_add(reader.read_uleb128().ok()?)?));
+                },
+                _ => return None,
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DebugInfo {
+    units: Vec<CompileUnit>,
+}
+
+impl DebugInfo {
+    pub fn build(sections: &DwarfSections, is_little_endian: bool) -> Result<DebugInfo, &'static str> {
+        let mut units: Vec<CompileUnit> = Vec::new();
+        let mut reader = DwarfReader::new(&sections.debug_info, 0, is_little_endian);
+        let mut abbreviation_tables: HashMap<u64, HashMap<u64, Abbreviation>> = HashMap::new();
+
+        while !reader.is_at_end() {
+            let offset = reader.offset() as u64;
+            let (unit_length, is_dwarf64) = reader.read_initial_length()?;
+            let unit_end = reader.offset().checked_add(usize::try_from(unit_length).map_err(|_| "Compilation unit too long")?)
+                .filter(|end| *end <= sections.debug_info.len())
+                .ok_or("Compilation unit exceeds .debug_info")?;
+
+            let version = reader.read_u16()?;
+            let unit_type;
+            let address_size;
+            let abbrev_offset;
+
+            match version {
+                2..=4 => {
+                    unit_type = DW_UT_COMPILE;
+                    abbrev_offset = reader.read_offset(is_dwarf64)?;
+                    address_size = reader.read_u8()?;
+                },
+                5 => {
+                    unit_type = reader.read_u8()?;
+                    address_size = reader.read_u8()?;
+                    abbrev_offset = reader.read_offset(is_dwarf64)?;
+                    match unit_type {
+                        DW_UT_SKELETON | DW_UT_SPLIT_COMPILE => reader.skip(8)?,
+                        DW_UT_TYPE | DW_UT_SPLIT_TYPE => {
+                            reader.skip(8)?;
+                            reader.read_offset(is_dwarf64)?;
+                        },
+                        _ => (),
+                    }
+                },
+                _ => return Err("Unsupported DWARF version"),
+            }
+
+            if let std::collections::hash_map::Entry::Vacant(entry) = abbreviation_tables.entry(abbrev_offset) {
+                entry.insert(parse_abbreviations(&sections.debug_abbrev, abbrev_offset, is_little_endian)?);
+            }
+            let abbreviations = &abbreviation_tables[&abbrev_offset];
+
+            let format = UnitFormat{version, is_dwarf64, address_size, unit_offset: offset};
+            let mut die_count = 0;
+            let mut attributes: Vec<(u64, AttributeValue)> = Vec::new();
+            let mut subprograms: HashMap<u64, Vec<(u64, AttributeValue)>> = HashMap::new();
+
+            while reader.offset() < unit_end {
+                let die_offset = reader.offset() as u64;
+                let code = reader.read_uleb128()?;
+                if code == 0 {
+                    continue;
+                }
+
+                let abbreviation = abbreviations.get(&code).ok_or("DIE refers to a missing abbreviation")?;
+                let mut die_attributes: Vec<(u64, AttributeValue)> = Vec::new();
+                for spec in abbreviation.attributes.iter() {
+                    die_attributes.push((spec.name, read_attribute(&mut reader, spec.form, spec.implicit_const, &format)?));
+                }
+
+                // Besides the unit DIE only subprograms and inlined calls are kept, the rest of the tree is walked to validate and count it
+                if die_count == 0 {
+                    attributes = die_attributes;
+                } else if abbreviation.tag == DW_TAG_SUBPROGRAM || abbreviation.tag == DW_TAG_INLINED_SUBROUTINE {
+                    subprograms.insert(die_offset, die_attributes);
+                }
+                die_count += 1;
+            }
+            reader.set_offset(unit_end);
+
+            let mut unit = CompileUnit{
+                offset,
+                version,
+                is_dwarf64,
+                unit_type,
+                address_size,
+                abbrev_offset,
+                die_count,
+                attributes,
+                producer: None,
+                language: None,
+                name: None,
+                comp_dir: None,
+                functions: Vec::new()};
+
+            let string = |name: u64| unit.attribute(name).and_then(|value| resolve_string(sections, value, &unit, is_little_endian));
+            let (producer, name, comp_dir) = (string(DW_AT_PRODUCER), string(DW_AT_NAME), string(DW_AT_COMP_DIR));
+            unit.producer = producer;
+            unit.name = name;
+            unit.comp_dir = comp_dir;
+            unit.language = match unit.attribute(DW_AT_LANGUAGE) {
+                Some(AttributeValue::Unsigned(value)) => Some(*value),
+                _ => None,
+            };
+            unit.functions = collect_functions(&subprograms, &unit, sections, is_little_endian);
+
+            units.push(unit);
+        }
+
+        Ok(DebugInfo{units})
+    }
+
+    pub fn print(&self) {
+        println!("{}Contents of the .debug_info section contain {} compilation units:{}", termcolors::purple(), self.units.len(), termcolors::default());
+        for unit in self.units.iter() {
+            unit.print();
+            println!();
+        }
+        print!("{}", termcolors::default());
+    }
+
+    pub fn units(&self) -> &[CompileUnit] {
+        &self.units
+    }
+
+    // Innermost function whose [low_pc, high_pc) range contains the address, like addr2line this is the
+    // inlined function rather than its caller. Ties go to the later DIE, which is the one nested deeper
+    pub fn function_name(&self, address: u64) -> Option<&String> {
+        self.units.iter()
+            .flat_map(|unit| unit.functions.iter())
+            .filter(|function| function.low_pc <= address && address < function.high_pc)
+            .min_by_key(|function| (function.high_pc - function.low_pc, std::cmp::Reverse(function.die_offset)))
+            .and_then(|function| function.name.as_ref())
+    }
+}
+
+pub struct UnitFormat {
+    pub version: u16,
+    pub is_dwarf64: bool,
+    pub address_size: u8,
+    pub unit_offset: u64,
+}
+
+fn collect_functions(subprograms: &HashMap<u64, Vec<(u64, AttributeValue)>>, unit: &CompileUnit, sections: &DwarfSections, is_little_endian: bool) -> Vec<Function> {
+    let attribute = |attributes: &Vec<(u64, AttributeValue)>, name: u64| attributes.iter()
+        .find(|(attribute, _)| *attribute == name)
+        .map(|(_, value)| value.clone());
+
+    // Out of line definitions and concrete instances take their names from the declaration or abstract instance
+    let function_name = |attributes: &Vec<(u64, AttributeValue)>| -> Option<String> {
+        let mut current = attributes;
+        for _ in 0..8 {
+            let name = attribute(current, DW_AT_LINKAGE_NAME)
+                .or_else(|| attribute(current, DW_AT_MIPS_LINKAGE_NAME))
+                .or_else(|| attribute(current, DW_AT_NAME));
+            if let Some(name) = name {
+                return resolve_string(sections, &name, unit, is_little_endian);
+            }
+
+            match attribute(current, DW_AT_SPECIFICATION).or_else(|| attribute(current, DW_AT_ABSTRACT_ORIGIN)) {
+                Some(AttributeValue::Reference(offset)) => current = subprograms.get(&offset)?,
+                _ => return None,
+            }
+        }
+        None
+    };
+
+    // Functions split into several pieces, as inlined calls often are, get one entry per DW_AT_ranges range
+    subprograms.iter().flat_map(|(die_offset, attributes)| {
+        let ranges = match attribute(attributes, DW_AT_RANGES) {
+            Some(ranges) => unit.ranges(&ranges, sections, is_little_endian).unwrap_or_default(),
+            None => (|| {
+                let low_pc = unit.address(&attribute(attributes, DW_AT_LOW_PC)?, sections, is_little_endian)?;
+                // DWARF 4 allows DW_AT_high_pc to be encoded as a length relative to DW_AT_low_pc
+                let high_pc = match attribute(attributes, DW_AT_HIGH_PC)? {
+                    AttributeValue::Unsigned(length) => low_pc.checked_add(length)?,
+                    value => unit.address(&value, sections, is_little_endian)?,
+                };
+                Some(vec![(low_pc, high_pc)])
+            })().unwrap_or_default(),
+        };
+        let name = function_name(attributes);
+        ranges.into_iter().map(move |(low_pc, high_pc)| Function{die_offset: *die_offset, low_pc, high_pc, name: name.clone()})
+    }).collect()
+}
+
+fn parse_abbreviations(data: &[u8], offset: u64, is_little_endian: bool) -> Result<HashMap<u64, Abbreviation>, &'static str> {
+    let mut reader = DwarfReader::new(data, usize::try_from(offset).map_err(|_| "Abbreviation offset too large")?, is_little_endian);
+    let mut abbreviations: HashMap<u64, Abbreviation> = HashMap::new();
+
+    loop {
+        let code = reader.read_uleb128()?;
+        if code == 0 {
+            return Ok(abbreviations);
+        }
+
+        // The children flag is not needed, null entries already close every sibling list
+        let tag = reader.read_uleb128()?;
+        let _has_children = reader.read_u8()?;
+        let mut attributes: Vec<AttributeSpec> = Vec::new();
+
+        loop {
+            let name = reader.read_uleb128()?;
+            let form = reader.read_uleb128()?;
+            if name == 0 && form == 0 {
+                break;
+            }
+
+            let implicit_const = if form == DW_FORM_IMPLICIT_CONST { reader.read_sleb128()? } else { 0 };
+            attributes.push(AttributeSpec{name, form, implicit_const});
+        }
+
+        abbreviations.insert(code, Abbreviation{tag, attributes});
+    }
+}
+
+pub fn read_attribute(reader: &mut DwarfReader, form: u64, implicit_const: i64, format: &UnitFormat) -> Result<AttributeValue, &'static str> {
+    let value = match form {
+        DW_FORM_ADDR => AttributeValue::Address(reader.read_address(format.address_size)?),
+        DW_FORM_DATA1 | DW_FORM_FLAG => AttributeValue::Unsigned(reader.read_u8()? as u64),
+        DW_FORM_DATA2 => AttributeValue::Unsigned(reader.read_u16()? as u64),
+        DW_FORM_DATA4 | DW_FORM_REF_SUP4 => AttributeValue::Unsigned(reader.read_u32()? as u64),
+        DW_FORM_DATA8 | DW_FORM_REF_SIG8 | DW_FORM_REF_SUP8 => AttributeValue::Unsigned(reader.read_u64()?),
+        // Unit relative references are turned into .debug_info offsets
+        DW_FORM_REF1 => AttributeValue::Reference(format.unit_offset + reader.read_u8()? as u64),
+        DW_FORM_REF2 => AttributeValue::Reference(format.unit_offset + reader.read_u16()? as u64),
+        DW_FORM_REF4 => AttributeValue::Reference(format.unit_offset + reader.read_u32()? as u64),
+        DW_FORM_REF8 => AttributeValue::Reference(format.unit_offset.wrapping_add(reader.read_u64()?)),
+        DW_FORM_REF_UDATA => AttributeValue::Reference(format.unit_offset.wrapping_add(reader.read_uleb128()?)),
+        DW_FORM_DATA16 => {
+            reader.skip(16)?;
+            AttributeValue::Block(16)
+        },
+        DW_FORM_SDATA => AttributeValue::Signed(reader.read_sleb128()?),
+        DW_FORM_UDATA => AttributeValue::Unsigned(reader.read_uleb128()?),
+        DW_FORM_STRING => AttributeValue::String(reader.read_cstr()?),
+        DW_FORM_STRP => AttributeValue::DebugStr(reader.read_offset(format.is_dwarf64)?),
+        DW_FORM_LINE_STRP => AttributeValue::DebugLineStr(reader.read_offset(format.is_dwarf64)?),
+        DW_FORM_STRP_SUP | DW_FORM_GNU_STRP_ALT | DW_FORM_GNU_REF_ALT => {
+            reader.read_offset(format.is_dwarf64)?;
+            AttributeValue::Other
+        },
+        // DWARF 2 sized DW_FORM_ref_addr like an address, later versions like an offset
+        DW_FORM_REF_ADDR if format.version == 2 => AttributeValue::Reference(reader.read_address(format.address_size)?),
+        DW_FORM_REF_ADDR => AttributeValue::Reference(reader.read_offset(format.is_dwarf64)?),
+        DW_FORM_SEC_OFFSET => AttributeValue::Unsigned(reader.read_offset(format.is_dwarf64)?),
+        DW_FORM_STRX | DW_FORM_GNU_STR_INDEX => AttributeValue::StrIndex(reader.read_uleb128()?),
+        DW_FORM_STRX1 => AttributeValue::StrIndex(reader.read_u8()? as u64),
+        DW_FORM_STRX2 => AttributeValue::StrIndex(reader.read_u16()? as u64),
+        DW_FORM_STRX3 => AttributeValue::StrIndex(reader.read_u24()? as u64),
+        DW_FORM_STRX4 => AttributeValue::StrIndex(reader.read_u32()? as u64),
+        DW_FORM_ADDRX | DW_FORM_GNU_ADDR_INDEX => AttributeValue::AddressIndex(reader.read_uleb128()?),
+        DW_FORM_ADDRX1 => AttributeValue::AddressIndex(reader.read_u8()? as u64),
+        DW_FORM_ADDRX2 => AttributeValue::AddressIndex(reader.read_u16()? as u64),
+        DW_FORM_ADDRX3 => AttributeValue::AddressIndex(reader.read_u24()? as u64),
+        DW_FORM_ADDRX4 => AttributeValue::AddressIndex(reader.read_u32()? as u64),
+        DW_FORM_LOCLISTX => AttributeValue::Unsigned(reader.read_uleb128()?),
+        DW_FORM_RNGLISTX => AttributeValue::RangeListIndex(reader.read_uleb128()?),
+        DW_FORM_FLAG_PRESENT => AttributeValue::Unsigned(1),
+        DW_FORM_IMPLICIT_CONST => AttributeValue::Signed(implicit_const),
+        DW_FORM_BLOCK1 => read_block(reader, |reader| Ok(reader.read_u8()? as u64))?,
+        DW_FORM_BLOCK2 => read_block(reader, |reader| Ok(reader.read_u16()? as u64))?,
+        DW_FORM_BLOCK4 => read_block(reader, |reader| Ok(reader.read_u32()? as u64))?,
+        DW_FORM_BLOCK | DW_FORM_EXPRLOC => read_block(reader, |reader| reader.read_uleb128())?,
+        DW_FORM_INDIRECT => {
+            let form = reader.read_uleb128()?;
+            if form == DW_FORM_INDIRECT {
+                return Err("Nested DW_FORM_indirect");
+            }
+            read_attribute(reader, form, 0, format)?
+        },
+        _ => return Err("Unsupported DWARF attribute form"),
+    };
+
+    Ok(value)
+}
+
+fn read_block(reader: &mut DwarfReader, length: impl Fn(&mut DwarfReader) -> Result<u64, &'static str>) -> Result<AttributeValue, &'static str> {
+    let length = length(reader)?;
+    reader.skip(length)?;
+    Ok(AttributeValue::Block(length as usize))
+}
+
+fn string_from_table(table: &[u8], offset: u64, is_little_endian: bool) -> Option<String> {
+    DwarfReader::new(table, usize::try_from(offset).ok()?, is_little_endian).read_cstr().ok()
+}
+
+// Strings that do not depend on unit attributes, as used by line program headers
+pub fn string_value(sections: &DwarfSections, value: &AttributeValue, is_little_endian: bool) -> Option<String> {
+    match value {
+        AttributeValue::String(string) => Some(string.clone()),
+        AttributeValue::DebugStr(offset) => string_from_table(&sections.debug_str, *offset, is_little_endian),
+        AttributeValue::DebugLineStr(offset) => string_from_table(&sections.debug_line_str, *offset, is_little_endian),
+        _ => None,
+    }
+}
+
+fn resolve_string(sections: &DwarfSections, value: &AttributeValue, unit: &CompileUnit, is_little_endian: bool) -> Option<String> {
+    match value {
+        AttributeValue::StrIndex(index) => {
+            // Without DW_AT_str_offsets_base the table starts right after its own header
+            let offset_size = if unit.is_dwarf64 { 8 } else { 4 };
+            let base = match unit.attribute(DW_AT_STR_OFFSETS_BASE) {
+                Some(AttributeValue::Unsigned(base)) => *base,
+                _ => if unit.is_dwarf64 { 16 } else { 8 },
+            };
+            let entry_offset = base.checked_add(index.checked_mul(offset_size)?)?;
+            let mut reader = DwarfReader::new(&sections.debug_str_offsets, usize::try_from(entry_offset).ok()?, is_little_endian);
+            string_from_table(&sections.debug_str, reader.read_offset(unit.is_dwarf64).ok()?, is_little_endian)
+        },
+        _ => string_value(sections, value, is_little_endian),
+    }
+}
+
+fn unit_type_to_string(unit_type: u8) -> String {
+    match unit_type {
+        DW_UT_COMPILE => String::from("DW_UT_compile"),
+        DW_UT_TYPE => String::from("DW_UT_type"),
+        DW_UT_PARTIAL => String::from("DW_UT_partial"),
+        DW_UT_SKELETON => String::from("DW_UT_skeleton"),
+        DW_UT_SPLIT_COMPILE => String::from("DW_UT_split_compile"),
+        DW_UT_SPLIT_TYPE => String::from("DW_UT_split_type"),
+        value => format!("<unknown unit type: 0x{:x}>", value),
+    }
+}
+
+pub fn language_to_string(language: u64) -> String {
+    let name = match language {
+        0x0001 => "C89",
+        0x0002 => "C",
+        0x0003 => "Ada83",
+        0x0004 => "C_plus_plus",
+        0x0005 => "Cobol74",
+        0x0006 => "Cobol85",
+        0x0007 => "Fortran77",
+        0x0008 => "Fortran90",
+        0x0009 => "Pascal83",
+        0x000A => "Modula2",
+        0x000B => "Java",
+        0x000C => "C99",
+        0x000D => "Ada95",
+        0x000E => "Fortran95",
+        0x000F => "PLI",
+        0x0010 => "ObjC",
+        0x0011 => "ObjC_plus_plus",
+        0x0012 => "UPC",
+        0x0013 => "D",
+        0x0014 => "Python",
+        0x0015 => "OpenCL",
+        0x0016 => "Go",
+        0x0017 => "Modula3",
+        0x0018 => "Haskell",
+        0x0019 => "C_plus_plus_03",
+        0x001A => "C_plus_plus_11",
+        0x001B => "OCaml",
+        0x001C => "Rust",
+        0x001D => "C11",
+        0x001E => "Swift",
+        0x001F => "Julia",
+        0x0020 => "Dylan",
+        0x0021 => "C_plus_plus_14",
+        0x0022 => "Fortran03",
+        0x0023 => "Fortran08",
+        0x0024 => "RenderScript",
+        0x0025 => "BLISS",
+        0x0026 => "Kotlin",
+        0x0027 => "Zig",
+        0x0028 => "Crystal",
+        0x002A => "C_plus_plus_17",
+        0x002B => "C_plus_plus_20",
+        0x002C => "C17",
+        0x002D => "Fortran18",
+        0x002E => "Ada2005",
+        0x002F => "Ada2012",
+        0x0030 => "HIP",
+        0x0031 => "Assembly",
+        0x0032 => "C_sharp",
+        0x0033 => "Mojo",
+        0x8001 => "Mips_Assembler",
+        _ => return format!("<unknown language: 0x{:x}>", language),
+    };
+
+    format!("DW_LANG_{} (0x{:x})", name, language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dwarf5_sections() -> DwarfSections {
+        // Abbreviation 1: DW_TAG_compile_unit with children, producer as strp, language as data1,
+        // name as strx1 and comp_dir as line_strp. Abbreviation 2: DW_TAG_base_type without attributes.
+        let debug_abbrev = vec![
+            0x01, 0x11, 0x01,
+            0x25, 0x0E, 0x13, 0x0B, 0x03, 0x25, 0x1B, 0x1F, 0x72, 0x17,
+            0x00, 0x00,
+            0x02, 0x24, 0x00, 0x00, 0x00,
+            0x00,
+        ];
+        let mut debug_info = vec![
+            0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x01, 0x08, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x1D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+            0x02,
+            0x00,
+        ];
+        let length = debug_info.len() as u32 - 4;
+        debug_info[0..4].copy_from_slice(&length.to_le_bytes());
+
+        DwarfSections{
+            debug_info,
+            debug_abbrev,
+            debug_str: b"GNU C17\0main.c\0".to_vec(),
+            debug_str_offsets: vec![0x08, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00],
+            debug_line_str: b"/tmp\0".to_vec(),
+            ..Default::default()}
+    }
+
+    #[test]
+    fn debug_info_should_list_dwarf5_unit_attributes() {
+        let sections = dwarf5_sections();
+        let info = DebugInfo::build(&sections, true).expect("Should parse .debug_info");
+        assert_eq!(info.units.len(), 1);
+
+        let unit = &info.units[0];
+        assert_eq!(unit.version, 5);
+        assert_eq!(unit.die_count, 2);
+        assert_eq!(unit.producer, Some(String::from("GNU C17")));
+        assert_eq!(unit.language, Some(0x1D));
+        assert_eq!(unit.name, Some(String::from("main.c")));
+        assert_eq!(unit.comp_dir, Some(String::from("/tmp")));
+    }
+
+    #[test]
+    fn debug_info_should_reject_truncated_unit() {
+        let mut sections = dwarf5_sections();
+        sections.debug_info.truncate(20);
+        assert!(DebugInfo::build(&sections, true).is_err());
+    }
+
+    #[test]
+    fn language_to_string_should_name_known_languages() {
+        assert_eq!(language_to_string(0x1C), "DW_LANG_Rust (0x1c)");
+        assert_eq!(language_to_string(0x4242), "<unknown language: 0x4242>");
+    }
+
+    #[test]
+    fn function_name_should_prefer_inlined_calls() {
+        // Abbreviation 1: DW_TAG_compile_unit with low_pc. 2: DW_TAG_subprogram with name, low_pc and high_pc as
+        // a length. 3: abstract DW_TAG_subprogram with a name only. 4: DW_TAG_inlined_subroutine with
+        // abstract_origin and ranges as sec_offset
+        let debug_abbrev = vec![
+            0x01, 0x11, 0x01, 0x11, 0x01, 0x00, 0x00,
+            0x02, 0x2E, 0x00, 0x03, 0x08, 0x11, 0x01, 0x12, 0x06, 0x00, 0x00,
+            0x03, 0x2E, 0x00, 0x03, 0x08, 0x00, 0x00,
+            0x04, 0x1D, 0x00, 0x31, 0x13, 0x55, 0x17, 0x00, 0x00,
+            0x00,
+        ];
+        let mut debug_info = vec![0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x01, 0x08, 0x00, 0x00, 0x00, 0x00];
+        debug_info.push(0x01);
+        debug_info.extend(0x1000u64.to_le_bytes());
+        debug_info.push(0x02);
+        debug_info.extend(b"outer\0");
+        debug_info.extend(0x1000u64.to_le_bytes());
+        debug_info.extend(0x100u32.to_le_bytes());
+        debug_info.push(0x03);
+        debug_info.extend(b"inner\0");
+        debug_info.push(0x04);
+        debug_info.extend(40u32.to_le_bytes());
+        debug_info.extend(12u32.to_le_bytes());
+        debug_info.push(0x00);
+        let length = debug_info.len() as u32 - 4;
+        debug_info[0..4].copy_from_slice(&length.to_le_bytes());
+
+        let debug_rnglists = vec![
+            0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00,
+            DW_RLE_OFFSET_PAIR, 0x40, 0x50,
+            DW_RLE_OFFSET_PAIR, 0x80, 0x01, 0x90, 0x01,
+            DW_RLE_END_OF_LIST,
+        ];
+
+        let sections = DwarfSections{debug_info, debug_abbrev, debug_rnglists, ..Default::default()};
+        let info = DebugInfo::build(&sections, true).expect("Should parse .debug_info");
+        assert_eq!(info.function_name(0x1044), Some(&String::from("inner")));
+        assert_eq!(info.function_name(0x1060), Some(&String::from("outer")));
+        assert_eq!(info.function_name(0x1084), Some(&String::from("inner")));
+        assert_eq!(info.function_name(0x2000), None);
+    }
+}
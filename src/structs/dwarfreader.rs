@@ -0,0 +1,164 @@
+use crate::bits::*;
+
+// Cursor over a DWARF section; every read is bounds checked and advances the offset
+#[derive(Debug, Clone)]
+pub struct DwarfReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    is_little_endian: bool,
+}
+
+impl<'a> DwarfReader<'a> {
+    pub fn new(data: &'a [u8], offset: usize, is_little_endian: bool) -> DwarfReader<'a> {
+        DwarfReader{data, offset, is_little_endian}
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    pub fn read_bytes(&mut self, size: usize) -> Result<&'a [u8], &'static str> {
+        let end = self.offset.checked_add(size).ok_or("DWARF data size overflows")?;
+        let bytes = self.data.get(self.offset..end).ok_or("Unexpected end of DWARF data")?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    pub fn skip(&mut self, size: u64) -> Result<(), &'static str> {
+        self.read_bytes(usize::try_from(size).map_err(|_| "DWARF data size overflows")?)?;
+        Ok(())
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, &'static str> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, &'static str> {
+        Ok(to_u16_from_slice(self.read_bytes(2)?.try_into().unwrap(), self.is_little_endian))
+    }
+
+    pub fn read_u24(&mut self) -> Result<u32, &'static str> {
+        let bytes = self.read_bytes(3)?;
+        Ok(match self.is_little_endian {
+            true => to_u32(0, bytes[2], bytes[1], bytes[0]),
+            false => to_u32(0, bytes[0], bytes[1], bytes[2]),
+        })
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, &'static str> {
+        Ok(to_u32_from_slice(self.read_bytes(4)?.try_into().unwrap(), self.is_little_endian))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, &'static str> {
+        Ok(to_u64_from_slice(self.read_bytes(8)?.try_into().unwrap(), self.is_little_endian))
+    }
+
+    pub fn read_uleb128(&mut self) -> Result<u64, &'static str> {
+        let mut result: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift < 64 {
+                result |= ((byte & 0x7F) as u64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+    }
+
+    pub fn read_sleb128(&mut self) -> Result<i64, &'static str> {
+        let mut result: i64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift < 64 {
+                result |= ((byte & 0x7F) as i64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    result |= -1i64 << shift;
+                }
+                return Ok(result);
+            }
+        }
+    }
+
+    // Returns the unit length and whether the unit uses the 64-bit DWARF format
+    pub fn read_initial_length(&mut self) -> Result<(u64, bool), &'static str> {
+        match self.read_u32()? {
+            0xFFFFFFFF => Ok((self.read_u64()?, true)),
+            length if length >= 0xFFFFFFF0 => Err("Reserved DWARF unit length"),
+            length => Ok((length as u64, false)),
+        }
+    }
+
+    pub fn read_offset(&mut self, is_dwarf64: bool) -> Result<u64, &'static str> {
+        match is_dwarf64 {
+            true => self.read_u64(),
+            false => Ok(self.read_u32()? as u64),
+        }
+    }
+
+    pub fn read_address(&mut self, address_size: u8) -> Result<u64, &'static str> {
+        match address_size {
+            1 => Ok(self.read_u8()? as u64),
+            2 => Ok(self.read_u16()? as u64),
+            4 => Ok(self.read_u32()? as u64),
+            8 => self.read_u64(),
+            _ => Err("Unsupported DWARF address size"),
+        }
+    }
+
+    pub fn read_cstr(&mut self) -> Result<String, &'static str> {
+        let remaining = self.data.get(self.offset..).ok_or("Unexpected end of DWARF data")?;
+        let length = remaining.iter().position(|&b| b == 0).ok_or("Unterminated DWARF string")?;
+        let bytes = self.read_bytes(length + 1)?;
+        Ok(String::from_utf8_lossy(&bytes[..length]).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dwarf_reader_should_decode_leb128() {
+        let data = [0xE5, 0x8E, 0x26, 0x7F, 0x80, 0x7F, 0x02];
+        let mut reader = DwarfReader::new(&data, 0, true);
+        assert_eq!(reader.read_uleb128(), Ok(624485));
+        assert_eq!(reader.read_sleb128(), Ok(-1));
+        assert_eq!(reader.read_sleb128(), Ok(-128));
+        assert_eq!(reader.read_sleb128(), Ok(2));
+        assert!(reader.is_at_end());
+        assert!(reader.read_u8().is_err());
+    }
+
+    #[test]
+    fn dwarf_reader_should_detect_64bit_initial_length() {
+        let data = [0xFF, 0xFF, 0xFF, 0xFF, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00];
+        let mut reader = DwarfReader::new(&data, 0, true);
+        assert_eq!(reader.read_initial_length(), Ok((0x10, true)));
+
+        let mut reader = DwarfReader::new(&data, 12, true);
+        assert_eq!(reader.read_initial_length(), Ok((0x1234, false)));
+    }
+
+    #[test]
+    fn dwarf_reader_should_read_strings_and_big_endian_values() {
+        let data = [0x61, 0x62, 0x00, 0x01, 0x02, 0x03];
+        let mut reader = DwarfReader::new(&data, 0, false);
+        assert_eq!(reader.read_cstr(), Ok(String::from("ab")));
+        assert_eq!(reader.read_u24(), Ok(0x010203));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::bits::*;
+use crate::elffile::Source;
+use crate::structs::programheader::ProgramHeader;
+use crate::structs::sectionheader::string_until_null;
+use crate::structs::word::Word;
+use crate::termcolors;
+
+use super::dynamicflags;
+use super::dynamictag::DynamicTag;
+
+#[derive(Debug)]
+pub struct DynamicEntry {
+    d_tag: DynamicTag,
+    d_tag_raw: u64,
+    d_val: Word,
+    d_val_str: Option<String>,
+}
+
+impl DynamicEntry {
+    pub fn print(&self) {
+        let tag_width = match self.d_val {
+            Word::Bits32(_) => 8,
+            Word::Bits64(_) => 16,
+        };
+
+        println!("{}0x{:0tag_width$x} {}{:20} {}{}",
+            termcolors::green(),
+            self.d_tag_raw,
+            termcolors::yellow(),
+            self.d_tag,
+            termcolors::white(),
+            self.value_to_string());
+    }
+
+    fn value_to_string(&self) -> String {
+        let value = self.d_val.to_u64().unwrap();
+
+        if let Some(string) = &self.d_val_str {
+            return match self.d_tag {
+                DynamicTag::DtNeeded    => format!("Shared library: [{}]", string),
+                DynamicTag::DtSoname    => format!("Library soname: [{}]", string),
+                DynamicTag::DtRpath     => format!("Library rpath: [{}]", string),
+                DynamicTag::DtRunpath   => format!("Library runpath: [{}]", string),
+                DynamicTag::DtAuxiliary => format!("Auxiliary library: [{}]", string),
+                DynamicTag::DtFilter    => format!("Filter library: [{}]", string),
+                DynamicTag::DtConfig    => format!("Configuration file: [{}]", string),
+                DynamicTag::DtDepaudit  => format!("Dependency audit library: [{}]", string),
+                DynamicTag::DtAudit     => format!("Audit library: [{}]", string),
+                _ => string.clone(),
+            };
+        }
+
+        match self.d_tag {
+            DynamicTag::DtFlags => dynamicflags::flags_to_string(value),
+            DynamicTag::DtFlags1 => dynamicflags::flags_1_to_string(value),
+            DynamicTag::DtPltrel => match value {
+                0x07 => String::from("RELA"),
+                0x11 => String::from("REL"),
+                _ => format!("0x{:x}", value),
+            },
+            DynamicTag::DtPltrelsz
+            | DynamicTag::DtRelasz
+            | DynamicTag::DtRelaent
+            | DynamicTag::DtStrsz
+            | DynamicTag::DtSyment
+            | DynamicTag::DtRelsz
+            | DynamicTag::DtRelent
+            | DynamicTag::DtInitArraysz
+            | DynamicTag::DtFiniArraysz
+            | DynamicTag::DtPreinitArraysz
+            | DynamicTag::DtRelrsz
+            | DynamicTag::DtRelrent
+            | DynamicTag::DtGnuConflictsz
+            | DynamicTag::DtGnuLiblistsz
+            | DynamicTag::DtPltpadsz
+            | DynamicTag::DtMoveent
+            | DynamicTag::DtMovesz
+            | DynamicTag::DtSyminsz
+            | DynamicTag::DtSyminent => format!("{} (bytes)", value),
+            DynamicTag::DtVerdefnum
+            | DynamicTag::DtVerneednum
+            | DynamicTag::DtRelacount
+            | DynamicTag::DtRelcount => format!("{}", value),
+            _ => format!("0x{:x}", value),
+        }
+    }
+
+    fn is_string_valued(&self) -> bool {
+        matches!(self.d_tag,
+            DynamicTag::DtNeeded |
+            DynamicTag::DtSoname |
+            DynamicTag::DtRpath |
+            DynamicTag::DtRunpath |
+            DynamicTag::DtAuxiliary |
+            DynamicTag::DtFilter |
+            DynamicTag::DtConfig |
+            DynamicTag::DtDepaudit |
+            DynamicTag::DtAudit)
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicSection {
+    offset: u64,
+    entries: Vec<DynamicEntry>,
+}
+
+impl DynamicSection {
+    pub fn build(source: Source, offset: u64, size: u64, program_header: &ProgramHeader, is_32bit: bool, is_little_endian: bool) -> Result<DynamicSection, &'static str> {
+        let payload = source.read_at(offset, size).map_err(|_| "Dynamic section too short")?;
+
+        let entry_size = if is_32bit { 0x08 } else { 0x10 };
+        let mut entries: Vec<DynamicEntry> = Vec::new();
+
+        for entry_offset in (0..payload.len() - payload.len() % entry_size).step_by(entry_size) {
+            let d_tag_raw;
+            let d_val;
+
+            if is_32bit {
+                d_tag_raw = to_u32_from_slice(&payload[entry_offset..entry_offset+4].try_into().unwrap(), is_little_endian) as u64;
+                d_val = Word::Bits32(to_u32_from_slice(&payload[entry_offset+4..entry_offset+8].try_into().unwrap(), is_little_endian));
+            } else {
+                d_tag_raw = to_u64_from_slice(&payload[entry_offset..entry_offset+8].try_into().unwrap(), is_little_endian);
+                d_val = Word::Bits64(to_u64_from_slice(&payload[entry_offset+8..entry_offset+16].try_into().unwrap(), is_little_endian));
+            }
+
+            let d_tag = DynamicTag::from_u64(d_tag_raw);
+
+            entries.push(DynamicEntry{d_tag, d_tag_raw, d_val, d_val_str: None});
+
+            if d_tag == DynamicTag::DtNull {
+                break;
+            }
+        }
+
+        // String values are offsets into DT_STRTAB, which is only known by its virtual address
+        let find_value = |tag: DynamicTag| entries.iter().find(|entry| entry.d_tag == tag).map(|entry| entry.d_val.to_u64().unwrap());
+        let string_table = find_value(DynamicTag::DtStrtab)
+            .and_then(|address| program_header.read_virtual(address, find_value(DynamicTag::DtStrsz).unwrap_or(0)));
+
+        if let Some(strings) = string_table {
+            for entry in entries.iter_mut().filter(|entry| entry.is_string_valued()) {
+                let name_offset = entry.d_val.to_u64().unwrap() as usize;
+                if name_offset < strings.len() {
+                    entry.d_val_str = Some(string_until_null(&strings[name_offset..]));
+                }
+            }
+        }
+
+        Ok(DynamicSection{offset, entries})
+    }
+
+    pub fn print(&self) {
+        println!("{}Dynamic section at offset 0x{:x} contains {} entries:{}", termcolors::purple(), self.offset, self.entries.len(), termcolors::default());
+        println!("  {}Tag\t\t     {}Type\t\t  {}Name/Value",
+            termcolors::green(),
+            termcolors::yellow(),
+            termcolors::white());
+        for entry in self.entries.iter() {
+            print!(" ");
+            entry.print();
+        }
+        print!("{}", termcolors::default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dynamic_tag_should_be_decoded_from_value() {
+        assert_eq!(DynamicTag::from_u64(0x01), DynamicTag::DtNeeded);
+        assert_eq!(DynamicTag::from_u64(0x6FFFFEF5), DynamicTag::DtGnuHash);
+        assert_eq!(DynamicTag::from_u64(0x6FFFFFFB), DynamicTag::DtFlags1);
+        assert_eq!(DynamicTag::from_u64(0x60000010), DynamicTag::DtOs(0x60000010));
+        assert_eq!(DynamicTag::from_u64(0x40), DynamicTag::DtUnknown(0x40));
+    }
+
+    #[test]
+    fn dynamic_entry_should_describe_sizes_and_counts() {
+        let entry = DynamicEntry{d_tag: DynamicTag::DtStrsz, d_tag_raw: 0x0A, d_val: Word::Bits64(136), d_val_str: None};
+        assert_eq!(entry.value_to_string(), "136 (bytes)");
+
+        let entry = DynamicEntry{d_tag: DynamicTag::DtVerneednum, d_tag_raw: 0x6FFFFFFF, d_val: Word::Bits64(1), d_val_str: None};
+        assert_eq!(entry.value_to_string(), "1");
+
+        let entry = DynamicEntry{d_tag: DynamicTag::DtNeeded, d_tag_raw: 0x01, d_val: Word::Bits64(1), d_val_str: Some(String::from("libc.so.6"))};
+        assert_eq!(entry.value_to_string(), "Shared library: [libc.so.6]");
+    }
+}
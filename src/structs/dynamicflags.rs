@@ -0,0 +1,112 @@
+pub const DF_ORIGIN: u64 = 0x1;
+pub const DF_SYMBOLIC: u64 = 0x2;
+pub const DF_TEXTREL: u64 = 0x4;
+pub const DF_BIND_NOW: u64 = 0x8;
+pub const DF_STATIC_TLS: u64 = 0x10;
+
+pub const DF_1_NOW: u64 = 0x1;
+pub const DF_1_GLOBAL: u64 = 0x2;
+pub const DF_1_GROUP: u64 = 0x4;
+pub const DF_1_NODELETE: u64 = 0x8;
+pub const DF_1_LOADFLTR: u64 = 0x10;
+pub const DF_1_INITFIRST: u64 = 0x20;
+pub const DF_1_NOOPEN: u64 = 0x40;
+pub const DF_1_ORIGIN: u64 = 0x80;
+pub const DF_1_DIRECT: u64 = 0x100;
+pub const DF_1_TRANS: u64 = 0x200;
+pub const DF_1_INTERPOSE: u64 = 0x400;
+pub const DF_1_NODEFLIB: u64 = 0x800;
+pub const DF_1_NODUMP: u64 = 0x1000;
+pub const DF_1_CONFALT: u64 = 0x2000;
+pub const DF_1_ENDFILTEE: u64 = 0x4000;
+pub const DF_1_DISPRELDNE: u64 = 0x8000;
+pub const DF_1_DISPRELPND: u64 = 0x10000;
+pub const DF_1_NODIRECT: u64 = 0x20000;
+pub const DF_1_IGNMULDEF: u64 = 0x40000;
+pub const DF_1_NOKSYMS: u64 = 0x80000;
+pub const DF_1_NOHDR: u64 = 0x100000;
+pub const DF_1_EDITED: u64 = 0x200000;
+pub const DF_1_NORELOC: u64 = 0x400000;
+pub const DF_1_SYMINTPOSE: u64 = 0x800000;
+pub const DF_1_GLOBAUDIT: u64 = 0x1000000;
+pub const DF_1_SINGLETON: u64 = 0x2000000;
+pub const DF_1_STUB: u64 = 0x4000000;
+pub const DF_1_PIE: u64 = 0x8000000;
+
+const FLAGS: [(u64, &str); 5] = [
+    (DF_ORIGIN, "ORIGIN"),
+    (DF_SYMBOLIC, "SYMBOLIC"),
+    (DF_TEXTREL, "TEXTREL"),
+    (DF_BIND_NOW, "BIND_NOW"),
+    (DF_STATIC_TLS, "STATIC_TLS"),
+];
+
+const FLAGS_1: [(u64, &str); 28] = [
+    (DF_1_NOW, "NOW"),
+    (DF_1_GLOBAL, "GLOBAL"),
+    (DF_1_GROUP, "GROUP"),
+    (DF_1_NODELETE, "NODELETE"),
+    (DF_1_LOADFLTR, "LOADFLTR"),
+    (DF_1_INITFIRST, "INITFIRST"),
+    (DF_1_NOOPEN, "NOOPEN"),
+    (DF_1_ORIGIN, "ORIGIN"),
+    (DF_1_DIRECT, "DIRECT"),
+    (DF_1_TRANS, "TRANS"),
+    (DF_1_INTERPOSE, "INTERPOSE"),
+    (DF_1_NODEFLIB, "NODEFLIB"),
+    (DF_1_NODUMP, "NODUMP"),
+    (DF_1_CONFALT, "CONFALT"),
+    (DF_1_ENDFILTEE, "ENDFILTEE"),
+    (DF_1_DISPRELDNE, "DISPRELDNE"),
+    (DF_1_DISPRELPND, "DISPRELPND"),
+    (DF_1_NODIRECT, "NODIRECT"),
+    (DF_1_IGNMULDEF, "IGNMULDEF"),
+    (DF_1_NOKSYMS, "NOKSYMS"),
+    (DF_1_NOHDR, "NOHDR"),
+    (DF_1_EDITED, "EDITED"),
+    (DF_1_NORELOC, "NORELOC"),
+    (DF_1_SYMINTPOSE, "SYMINTPOSE"),
+    (DF_1_GLOBAUDIT, "GLOBAUDIT"),
+    (DF_1_SINGLETON, "SINGLETON"),
+    (DF_1_STUB, "STUB"),
+    (DF_1_PIE, "PIE"),
+];
+
+fn known_flags_to_string(flags: u64, known: &[(u64, &str)]) -> String {
+    let mut result: Vec<String> = known.iter()
+        .filter(|(mask, _)| flags & mask != 0)
+        .map(|(_, name)| String::from(*name))
+        .collect();
+
+    let unknown = known.iter().fold(flags, |rest, (mask, _)| rest & !mask);
+    if unknown != 0 {
+        result.push(format!("0x{:X}", unknown));
+    }
+
+    result.join(" ")
+}
+
+pub fn flags_to_string(flags: u64) -> String {
+    known_flags_to_string(flags, &FLAGS)
+}
+
+pub fn flags_1_to_string(flags: u64) -> String {
+    format!("Flags: {}", known_flags_to_string(flags, &FLAGS_1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_to_string_should_list_set_flags() {
+        assert_eq!(flags_to_string(DF_BIND_NOW), "BIND_NOW");
+        assert_eq!(flags_to_string(DF_ORIGIN | DF_TEXTREL), "ORIGIN TEXTREL");
+    }
+
+    #[test]
+    fn flags_1_to_string_should_report_unknown_bits() {
+        assert_eq!(flags_1_to_string(DF_1_NOW | DF_1_PIE), "Flags: NOW PIE");
+        assert_eq!(flags_1_to_string(0x80000000), "Flags: 0x80000000");
+    }
+}
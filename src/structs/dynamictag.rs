@@ -0,0 +1,242 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DynamicTag {
+    DtNull,
+    DtNeeded,
+    DtPltrelsz,
+    DtPltgot,
+    DtHash,
+    DtStrtab,
+    DtSymtab,
+    DtRela,
+    DtRelasz,
+    DtRelaent,
+    DtStrsz,
+    DtSyment,
+    DtInit,
+    DtFini,
+    DtSoname,
+    DtRpath,
+    DtSymbolic,
+    DtRel,
+    DtRelsz,
+    DtRelent,
+    DtPltrel,
+    DtDebug,
+    DtTextrel,
+    DtJmprel,
+    DtBindNow,
+    DtInitArray,
+    DtFiniArray,
+    DtInitArraysz,
+    DtFiniArraysz,
+    DtRunpath,
+    DtFlags,
+    DtPreinitArray,
+    DtPreinitArraysz,
+    DtSymtabShndx,
+    DtRelrsz,
+    DtRelr,
+    DtRelrent,
+    DtGnuPrelinked,
+    DtGnuConflictsz,
+    DtGnuLiblistsz,
+    DtChecksum,
+    DtPltpadsz,
+    DtMoveent,
+    DtMovesz,
+    DtFeature1,
+    DtPosflag1,
+    DtSyminsz,
+    DtSyminent,
+    DtGnuHash,
+    DtTlsdescPlt,
+    DtTlsdescGot,
+    DtGnuConflict,
+    DtGnuLiblist,
+    DtConfig,
+    DtDepaudit,
+    DtAudit,
+    DtPltpad,
+    DtMovetab,
+    DtSyminfo,
+    DtVersym,
+    DtRelacount,
+    DtRelcount,
+    DtFlags1,
+    DtVerdef,
+    DtVerdefnum,
+    DtVerneed,
+    DtVerneednum,
+    DtAuxiliary,
+    DtFilter,
+    DtOs(u64),
+    DtProc(u64),
+    DtUnknown(u64),
+}
+
+const DT_LOOS: u64 = 0x6000000D;
+const DT_HIOS: u64 = 0x6FFFFFFF;
+const DT_LOPROC: u64 = 0x70000000;
+const DT_HIPROC: u64 = 0x7FFFFFFF;
+
+impl DynamicTag {
+    pub fn from_u64(value: u64) -> DynamicTag {
+        match value {
+            0x00 => DynamicTag::DtNull,
+            0x01 => DynamicTag::DtNeeded,
+            0x02 => DynamicTag::DtPltrelsz,
+            0x03 => DynamicTag::DtPltgot,
+            0x04 => DynamicTag::DtHash,
+            0x05 => DynamicTag::DtStrtab,
+            0x06 => DynamicTag::DtSymtab,
+            0x07 => DynamicTag::DtRela,
+            0x08 => DynamicTag::DtRelasz,
+            0x09 => DynamicTag::DtRelaent,
+            0x0A => DynamicTag::DtStrsz,
+            0x0B => DynamicTag::DtSyment,
+            0x0C => DynamicTag::DtInit,
+            0x0D => DynamicTag::DtFini,
+            0x0E => DynamicTag::DtSoname,
+            0x0F => DynamicTag::DtRpath,
+            0x10 => DynamicTag::DtSymbolic,
+            0x11 => DynamicTag::DtRel,
+            0x12 => DynamicTag::DtRelsz,
+            0x13 => DynamicTag::DtRelent,
+            0x14 => DynamicTag::DtPltrel,
+            0x15 => DynamicTag::DtDebug,
+            0x16 => DynamicTag::DtTextrel,
+            0x17 => DynamicTag::DtJmprel,
+            0x18 => DynamicTag::DtBindNow,
+            0x19 => DynamicTag::DtInitArray,
+            0x1A => DynamicTag::DtFiniArray,
+            0x1B => DynamicTag::DtInitArraysz,
+            0x1C => DynamicTag::DtFiniArraysz,
+            0x1D => DynamicTag::DtRunpath,
+            0x1E => DynamicTag::DtFlags,
+            0x20 => DynamicTag::DtPreinitArray,
+            0x21 => DynamicTag::DtPreinitArraysz,
+            0x22 => DynamicTag::DtSymtabShndx,
+            0x23 => DynamicTag::DtRelrsz,
+            0x24 => DynamicTag::DtRelr,
+            0x25 => DynamicTag::DtRelrent,
+            0x6FFFFDF5 => DynamicTag::DtGnuPrelinked,
+            0x6FFFFDF6 => DynamicTag::DtGnuConflictsz,
+            0x6FFFFDF7 => DynamicTag::DtGnuLiblistsz,
+            0x6FFFFDF8 => DynamicTag::DtChecksum,
+            0x6FFFFDF9 => DynamicTag::DtPltpadsz,
+            0x6FFFFDFA => DynamicTag::DtMoveent,
+            0x6FFFFDFB => DynamicTag::DtMovesz,
+            0x6FFFFDFC => DynamicTag::DtFeature1,
+            0x6FFFFDFD => DynamicTag::DtPosflag1,
+            0x6FFFFDFE => DynamicTag::DtSyminsz,
+            0x6FFFFDFF => DynamicTag::DtSyminent,
+            0x6FFFFEF5 => DynamicTag::DtGnuHash,
+            0x6FFFFEF6 => DynamicTag::DtTlsdescPlt,
+            0x6FFFFEF7 => DynamicTag::DtTlsdescGot,
+            0x6FFFFEF8 => DynamicTag::DtGnuConflict,
+            0x6FFFFEF9 => DynamicTag::DtGnuLiblist,
+            0x6FFFFEFA => DynamicTag::DtConfig,
+            0x6FFFFEFB => DynamicTag::DtDepaudit,
+            0x6FFFFEFC => DynamicTag::DtAudit,
+            0x6FFFFEFD => DynamicTag::DtPltpad,
+            0x6FFFFEFE => DynamicTag::DtMovetab,
+            0x6FFFFEFF => DynamicTag::DtSyminfo,
+            0x6FFFFFF0 => DynamicTag::DtVersym,
+            0x6FFFFFF9 => DynamicTag::DtRelacount,
+            0x6FFFFFFA => DynamicTag::DtRelcount,
+            0x6FFFFFFB => DynamicTag::DtFlags1,
+            0x6FFFFFFC => DynamicTag::DtVerdef,
+            0x6FFFFFFD => DynamicTag::DtVerdefnum,
+            0x6FFFFFFE => DynamicTag::DtVerneed,
+            0x6FFFFFFF => DynamicTag::DtVerneednum,
+            0x7FFFFFFD => DynamicTag::DtAuxiliary,
+            0x7FFFFFFF => DynamicTag::DtFilter,
+            DT_LOOS..=DT_HIOS => DynamicTag::DtOs(value),
+            DT_LOPROC..=DT_HIPROC => DynamicTag::DtProc(value),
+            _ => DynamicTag::DtUnknown(value),
+        }
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for DynamicTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            DynamicTag::DtNull           => String::from("(NULL)"),
+            DynamicTag::DtNeeded         => String::from("(NEEDED)"),
+            DynamicTag::DtPltrelsz       => String::from("(PLTRELSZ)"),
+            DynamicTag::DtPltgot         => String::from("(PLTGOT)"),
+            DynamicTag::DtHash           => String::from("(HASH)"),
+            DynamicTag::DtStrtab         => String::from("(STRTAB)"),
+            DynamicTag::DtSymtab         => String::from("(SYMTAB)"),
+            DynamicTag::DtRela           => String::from("(RELA)"),
+            DynamicTag::DtRelasz         => String::from("(RELASZ)"),
+            DynamicTag::DtRelaent        => String::from("(RELAENT)"),
+            DynamicTag::DtStrsz          => String::from("(STRSZ)"),
+            DynamicTag::DtSyment         => String::from("(SYMENT)"),
+            DynamicTag::DtInit           => String::from("(INIT)"),
+            DynamicTag::DtFini           => String::from("(FINI)"),
+            DynamicTag::DtSoname         => String::from("(SONAME)"),
+            DynamicTag::DtRpath          => String::from("(RPATH)"),
+            DynamicTag::DtSymbolic       => String::from("(SYMBOLIC)"),
+            DynamicTag::DtRel            => String::from("(REL)"),
+            DynamicTag::DtRelsz          => String::from("(RELSZ)"),
+            DynamicTag::DtRelent         => String::from("(RELENT)"),
+            DynamicTag::DtPltrel         => String::from("(PLTREL)"),
+            DynamicTag::DtDebug          => String::from("(DEBUG)"),
+            DynamicTag::DtTextrel        => String::from("(TEXTREL)"),
+            DynamicTag::DtJmprel         => String::from("(JMPREL)"),
+            DynamicTag::DtBindNow        => String::from("(BIND_NOW)"),
+            DynamicTag::DtInitArray      => String::from("(INIT_ARRAY)"),
+            DynamicTag::DtFiniArray      => String::from("(FINI_ARRAY)"),
+            DynamicTag::DtInitArraysz    => String::from("(INIT_ARRAYSZ)"),
+            DynamicTag::DtFiniArraysz    => String::from("(FINI_ARRAYSZ)"),
+            DynamicTag::DtRunpath        => String::from("(RUNPATH)"),
+            DynamicTag::DtFlags          => String::from("(FLAGS)"),
+            DynamicTag::DtPreinitArray   => String::from("(PREINIT_ARRAY)"),
+            DynamicTag::DtPreinitArraysz => String::from("(PREINIT_ARRAYSZ)"),
+            DynamicTag::DtSymtabShndx    => String::from("(SYMTAB_SHNDX)"),
+            DynamicTag::DtRelrsz         => String::from("(RELRSZ)"),
+            DynamicTag::DtRelr           => String::from("(RELR)"),
+            DynamicTag::DtRelrent        => String::from("(RELRENT)"),
+            DynamicTag::DtGnuPrelinked   => String::from("(GNU_PRELINKED)"),
+            DynamicTag::DtGnuConflictsz  => String::from("(GNU_CONFLICTSZ)"),
+            DynamicTag::DtGnuLiblistsz   => String::from("(GNU_LIBLISTSZ)"),
+            DynamicTag::DtChecksum       => String::from("(CHECKSUM)"),
+            DynamicTag::DtPltpadsz       => String::from("(PLTPADSZ)"),
+            DynamicTag::DtMoveent        => String::from("(MOVEENT)"),
+            DynamicTag::DtMovesz         => String::from("(MOVESZ)"),
+            DynamicTag::DtFeature1       => String::from("(FEATURE_1)"),
+            DynamicTag::DtPosflag1       => String::from("(POSFLAG_1)"),
+            DynamicTag::DtSyminsz        => String::from("(SYMINSZ)"),
+            DynamicTag::DtSyminent       => String::from("(SYMINENT)"),
+            DynamicTag::DtGnuHash        => String::from("(GNU_HASH)"),
+            DynamicTag::DtTlsdescPlt     => String::from("(TLSDESC_PLT)"),
+            DynamicTag::DtTlsdescGot     => String::from("(TLSDESC_GOT)"),
+            DynamicTag::DtGnuConflict    => String::from("(GNU_CONFLICT)"),
+            DynamicTag::DtGnuLiblist     => String::from("(GNU_LIBLIST)"),
+            DynamicTag::DtConfig         => String::from("(CONFIG)"),
+            DynamicTag::DtDepaudit       => String::from("(DEPAUDIT)"),
+            DynamicTag::DtAudit          => String::from("(AUDIT)"),
+            DynamicTag::DtPltpad         => String::from("(PLTPAD)"),
+            DynamicTag::DtMovetab        => String::from("(MOVETAB)"),
+            DynamicTag::DtSyminfo        => String::from("(SYMINFO)"),
+            DynamicTag::DtVersym         => String::from("(VERSYM)"),
+            DynamicTag::DtRelacount      => String::from("(RELACOUNT)"),
+            DynamicTag::DtRelcount       => String::from("(RELCOUNT)"),
+            DynamicTag::DtFlags1         => String::from("(FLAGS_1)"),
+            DynamicTag::DtVerdef         => String::from("(VERDEF)"),
+            DynamicTag::DtVerdefnum      => String::from("(VERDEFNUM)"),
+            DynamicTag::DtVerneed        => String::from("(VERNEED)"),
+            DynamicTag::DtVerneednum     => String::from("(VERNEEDNUM)"),
+            DynamicTag::DtAuxiliary      => String::from("(AUXILIARY)"),
+            DynamicTag::DtFilter         => String::from("(FILTER)"),
+            DynamicTag::DtOs(num)        => format!("(OS) 0x{:X}", num),
+            DynamicTag::DtProc(num)      => format!("(PROC) 0x{:X}", num),
+            DynamicTag::DtUnknown(num)   => format!("(UNKNOWN) 0x{:X}", num),
+        };
+
+        f.pad(&description)
+    }
+}
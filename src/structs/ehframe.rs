@@ -0,0 +1,724 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+use crate::structs::dwarfreader::DwarfReader;
+use crate::structs::machine::Machine;
+use crate::termcolors;
+
+pub const DW_EH_PE_ABSPTR: u8 = 0x00;
+pub const DW_EH_PE_ULEB128: u8 = 0x01;
+pub const DW_EH_PE_UDATA2: u8 = 0x02;
+pub const DW_EH_PE_UDATA4: u8 = 0x03;
+pub const DW_EH_PE_UDATA8: u8 = 0x04;
+pub const DW_EH_PE_SLEB128: u8 = 0x09;
+pub const DW_EH_PE_SDATA2: u8 = 0x0A;
+pub const DW_EH_PE_SDATA4: u8 = 0x0B;
+pub const DW_EH_PE_SDATA8: u8 = 0x0C;
+pub const DW_EH_PE_PCREL: u8 = 0x10;
+pub const DW_EH_PE_DATAREL: u8 = 0x30;
+pub const DW_EH_PE_OMIT: u8 = 0xFF;
+
+pub const DW_CFA_ADVANCE_LOC: u8 = 0x40;
+pub const DW_CFA_OFFSET: u8 = 0x80;
+pub const DW_CFA_RESTORE: u8 = 0xC0;
+pub const DW_CFA_NOP: u8 = 0x00;
+pub const DW_CFA_SET_LOC: u8 = 0x01;
+pub const DW_CFA_ADVANCE_LOC1: u8 = 0x02;
+pub const DW_CFA_ADVANCE_LOC2: u8 = 0x03;
+pub const DW_CFA_ADVANCE_LOC4: u8 = 0x04;
+pub const DW_CFA_OFFSET_EXTENDED: u8 = 0x05;
+pub const DW_CFA_RESTORE_EXTENDED: u8 = 0x06;
+pub const DW_CFA_UNDEFINED: u8 = 0x07;
+pub const DW_CFA_SAME_VALUE: u8 = 0x08;
+pub const DW_CFA_REGISTER: u8 = 0x09;
+pub const DW_CFA_REMEMBER_STATE: u8 = 0x0A;
+pub const DW_CFA_RESTORE_STATE: u8 = 0x0B;
+pub const DW_CFA_DEF_CFA: u8 = 0x0C;
+pub const DW_CFA_DEF_CFA_REGISTER: u8 = 0x0D;
+pub const DW_CFA_DEF_CFA_OFFSET: u8 = 0x0E;
+pub const DW_CFA_DEF_CFA_EXPRESSION: u8 = 0x0F;
+pub const DW_CFA_EXPRESSION: u8 = 0x10;
+pub const DW_CFA_OFFSET_EXTENDED_SF: u8 = 0x11;
+pub const DW_CFA_DEF_CFA_SF: u8 = 0x12;
+pub const DW_CFA_DEF_CFA_OFFSET_SF: u8 = 0x13;
+pub const DW_CFA_VAL_OFFSET: u8 = 0x14;
+pub const DW_CFA_VAL_OFFSET_SF: u8 = 0x15;
+pub const DW_CFA_VAL_EXPRESSION: u8 = 0x16;
+pub const DW_CFA_GNU_WINDOW_SAVE: u8 = 0x2D;
+pub const DW_CFA_GNU_ARGS_SIZE: u8 = 0x2E;
+pub const DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED: u8 = 0x2F;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CfaRule {
+    Undefined,
+    RegisterOffset(u64, i64),
+    Expression,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegisterRule {
+    Undefined,
+    SameValue,
+    Offset(i64),
+    ValOffset(i64),
+    Register(u64),
+    Expression,
+    ValExpression,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnwindRow {
+    location: u64,
+    cfa: CfaRule,
+    registers: BTreeMap<u64, RegisterRule>,
+}
+
+#[derive(Debug)]
+pub struct CommonInformationEntry {
+    offset: usize,
+    length: u64,
+    augmentation: String,
+    code_alignment_factor: u64,
+    data_alignment_factor: i64,
+    return_address_register: u64,
+    fde_encoding: u8,
+    lsda_encoding: u8,
+    personality: Option<u64>,
+    is_signal_frame: bool,
+    initial_rules: BTreeMap<u64, RegisterRule>,
+    initial_cfa: CfaRule,
+    rows: Vec<UnwindRow>,
+}
+
+#[derive(Debug)]
+pub struct FrameDescriptionEntry {
+    offset: usize,
+    length: u64,
+    cie_pointer: u64,
+    cie_offset: usize,
+    pc_begin: u64,
+    pc_range: u64,
+    lsda: Option<u64>,
+    rows: Vec<UnwindRow>,
+}
+
+#[derive(Debug)]
+enum FrameEntry {
+    Cie(CommonInformationEntry),
+    Fde(FrameDescriptionEntry),
+    Terminator(usize),
+}
+
+// Where encoded pointers are relative to: the pointer's own address or the start of .eh_frame_hdr
+#[derive(Debug, Clone, Copy)]
+struct PointerContext {
+    section_address: u64,
+    data_address: u64,
+    address_size: u8,
+}
+
+#[derive(Debug)]
+pub struct EhFrame {
+    address: u64,
+    machine: Machine,
+    address_size: u8,
+    entries: Vec<FrameEntry>,
+}
+
+impl EhFrame {
+    pub fn build(data: &[u8], address: u64, machine: Machine, is_32bit: bool, is_little_endian: bool) -> Result<EhFrame, &'static str> {
+        let address_size = if is_32bit { 4 } else { 8 };
+        let context = PointerContext{section_address: address, data_address: 0, address_size};
+        let mut reader = DwarfReader::new(data, 0, is_little_endian);
+        let mut entries: Vec<FrameEntry> = Vec::new();
+
+        while !reader.is_at_end() {
+            let offset = reader.offset();
+            let (length, is_dwarf64) = reader.read_initial_length()?;
+            if length == 0 {
+                entries.push(FrameEntry::Terminator(offset));
+                continue;
+            }
+
+            let entry_end = usize::try_from(length).ok()
+                .and_then(|length| reader.offset().checked_add(length))
+                .filter(|end| *end <= data.len())
+                .ok_or("Frame entry extends past the end of .eh_frame")?;
+
+            // In .eh_frame the CIE pointer is the distance back from this field to the CIE
+            let id_offset = reader.offset();
+            let id = reader.read_offset(is_dwarf64)?;
+
+            if id == 0 {
+                let cie = parse_cie(&mut reader, offset, length, entry_end, &context, machine)?;
+                entries.push(FrameEntry::Cie(cie));
+            } else {
+                let cie_offset = (id_offset as u64).checked_sub(id).ok_or("FDE refers to a CIE before the section start")? as usize;
+                let cie = entries.iter().find_map(|entry| match entry {
+                    FrameEntry::Cie(cie) if cie.offset == cie_offset => Some(cie),
+                    _ => None,
+                }).ok_or("FDE refers to a missing CIE")?;
+
+                let fde = parse_fde(&mut reader, offset, length, id, cie, entry_end, &context, machine)?;
+                entries.push(FrameEntry::Fde(fde));
+            }
+            reader.set_offset(entry_end);
+        }
+
+        Ok(EhFrame{address, machine, address_size, entries})
+    }
+
+    fn fdes(&self) -> impl Iterator<Item = &FrameDescriptionEntry> {
+        self.entries.iter().filter_map(|entry| match entry {
+            FrameEntry::Fde(fde) => Some(fde),
+            _ => None,
+        })
+    }
+
+    pub fn print(&self) {
+        let width = self.address_size as usize * 2;
+        println!("{}Contents of the .eh_frame section:{}", termcolors::purple(), termcolors::default());
+        println!();
+
+        for entry in self.entries.iter() {
+            println!();
+            match entry {
+                FrameEntry::Cie(cie) => {
+                    print!("{}{:08x} {}{:0width$x} {:08x} {}CIE \"{}\" cf={} df={} ra={}",
+                        termcolors::red(),
+                        cie.offset,
+                        termcolors::white(),
+                        cie.length,
+                        0,
+                        termcolors::yellow(),
+                        cie.augmentation,
+                        cie.code_alignment_factor,
+                        cie.data_alignment_factor,
+                        cie.return_address_register);
+                    if let Some(personality) = cie.personality {
+                        print!(" personality=0x{:x}", personality);
+                    }
+                    if cie.is_signal_frame {
+                        print!(" signal-frame");
+                    }
+                    println!("{}", termcolors::default());
+                    self.print_rows(&cie.rows, cie.return_address_register);
+                },
+                FrameEntry::Fde(fde) => {
+                    print!("{}{:08x} {}{:0width$x} {:08x} {}FDE cie={:08x} pc={:0width$x}..{:0width$x}",
+                        termcolors::red(),
+                        fde.offset,
+                        termcolors::white(),
+                        fde.length,
+                        fde.cie_pointer,
+                        termcolors::green(),
+                        fde.cie_offset,
+                        fde.pc_begin,
+                        fde.pc_begin.wrapping_add(fde.pc_range));
+                    if let Some(lsda) = fde.lsda {
+                        print!(" lsda=0x{:x}", lsda);
+                    }
+                    println!("{}", termcolors::default());
+
+                    let return_address_register = self.entries.iter().find_map(|entry| match entry {
+                        FrameEntry::Cie(cie) if cie.offset == fde.cie_offset => Some(cie.return_address_register),
+                        _ => None,
+                    }).unwrap_or_default();
+                    self.print_rows(&fde.rows, return_address_register);
+                },
+                FrameEntry::Terminator(offset) => println!("{}{:08x} {}ZERO terminator{}", termcolors::red(), offset, termcolors::white(), termcolors::default()),
+            }
+        }
+        print!("{}", termcolors::default());
+    }
+
+    // One column per register that has a rule anywhere in the table, as readelf --debug-dump=frames-interp does
+    fn print_rows(&self, rows: &[UnwindRow], return_address_register: u64) {
+        if rows.is_empty() {
+            return;
+        }
+
+        let width = self.address_size as usize * 2;
+        let columns: BTreeSet<u64> = rows.iter().flat_map(|row| row.registers.keys().copied()).collect();
+        let column_name = |register: u64| match register == return_address_register {
+            true => String::from("ra"),
+            false => register_name(self.machine, register),
+        };
+
+        print!("{}{:<width$} CFA      ", termcolors::blue(), "   LOC");
+        for register in columns.iter() {
+            print!("{:<5} ", column_name(*register));
+        }
+        println!("{}", termcolors::default());
+
+        for row in rows.iter() {
+            let cfa = match row.cfa {
+                CfaRule::Undefined => String::from("u"),
+                CfaRule::RegisterOffset(register, offset) => format!("{}{:+}", register_name(self.machine, register), offset),
+                CfaRule::Expression => String::from("exp"),
+            };
+            print!("{}{:0width$x} {}{:<8} ", termcolors::cyan(), row.location, termcolors::white(), cfa);
+
+            for register in columns.iter() {
+                let rule = match row.registers.get(register).unwrap_or(&RegisterRule::Undefined) {
+                    RegisterRule::Undefined => String::from("u"),
+                    RegisterRule::SameValue => String::from("s"),
+                    RegisterRule::Offset(offset) => format!("c{:+}", offset),
+                    RegisterRule::ValOffset(offset) => format!("v{:+}", offset),
+                    RegisterRule::Register(other) => register_name(self.machine, *other),
+                    RegisterRule::Expression => String::from("exp"),
+                    RegisterRule::ValExpression => String::from("vexp"),
+                };
+                print!("{:<5} ", rule);
+            }
+            println!("{}", termcolors::default());
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EhFrameHdr {
+    version: u8,
+    eh_frame_ptr_encoding: u8,
+    fde_count_encoding: u8,
+    table_encoding: u8,
+    eh_frame_ptr: Option<u64>,
+    fde_count: Option<u64>,
+    table: Vec<(u64, u64)>,
+}
+
+impl EhFrameHdr {
+    pub fn build(data: &[u8], address: u64, is_32bit: bool, is_little_endian: bool) -> Result<EhFrameHdr, &'static str> {
+        let address_size = if is_32bit { 4 } else { 8 };
+        let context = PointerContext{section_address: address, data_address: address, address_size};
+        let mut reader = DwarfReader::new(data, 0, is_little_endian);
+
+        let version = reader.read_u8()?;
+        if version != 1 {
+            return Err("Unsupported .eh_frame_hdr version");
+        }
+
+        let eh_frame_ptr_encoding = reader.read_u8()?;
+        let fde_count_encoding = reader.read_u8()?;
+        let table_encoding = reader.read_u8()?;
+        let eh_frame_ptr = read_encoded_pointer(&mut reader, eh_frame_ptr_encoding, &context)?;
+        let fde_count = read_encoded_pointer(&mut reader, fde_count_encoding, &context)?;
+
+        // The binary search table is only present when both its count and its entries are encoded
+        let mut table: Vec<(u64, u64)> = Vec::new();
+        if let (Some(count), false) = (fde_count, table_encoding == DW_EH_PE_OMIT) {
+            for _ in 0..count {
+                let initial_location = read_encoded_pointer(&mut reader, table_encoding, &context)?.unwrap_or_default();
+                let fde_address = read_encoded_pointer(&mut reader, table_encoding, &context)?.unwrap_or_default();
+                table.push((initial_location, fde_address));
+            }
+        }
+
+        Ok(EhFrameHdr{version, eh_frame_ptr_encoding, fde_count_encoding, table_encoding, eh_frame_ptr, fde_count, table})
+    }
+
+    // Lists everything an unwinder doing a binary search over the table would trip over
+    pub fn verify(&self, eh_frame: &EhFrame) -> Vec<String> {
+        let mut problems: Vec<String> = Vec::new();
+
+        if self.eh_frame_ptr != Some(eh_frame.address) {
+            problems.push(format!("eh_frame_ptr {} does not point to .eh_frame at 0x{:x}", optional_to_string(self.eh_frame_ptr), eh_frame.address));
+        }
+
+        let fde_count = eh_frame.fdes().count() as u64;
+        if self.fde_count.is_some_and(|count| count != fde_count) {
+            problems.push(format!("FDE count {} does not match the {} FDEs in .eh_frame", self.fde_count.unwrap_or_default(), fde_count));
+        }
+
+        for (index, pair) in self.table.windows(2).enumerate() {
+            if pair[1].0 < pair[0].0 {
+                problems.push(format!("Entry {} for 0x{:x} is not sorted after 0x{:x}", index + 1, pair[1].0, pair[0].0));
+            }
+        }
+
+        let mut referenced: BTreeSet<usize> = BTreeSet::new();
+        for (index, (initial_location, fde_address)) in self.table.iter().enumerate() {
+            let fde = fde_address.checked_sub(eh_frame.address)
+                .and_then(|offset| eh_frame.fdes().find(|fde| fde.offset as u64 == offset));
+            match fde {
+                Some(fde) if fde.pc_begin == *initial_location => { referenced.insert(fde.offset); },
+                Some(fde) => problems.push(format!("Entry {} for 0x{:x} points to the FDE at 0x{:x} which starts at 0x{:x}", index, initial_location, fde_address, fde.pc_begin)),
+                None => problems.push(format!("Entry {} for 0x{:x} points to 0x{:x} which is not an FDE", index, initial_location, fde_address)),
+            }
+        }
+
+        if !self.table.is_empty() {
+            for fde in eh_frame.fdes().filter(|fde| !referenced.contains(&fde.offset)) {
+                problems.push(format!("FDE at offset 0x{:08x} for 0x{:x} is missing from the search table", fde.offset, fde.pc_begin));
+            }
+        }
+
+        problems
+    }
+
+    pub fn print(&self, problems: &[String]) {
+        println!("{}Contents of the .eh_frame_hdr section:{}", termcolors::purple(), termcolors::default());
+        println!("  {}Version:               {}{}", termcolors::white(), termcolors::green(), self.version);
+        println!("  {}Pointer encodings:     {}eh_frame_ptr 0x{:02x}, fde_count 0x{:02x}, table 0x{:02x}",
+            termcolors::white(), termcolors::green(), self.eh_frame_ptr_encoding, self.fde_count_encoding, self.table_encoding);
+        println!("  {}eh_frame_ptr:          {}{}", termcolors::white(), termcolors::green(), optional_to_string(self.eh_frame_ptr));
+        println!("  {}FDE count:             {}{}", termcolors::white(), termcolors::green(), self.fde_count.map(|count| count.to_string()).unwrap_or(String::from("<omitted>")));
+
+        if problems.is_empty() {
+            println!("  {}Search table:          {}{} entries, sorted and matching .eh_frame", termcolors::white(), termcolors::green(), self.table.len());
+        } else {
+            println!("  {}Search table:          {}{} entries, {} problems", termcolors::white(), termcolors::red(), self.table.len(), problems.len());
+            for problem in problems.iter() {
+                println!("    {}{}", termcolors::red(), problem);
+            }
+        }
+        print!("{}", termcolors::default());
+    }
+}
+
+fn optional_to_string(value: Option<u64>) -> String {
+    match value {
+        Some(value) => format!("0x{:x}", value),
+        None => String::from("<omitted>"),
+    }
+}
+
+fn parse_cie(reader: &mut DwarfReader, offset: usize, length: u64, entry_end: usize, context: &PointerContext, machine: Machine) -> Result<CommonInformationEntry, &'static str> {
+    let version = reader.read_u8()?;
+    if !matches!(version, 1 | 3 | 4) {
+        return Err("Unsupported CIE version");
+    }
+
+    let augmentation = reader.read_cstr()?;
+    if augmentation.contains("eh") {
+        reader.read_address(context.address_size)?;
+    }
+    if version == 4 {
+        let _address_size = reader.read_u8()?;
+        let _segment_selector_size = reader.read_u8()?;
+    }
+
+    let code_alignment_factor = reader.read_uleb128()?;
+    let data_alignment_factor = reader.read_sleb128()?;
+    let return_address_register = match version {
+        1 => reader.read_u8()? as u64,
+        _ => reader.read_uleb128()?,
+    };
+
+    let mut cie = CommonInformationEntry{
+        offset,
+        length,
+        augmentation: augmentation.clone(),
+        code_alignment_factor,
+        data_alignment_factor,
+        return_address_register,
+        fde_encoding: DW_EH_PE_ABSPTR,
+        lsda_encoding: DW_EH_PE_OMIT,
+        personality: None,
+        is_signal_frame: false,
+        initial_rules: BTreeMap::new(),
+        initial_cfa: CfaRule::Undefined,
+        rows: Vec::new()};
+
+    // Augmentation data is only understood when the string starts with 'z', which also gives its length
+    if augmentation.starts_with('z') {
+        let data_length = reader.read_uleb128()?;
+        let data_end = reader.offset() + usize::try_from(data_length).map_err(|_| "CIE augmentation data too long")?;
+
+        for character in augmentation.chars().skip(1) {
+            match character {
+                'L' => cie.lsda_encoding = reader.read_u8()?,
+                'R' => cie.fde_encoding = reader.read_u8()?,
+                'P' => {
+                    let encoding = reader.read_u8()?;
+                    cie.personality = read_encoded_pointer(reader, encoding, context)?;
+                },
+                'S' => cie.is_signal_frame = true,
+                _ => break,
+            }
+        }
+        reader.set_offset(data_end);
+    }
+
+    let instructions = reader.offset()..entry_end;
+    let initial_row = UnwindRow{location: 0, cfa: CfaRule::Undefined, registers: BTreeMap::new()};
+    cie.rows = execute_instructions(reader, instructions, &cie, initial_row, context, machine)?;
+
+    let last = cie.rows.last();
+    cie.initial_rules = last.map(|row| row.registers.clone()).unwrap_or_default();
+    cie.initial_cfa = last.map(|row| row.cfa).unwrap_or(CfaRule::Undefined);
+    Ok(cie)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn parse_fde(reader: &mut DwarfReader, offset: usize, length: u64, cie_pointer: u64, cie: &CommonInformationEntry, entry_end: usize, context: &PointerContext, machine: Machine) -> Result<FrameDescriptionEntry, &'static str> {
+    let pc_begin = read_encoded_pointer(reader, cie.fde_encoding, context)?.unwrap_or_default();
+    // The range is a plain size, only the value format of the encoding applies
+    let pc_range = read_encoded_pointer(reader, cie.fde_encoding & 0x0F, context)?.unwrap_or_default();
+
+    let mut lsda = None;
+    if cie.augmentation.starts_with('z') {
+        let data_length = reader.read_uleb128()?;
+        let data_end = reader.offset() + usize::try_from(data_length).map_err(|_| "FDE augmentation data too long")?;
+        if cie.augmentation.contains('L') {
+            lsda = read_encoded_pointer(reader, cie.lsda_encoding, context)?;
+        }
+        reader.set_offset(data_end);
+    }
+
+    let instructions = reader.offset()..entry_end;
+    let initial_row = UnwindRow{location: pc_begin, cfa: cie.initial_cfa, registers: cie.initial_rules.clone()};
+    let rows = execute_instructions(reader, instructions, cie, initial_row, context, machine)?;
+
+    Ok(FrameDescriptionEntry{offset, length, cie_pointer, cie_offset: cie.offset, pc_begin, pc_range, lsda, rows})
+}
+
+// Runs a call frame program, returning one row per location change; a program of only nops yields no rows
+fn execute_instructions(reader: &mut DwarfReader, instructions: Range<usize>, cie: &CommonInformationEntry, initial_row: UnwindRow, context: &PointerContext, machine: Machine) -> Result<Vec<UnwindRow>, &'static str> {
+    let mut rows: Vec<UnwindRow> = Vec::new();
+    let mut row = initial_row;
+    let mut saved: Vec<(CfaRule, BTreeMap<u64, RegisterRule>)> = Vec::new();
+    let mut all_nops = true;
+    let code_alignment_factor = cie.code_alignment_factor;
+    let data_alignment_factor = cie.data_alignment_factor;
+
+    reader.set_offset(instructions.start);
+    while reader.offset() < instructions.end {
+        let opcode = reader.read_u8()?;
+        if opcode != DW_CFA_NOP {
+            all_nops = false;
+        }
+
+        let mut advance = None;
+        match (opcode & 0xC0, opcode & 0x3F) {
+            (DW_CFA_ADVANCE_LOC, delta) => advance = Some(row.location.wrapping_add(delta as u64 * code_alignment_factor)),
+            (DW_CFA_OFFSET, register) => {
+                let offset = reader.read_uleb128()? as i64 * data_alignment_factor;
+                row.registers.insert(register as u64, RegisterRule::Offset(offset));
+            },
+            (DW_CFA_RESTORE, register) => restore_register(&mut row, register as u64, cie),
+            _ => match opcode {
+                DW_CFA_NOP => (),
+                DW_CFA_SET_LOC => advance = Some(read_encoded_pointer(reader, cie.fde_encoding, context)?.unwrap_or_default()),
+                DW_CFA_ADVANCE_LOC1 => advance = Some(row.location.wrapping_add(reader.read_u8()? as u64 * code_alignment_factor)),
+                DW_CFA_ADVANCE_LOC2 => advance = Some(row.location.wrapping_add(reader.read_u16()? as u64 * code_alignment_factor)),
+                DW_CFA_ADVANCE_LOC4 => advance = Some(row.location.wrapping_add(reader.read_u32()? as u64 * code_alignment_factor)),
+                DW_CFA_OFFSET_EXTENDED => {
+                    let register = reader.read_uleb128()?;
+                    let offset = reader.read_uleb128()? as i64 * data_alignment_factor;
+                    row.registers.insert(register, RegisterRule::Offset(offset));
+                },
+                DW_CFA_RESTORE_EXTENDED => {
+                    let register = reader.read_uleb128()?;
+                    restore_register(&mut row, register, cie);
+                },
+                DW_CFA_UNDEFINED => { row.registers.insert(reader.read_uleb128()?, RegisterRule::Undefined); },
+                DW_CFA_SAME_VALUE => { row.registers.insert(reader.read_uleb128()?, RegisterRule::SameValue); },
+                DW_CFA_REGISTER => {
+                    let register = reader.read_uleb128()?;
+                    let other = reader.read_uleb128()?;
+                    row.registers.insert(register, RegisterRule::Register(other));
+                },
+                DW_CFA_REMEMBER_STATE => saved.push((row.cfa, row.registers.clone())),
+                DW_CFA_RESTORE_STATE => {
+                    let (cfa, registers) = saved.pop().ok_or("DW_CFA_restore_state without remembered state")?;
+                    row.cfa = cfa;
+                    row.registers = registers;
+                },
+                DW_CFA_DEF_CFA => {
+                    let register = reader.read_uleb128()?;
+                    let offset = reader.read_uleb128()? as i64;
+                    row.cfa = CfaRule::RegisterOffset(register, offset);
+                },
+                DW_CFA_DEF_CFA_SF => {
+                    let register = reader.read_uleb128()?;
+                    let offset = reader.read_sleb128()? * data_alignment_factor;
+                    row.cfa = CfaRule::RegisterOffset(register, offset);
+                },
+                DW_CFA_DEF_CFA_REGISTER => {
+                    let register = reader.read_uleb128()?;
+                    row.cfa = match row.cfa {
+                        CfaRule::RegisterOffset(_, offset) => CfaRule::RegisterOffset(register, offset),
+                        _ => CfaRule::RegisterOffset(register, 0),
+                    };
+                },
+                DW_CFA_DEF_CFA_OFFSET | DW_CFA_DEF_CFA_OFFSET_SF => {
+                    let offset = match opcode {
+                        DW_CFA_DEF_CFA_OFFSET => reader.read_uleb128()? as i64,
+                        _ => reader.read_sleb128()? * data_alignment_factor,
+                    };
+                    if let CfaRule::RegisterOffset(register, _) = row.cfa {
+                        row.cfa = CfaRule::RegisterOffset(register, offset);
+                    }
+                },
+                DW_CFA_DEF_CFA_EXPRESSION => {
+                    let length = reader.read_uleb128()?;
+                    reader.skip(length)?;
+                    row.cfa = CfaRule::Expression;
+                },
+                DW_CFA_EXPRESSION | DW_CFA_VAL_EXPRESSION => {
+                    let register = reader.read_uleb128()?;
+                    let length = reader.read_uleb128()?;
+                    reader.skip(length)?;
+                    let rule = if opcode == DW_CFA_EXPRESSION { RegisterRule::Expression } else { RegisterRule::ValExpression };
+                    row.registers.insert(register, rule);
+                },
+                DW_CFA_OFFSET_EXTENDED_SF => {
+                    let register = reader.read_uleb128()?;
+                    let offset = reader.read_sleb128()? * data_alignment_factor;
+                    row.registers.insert(register, RegisterRule::Offset(offset));
+                },
+                DW_CFA_VAL_OFFSET => {
+                    let register = reader.read_uleb128()?;
+                    let offset = reader.read_uleb128()? as i64 * data_alignment_factor;
+                    row.registers.insert(register, RegisterRule::ValOffset(offset));
+                },
+                DW_CFA_VAL_OFFSET_SF => {
+                    let register = reader.read_uleb128()?;
+                    let offset = reader.read_sleb128()? * data_alignment_factor;
+                    row.registers.insert(register, RegisterRule::ValOffset(offset));
+                },
+                DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED => {
+                    let register = reader.read_uleb128()?;
+                    let offset = -(reader.read_uleb128()? as i64 * data_alignment_factor);
+                    row.registers.insert(register, RegisterRule::Offset(offset));
+                },
+                DW_CFA_GNU_ARGS_SIZE => { reader.read_uleb128()?; },
+                // SPARC register window save, reused by AArch64 for toggling return address signing
+                DW_CFA_GNU_WINDOW_SAVE if machine == Machine::EMAARCH64 || machine == Machine::EMSPARCV9 => (),
+                _ => return Err("Unknown call frame instruction"),
+            },
+        }
+
+        if let Some(location) = advance {
+            rows.push(row.clone());
+            row.location = location;
+        }
+    }
+
+    if !all_nops {
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn restore_register(row: &mut UnwindRow, register: u64, cie: &CommonInformationEntry) {
+    match cie.initial_rules.get(&register) {
+        Some(rule) => row.registers.insert(register, *rule),
+        None => row.registers.remove(&register),
+    };
+}
+
+fn read_encoded_pointer(reader: &mut DwarfReader, encoding: u8, context: &PointerContext) -> Result<Option<u64>, &'static str> {
+    if encoding == DW_EH_PE_OMIT {
+        return Ok(None);
+    }
+
+    let field_address = context.section_address.wrapping_add(reader.offset() as u64);
+    let value = match encoding & 0x0F {
+        DW_EH_PE_ABSPTR => reader.read_address(context.address_size)?,
+        DW_EH_PE_ULEB128 => reader.read_uleb128()?,
+        DW_EH_PE_UDATA2 => reader.read_u16()? as u64,
+        DW_EH_PE_UDATA4 => reader.read_u32()? as u64,
+        DW_EH_PE_UDATA8 => reader.read_u64()?,
+        DW_EH_PE_SLEB128 => reader.read_sleb128()? as u64,
+        DW_EH_PE_SDATA2 => reader.read_u16()? as i16 as u64,
+        DW_EH_PE_SDATA4 => reader.read_u32()? as i32 as u64,
+        DW_EH_PE_SDATA8 => reader.read_u64()?,
+        _ => return Err("Unsupported pointer encoding"),
+    };
+
+    // DW_EH_PE_indirect (0x80) is ignored: the address of the pointer is reported rather than its target
+    let base = match encoding & 0x70 {
+        DW_EH_PE_ABSPTR => 0,
+        DW_EH_PE_PCREL => field_address,
+        DW_EH_PE_DATAREL => context.data_address,
+        _ => return Err("Unsupported pointer application"),
+    };
+
+    let pointer = base.wrapping_add(value);
+    Ok(Some(match context.address_size {
+        4 => pointer & 0xFFFFFFFF,
+        _ => pointer,
+    }))
+}
+
+// DWARF register numbers as named by the psABI of each architecture
+fn register_name(machine: Machine, register: u64) -> String {
+    const X86_64: [&str; 17] = ["rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp",
+        "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15", "rip"];
+    const I386: [&str; 10] = ["eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "eip", "eflags"];
+
+    match machine {
+        Machine::EMX8664 if register < 17 => String::from(X86_64[register as usize]),
+        Machine::EM386 if register < 10 => String::from(I386[register as usize]),
+        Machine::EMAARCH64 if register < 31 => format!("x{}", register),
+        Machine::EMAARCH64 if register == 31 => String::from("sp"),
+        _ => format!("r{}", register),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // CIE "zR" cf=1 df=-8 ra=16 with pcrel|sdata4 FDE pointers, then one FDE for 0x1000..0x1020
+    fn eh_frame_data() -> Vec<u8> {
+        let mut data = vec![
+            0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, b'z', b'R', 0x00, 0x01, 0x78, 0x10, 0x01, 0x1B,
+            DW_CFA_DEF_CFA, 0x07, 0x08, DW_CFA_OFFSET | 0x10, 0x01, DW_CFA_NOP, DW_CFA_NOP,
+            0x1C, 0x00, 0x00, 0x00, 0x1C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00,
+            DW_CFA_ADVANCE_LOC | 0x01, DW_CFA_DEF_CFA_OFFSET, 0x10, DW_CFA_OFFSET | 0x06, 0x02,
+            DW_CFA_REMEMBER_STATE, DW_CFA_ADVANCE_LOC | 0x04, DW_CFA_DEF_CFA_REGISTER, 0x06,
+            DW_CFA_ADVANCE_LOC | 0x08, DW_CFA_RESTORE_STATE, DW_CFA_NOP, DW_CFA_NOP, DW_CFA_NOP, DW_CFA_NOP,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        // pc_begin is relative to its own address 0x2000 + 0x20
+        let pc_begin = (0x1000i64 - 0x2020) as i32;
+        data[0x20..0x24].copy_from_slice(&pc_begin.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn eh_frame_should_build_cfa_table() {
+        let eh_frame = EhFrame::build(&eh_frame_data(), 0x2000, Machine::EMX8664, false, true).expect("Should parse .eh_frame");
+        assert_eq!(eh_frame.entries.len(), 3);
+        assert!(matches!(eh_frame.entries[2], FrameEntry::Terminator(0x38)));
+
+        let fde = eh_frame.fdes().next().expect("Should contain an FDE");
+        assert_eq!(fde.cie_offset, 0);
+        assert_eq!((fde.pc_begin, fde.pc_range), (0x1000, 0x20));
+
+        let locations: Vec<u64> = fde.rows.iter().map(|row| row.location).collect();
+        assert_eq!(locations, vec![0x1000, 0x1001, 0x1005, 0x100D]);
+        assert_eq!(fde.rows[0].cfa, CfaRule::RegisterOffset(7, 8));
+        assert_eq!(fde.rows[1].cfa, CfaRule::RegisterOffset(7, 16));
+        assert_eq!(fde.rows[1].registers.get(&6), Some(&RegisterRule::Offset(-16)));
+        assert_eq!(fde.rows[2].cfa, CfaRule::RegisterOffset(6, 16));
+        // restore_state brings back the CFA remembered before switching to rbp
+        assert_eq!(fde.rows[3].cfa, CfaRule::RegisterOffset(7, 16));
+        assert_eq!(fde.rows[3].registers.get(&16), Some(&RegisterRule::Offset(-8)));
+    }
+
+    #[test]
+    fn eh_frame_hdr_should_verify_search_table() {
+        let eh_frame = EhFrame::build(&eh_frame_data(), 0x2000, Machine::EMX8664, false, true).expect("Should parse .eh_frame");
+
+        // eh_frame_ptr pcrel|sdata4, fde_count udata4, table datarel|sdata4; header at 0x1F00
+        let mut data = vec![0x01, 0x1B, 0x03, 0x3B];
+        data.extend((0x2000i32 - 0x1F04).to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend((0x1000i32 - 0x1F00).to_le_bytes());
+        data.extend((0x2018i32 - 0x1F00).to_le_bytes());
+
+        let hdr = EhFrameHdr::build(&data, 0x1F00, false, true).expect("Should parse .eh_frame_hdr");
+        assert_eq!(hdr.eh_frame_ptr, Some(0x2000));
+        assert_eq!(hdr.table, vec![(0x1000, 0x2018)]);
+        assert!(hdr.verify(&eh_frame).is_empty());
+
+        // Pointing the entry at the CIE instead of the FDE must be reported
+        data[12..16].copy_from_slice(&(0x2000i32 - 0x1F00).to_le_bytes());
+        let hdr = EhFrameHdr::build(&data, 0x1F00, false, true).expect("Should parse .eh_frame_hdr");
+        assert_eq!(hdr.verify(&eh_frame).len(), 2);
+    }
+}
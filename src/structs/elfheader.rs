@@ -2,6 +2,7 @@ use super::identifier::Identifier;
 use super::machine::Machine;
 use super::type_::Type;
 use super::word::Word;
+use super::symbols::SHN_XINDEX;
 
 use crate::termcolors::*;
 use crate::types;
@@ -24,10 +25,21 @@ pub struct ElfHeader{
     e_shentsize: types::Elf32Half,
     e_shnum: types::Elf32Half,
     e_shstrndx: types::Elf32Half,
+    shnum: types::Elf32Word,
+    shstrndx: types::Elf32Word,
+    phnum: types::Elf32Word,
 }
 
+pub const PN_XNUM: types::Elf32Half = 0xFFFF;
+
 impl ElfHeader {
     pub fn build(payload: &[u8]) -> Result<ElfHeader, &'static str> {
+        Self::build_with_first_section(payload, read_from(payload))
+    }
+
+    // For files that are not in memory: payload only needs the ELF header itself, and read_at fetches
+    // section header 0 in the rare case that the counts overflow into it
+    pub fn build_with_first_section(payload: &[u8], read_at: impl FnOnce(u64, usize) -> Option<Vec<u8>>) -> Result<ElfHeader, &'static str> {
         if payload.len() < consts::SHSTRNDX64_END { // TODO: add proper validation later
             return Err("Elf header too short");
         }
@@ -40,6 +52,7 @@ impl ElfHeader {
         let is32_bit = identifier.is32_bit();
         let is_little_endian = identifier.is_little_endian();
 
+        let entry;
         let program_header_offset;
         let section_header_table_offset;
         let flags;
@@ -51,6 +64,7 @@ impl ElfHeader {
         let section_header_table_names_index;
 
         if is32_bit {
+            entry = Word::build32(&payload[consts::ENTRY32_START..consts::ENTRY32_END].try_into().unwrap(), is_little_endian);
             program_header_offset = Word::build32(&payload[consts::PHOFF32_START..consts::PHOFF32_END].try_into().unwrap(),  is_little_endian);
             section_header_table_offset = Word::build32(&payload[consts::SHOFF32_START..consts::SHOFF32_END].try_into().unwrap(),  is_little_endian);
             flags = to_u32_from_slice(&payload[consts::FLAGS32_START..consts::FLAGS32_END].try_into().unwrap(), is_little_endian);
@@ -62,6 +76,7 @@ impl ElfHeader {
             section_header_table_names_index = to_u16_from_slice(&payload[consts::SHSTRNDX32_START..consts::SHSTRNDX32_END].try_into().unwrap(), is_little_endian);
 
         } else {
+            entry = Word::build64(&payload[consts::ENTRY64_START..consts::ENTRY64_END].try_into().unwrap(), is_little_endian);
             program_header_offset = Word::build64(&payload[consts::PHOFF64_START..consts::PHOFF64_END].try_into().unwrap(), is_little_endian);
             section_header_table_offset = Word::build64(&payload[consts::SHOFF64_START..consts::SHOFF64_END].try_into().unwrap(), is_little_endian);
             flags = to_u32_from_slice(&payload[consts::FLAGS64_START..consts::FLAGS64_END].try_into().unwrap(), is_little_endian);
@@ -73,12 +88,22 @@ impl ElfHeader {
             section_header_table_names_index = to_u16_from_slice(&payload[consts::SHSTRNDX64_START..consts::SHSTRNDX64_END].try_into().unwrap(), is_little_endian);
         }
 
+        // Counts that do not fit in the header escape into the first section header entry
+        let (shnum, shstrndx, phnum) = resolve_extended_numbering(
+            read_at,
+            section_header_table_offset.to_u64()?,
+            section_header_table_entries,
+            section_header_table_names_index,
+            program_header_table_entries,
+            is32_bit,
+            is_little_endian)?;
+
         Ok(ElfHeader{
             e_ident: identifier,
-            e_type: Type::from_u16(to_u16(payload[0x11], payload[0x10])).ok_or("Invalid type")?,
-            e_machine: Machine::from_u16(to_u16(payload[0x13], payload[0x12])).ok_or("Invalid machine type")?,
-            e_verison: to_u32(payload[0x17], payload[0x15], payload[0x15], payload[0x14]),
-            e_entry: Word::build(&payload[0x22..0x26].try_into().unwrap(), &payload[0x18..0x1C].try_into().unwrap(), is32_bit, is_little_endian),
+            e_type: Type::from_u16(to_u16_from_slice(&payload[0x10..0x12].try_into().unwrap(), is_little_endian)).ok_or("Invalid type")?,
+            e_machine: Machine::from_u16(to_u16_from_slice(&payload[0x12..0x14].try_into().unwrap(), is_little_endian)).ok_or("Invalid machine type")?,
+            e_verison: to_u32_from_slice(&payload[consts::VERSION_START..consts::VERSION_END].try_into().unwrap(), is_little_endian),
+            e_entry: entry,
             e_phoff: program_header_offset,
             e_shoff: section_header_table_offset,
             e_flags: flags,
@@ -88,6 +113,9 @@ impl ElfHeader {
             e_shentsize: section_header_table_size,
             e_shnum: section_header_table_entries,
             e_shstrndx: section_header_table_names_index,
+            shnum,
+            shstrndx,
+            phnum,
         })
     }
 
@@ -110,10 +138,38 @@ impl ElfHeader {
         println!("\tFlags:\t\t\t\t{}{:04X}{}", gray(), &self.e_flags, default());
         println!("\tElf header size:\t\t{}{}{} (bytes)", green(), &self.e_ehsize, default());
         println!("\tProgram header entry size:\t{}{}{} (bytes)", green(), &self.e_phentsize, default());
-        println!("\tProgram header entries:\t\t{}{}{}", green(), &self.e_phnum, default());
+        println!("\tProgram header entries:\t\t{}{}{}", green(), extended_to_string(self.e_phnum, self.phnum), default());
         println!("\tSection header size:\t\t{}{}{} (bytes)", green(), &self.e_shentsize, default());
-        println!("\tSection header entries:\t\t{}{}{}", green(), &self.e_shnum, default());
-        println!("\tSection names index:\t\t{}{}{}", green(), &self.e_shstrndx, default());
+        println!("\tSection header entries:\t\t{}{}{}", green(), extended_to_string(self.e_shnum, self.shnum), default());
+        println!("\tSection names index:\t\t{}{}{}", green(), extended_to_string(self.e_shstrndx, self.shstrndx), default());
+    }
+
+    pub fn identifier(&self) -> &Identifier {
+        &self.e_ident
+    }
+
+    pub fn file_type(&self) -> &Type {
+        &self.e_type
+    }
+
+    pub fn machine(&self) -> Machine {
+        self.e_machine
+    }
+
+    pub fn version(&self) -> types::Elf32Word {
+        self.e_verison
+    }
+
+    pub fn entry(&self) -> Word {
+        self.e_entry
+    }
+
+    pub fn flags(&self) -> types::Elf32Word {
+        self.e_flags
+    }
+
+    pub fn header_size(&self) -> types::Elf32Half {
+        self.e_ehsize
     }
 
     pub fn is32_bit(&self) -> bool {
@@ -124,8 +180,9 @@ impl ElfHeader {
         self.e_phoff
     }
 
-    pub fn program_header_entries(&self) -> types::Elf32Half{
-        self.e_phnum
+    // Entry counts and the names index are resolved through section header 0 when they overflow the ELF header fields
+    pub fn program_header_entries(&self) -> types::Elf32Word {
+        self.phnum
     }
 
     pub fn program_header_size(&self) -> types::Elf32Half{
@@ -140,21 +197,59 @@ impl ElfHeader {
         self.e_shoff
     }
 
-    pub fn section_header_entries(&self) -> types::Elf32Half {
-        self.e_shnum
+    pub fn section_header_entries(&self) -> types::Elf32Word {
+        self.shnum
     }
 
     pub fn section_header_size(&self) -> types::Elf32Half {
         self.e_shentsize
     }
 
-    pub fn section_names_index(&self) -> types::Elf32Half {
-        self.e_shstrndx
+    pub fn section_names_index(&self) -> types::Elf32Word {
+        self.shstrndx
+    }
+
+}
+
+fn resolve_extended_numbering(read_at: impl FnOnce(u64, usize) -> Option<Vec<u8>>, section_header_offset: u64, shnum: types::Elf32Half, shstrndx: types::Elf32Half, phnum: types::Elf32Half, is32_bit: bool, is_little_endian: bool) -> Result<(types::Elf32Word, types::Elf32Word, types::Elf32Word), &'static str> {
+    let needs_first_section = (shnum == 0 && section_header_offset != 0) || shstrndx == SHN_XINDEX || phnum == PN_XNUM;
+    if !needs_first_section {
+        return Ok((shnum as types::Elf32Word, shstrndx as types::Elf32Word, phnum as types::Elf32Word));
     }
 
+    // sh_size, sh_link and sh_info of section header 0 hold the real e_shnum, e_shstrndx and e_phnum
+    let entry = read_at(section_header_offset, if is32_bit { 0x20 } else { 0x30 })
+        .ok_or("Extended numbering refers to a missing section header")?;
+    let (size, link, info) = if is32_bit {
+        (to_u32_from_slice(&entry[0x14..0x18].try_into().unwrap(), is_little_endian) as u64,
+         to_u32_from_slice(&entry[0x18..0x1C].try_into().unwrap(), is_little_endian),
+         to_u32_from_slice(&entry[0x1C..0x20].try_into().unwrap(), is_little_endian))
+    } else {
+        (to_u64_from_slice(&entry[0x20..0x28].try_into().unwrap(), is_little_endian),
+         to_u32_from_slice(&entry[0x28..0x2C].try_into().unwrap(), is_little_endian),
+         to_u32_from_slice(&entry[0x2C..0x30].try_into().unwrap(), is_little_endian))
+    };
+
+    Ok((
+        if shnum == 0 && section_header_offset != 0 { types::Elf32Word::try_from(size).map_err(|_| "Too many section headers")? } else { shnum as types::Elf32Word },
+        if shstrndx == SHN_XINDEX { link } else { shstrndx as types::Elf32Word },
+        if phnum == PN_XNUM { info } else { phnum as types::Elf32Word },
+    ))
+}
+
+// Reader over a file that is entirely in memory
+fn read_from(payload: &[u8]) -> impl FnOnce(u64, usize) -> Option<Vec<u8>> + '_ {
+    move |offset, size| payload.get(usize::try_from(offset).ok()?..)?.get(..size).map(<[u8]>::to_vec)
+}
 
+fn extended_to_string(value: types::Elf32Half, resolved: types::Elf32Word) -> String {
+    if value as types::Elf32Word == resolved {
+        value.to_string()
+    } else {
+        format!("{} ({})", value, resolved)
+    }
 }
- 
+
 use std::fmt::UpperHex;
 fn print_hex_arr<T, const N: usize>(bytes: &[T; N]) -> String
 where
@@ -166,3 +261,59 @@ where
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_extended_numbering_should_keep_regular_values() {
+        let result = resolve_extended_numbering(read_from(&[]), 0x40, 29, 28, 13, false, true);
+        assert_eq!(result, Ok((29, 28, 13)));
+    }
+
+    #[test]
+    fn resolve_extended_numbering_should_read_first_section_header() {
+        let mut payload = vec![0u8; 0x40];
+        payload[0x20..0x28].copy_from_slice(&70008u64.to_le_bytes());
+        payload[0x28..0x2C].copy_from_slice(&70007u32.to_le_bytes());
+        payload[0x2C..0x30].copy_from_slice(&70000u32.to_le_bytes());
+        let result = resolve_extended_numbering(read_from(&payload), 0x0, 0, SHN_XINDEX, PN_XNUM, false, true);
+        assert_eq!(result, Ok((0, 70007, 70000)));
+
+        let mut payload = vec![0u8; 0x50];
+        payload[0x30..0x38].copy_from_slice(&70008u64.to_le_bytes());
+        let result = resolve_extended_numbering(read_from(&payload), 0x10, 0, 5, 2, false, true);
+        assert_eq!(result, Ok((70008, 5, 2)));
+    }
+
+    #[test]
+    fn resolve_extended_numbering_should_reject_missing_section_header() {
+        let result = resolve_extended_numbering(read_from(&[0u8; 0x10]), 0x8, 0, SHN_XINDEX, 0, true, false);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn build_should_decode_version_and_entry_in_file_byte_order() {
+        let mut payload = vec![0u8; 0x40];
+        payload[0..7].copy_from_slice(&[0x7F, b'E', b'L', b'F', 2, 2, 1]);
+        payload[0x10..0x12].copy_from_slice(&2u16.to_be_bytes());
+        payload[0x12..0x14].copy_from_slice(&62u16.to_be_bytes());
+        payload[0x14..0x18].copy_from_slice(&1u32.to_be_bytes());
+        payload[0x18..0x20].copy_from_slice(&0x1122334455667788u64.to_be_bytes());
+        let header = ElfHeader::build(&payload).expect("Big endian header should parse");
+        assert_eq!(header.version(), 1);
+        assert_eq!(header.entry().to_u64(), Ok(0x1122334455667788));
+
+        payload[4] = 1;
+        payload[0x18..0x1C].copy_from_slice(&0x8048000u32.to_be_bytes());
+        let header = ElfHeader::build(&payload).expect("Big endian header should parse");
+        assert_eq!(header.entry().to_u64(), Ok(0x8048000));
+    }
+
+    #[test]
+    fn extended_to_string_should_show_resolved_value() {
+        assert_eq!(extended_to_string(13, 13), "13");
+        assert_eq!(extended_to_string(0, 70008), "0 (70008)");
+    }
+}
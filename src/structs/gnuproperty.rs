@@ -0,0 +1,145 @@
+use crate::bits::*;
+use crate::structs::machine::Machine;
+
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 0x1;
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 0x2;
+pub const GNU_PROPERTY_1_NEEDED: u32 = 0xB0008000;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xC0000000;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xC0000002;
+pub const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xC0008001;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xC0008002;
+pub const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xC0010001;
+pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xC0010002;
+
+const GNU_PROPERTY_1_NEEDED_FLAGS: [(u32, &str); 1] = [
+    (0x1, "indirect external access"),
+];
+
+const X86_FEATURE_1_FLAGS: [(u32, &str); 4] = [
+    (0x1, "IBT"),
+    (0x2, "SHSTK"),
+    (0x4, "LAM_U48"),
+    (0x8, "LAM_U57"),
+];
+
+const X86_FEATURE_2_FLAGS: [(u32, &str); 12] = [
+    (0x1, "x86"),
+    (0x2, "x87"),
+    (0x4, "MMX"),
+    (0x8, "XMM"),
+    (0x10, "YMM"),
+    (0x20, "ZMM"),
+    (0x40, "FXSR"),
+    (0x80, "XSAVE"),
+    (0x100, "XSAVEOPT"),
+    (0x200, "XSAVEC"),
+    (0x400, "TMM"),
+    (0x800, "MASK"),
+];
+
+const X86_ISA_1_FLAGS: [(u32, &str); 4] = [
+    (0x1, "x86-64-baseline"),
+    (0x2, "x86-64-v2"),
+    (0x4, "x86-64-v3"),
+    (0x8, "x86-64-v4"),
+];
+
+const AARCH64_FEATURE_1_FLAGS: [(u32, &str); 3] = [
+    (0x1, "BTI"),
+    (0x2, "PAC"),
+    (0x4, "GCS"),
+];
+
+fn bits_to_string(bits: u32, known: &[(u32, &str)]) -> String {
+    if bits == 0 {
+        return String::from("<None>");
+    }
+
+    let mut result: Vec<String> = known.iter()
+        .filter(|(mask, _)| bits & mask != 0)
+        .map(|(_, name)| String::from(*name))
+        .collect();
+
+    let unknown = known.iter().fold(bits, |rest, (mask, _)| rest & !mask);
+    if unknown != 0 {
+        result.push(format!("<unknown: {:x}>", unknown));
+    }
+
+    result.join(", ")
+}
+
+fn property_to_string(pr_type: u32, data: &[u8], machine: Machine, is_32bit: bool, is_little_endian: bool) -> String {
+    let word = || -> Option<u32> {
+        if data.len() == 4 {
+            Some(to_u32_from_slice(data.try_into().unwrap(), is_little_endian))
+        } else {
+            None
+        }
+    };
+
+    let described = match (pr_type, machine) {
+        (GNU_PROPERTY_STACK_SIZE, _) => match (data.len(), is_32bit) {
+            (4, true) => Some(format!("stack size: 0x{:x}", to_u32_from_slice(data.try_into().unwrap(), is_little_endian))),
+            (8, false) => Some(format!("stack size: 0x{:x}", to_u64_from_slice(data.try_into().unwrap(), is_little_endian))),
+            _ => None,
+        },
+        (GNU_PROPERTY_NO_COPY_ON_PROTECTED, _) if data.is_empty() => Some(String::from("no copy on protected")),
+        (GNU_PROPERTY_1_NEEDED, _) => word().map(|bits| format!("1_needed: {}", bits_to_string(bits, &GNU_PROPERTY_1_NEEDED_FLAGS))),
+        (GNU_PROPERTY_X86_FEATURE_1_AND, Machine::EMX8664 | Machine::EM386) => word().map(|bits| format!("x86 feature: {}", bits_to_string(bits, &X86_FEATURE_1_FLAGS))),
+        (GNU_PROPERTY_X86_FEATURE_2_NEEDED, Machine::EMX8664 | Machine::EM386) => word().map(|bits| format!("x86 feature needed: {}", bits_to_string(bits, &X86_FEATURE_2_FLAGS))),
+        (GNU_PROPERTY_X86_FEATURE_2_USED, Machine::EMX8664 | Machine::EM386) => word().map(|bits| format!("x86 feature used: {}", bits_to_string(bits, &X86_FEATURE_2_FLAGS))),
+        (GNU_PROPERTY_X86_ISA_1_NEEDED, Machine::EMX8664 | Machine::EM386) => word().map(|bits| format!("x86 ISA needed: {}", bits_to_string(bits, &X86_ISA_1_FLAGS))),
+        (GNU_PROPERTY_X86_ISA_1_USED, Machine::EMX8664 | Machine::EM386) => word().map(|bits| format!("x86 ISA used: {}", bits_to_string(bits, &X86_ISA_1_FLAGS))),
+        (GNU_PROPERTY_AARCH64_FEATURE_1_AND, Machine::EMAARCH64) => word().map(|bits| format!("AArch64 feature: {}", bits_to_string(bits, &AARCH64_FEATURE_1_FLAGS))),
+        _ => Some(format!("<unknown type 0x{:x} data size 0x{:x}>", pr_type, data.len())),
+    };
+
+    described.unwrap_or_else(|| format!("<corrupt type 0x{:x} data size 0x{:x}>", pr_type, data.len()))
+}
+
+pub fn properties_to_string(descriptor: &[u8], machine: Machine, is_32bit: bool, is_little_endian: bool) -> String {
+    // Each property is padded to the natural word size of the file class
+    let alignment = if is_32bit { 4 } else { 8 };
+    let mut properties: Vec<String> = Vec::new();
+    let mut offset = 0;
+
+    while offset + 8 <= descriptor.len() {
+        let pr_type = to_u32_from_slice(&descriptor[offset..offset+4].try_into().unwrap(), is_little_endian);
+        let pr_datasz = to_u32_from_slice(&descriptor[offset+4..offset+8].try_into().unwrap(), is_little_endian) as usize;
+        let data_start = offset + 8;
+
+        if data_start + pr_datasz > descriptor.len() {
+            properties.push(format!("<corrupt type 0x{:x} data size 0x{:x}>", pr_type, pr_datasz));
+            break;
+        }
+
+        properties.push(property_to_string(pr_type, &descriptor[data_start..data_start+pr_datasz], machine, is_32bit, is_little_endian));
+        offset = (data_start + pr_datasz).next_multiple_of(alignment);
+    }
+
+    format!("Properties: {}", properties.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn properties_to_string_should_decode_x86_features_and_isa() {
+        let descriptor: Vec<u8> = vec![
+            0x02, 0x00, 0x00, 0xC0, 0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x80, 0x00, 0xC0, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let result = properties_to_string(&descriptor, Machine::EMX8664, false, true);
+        assert_eq!(result, "Properties: x86 feature: IBT, SHSTK, x86 ISA needed: x86-64-baseline");
+    }
+
+    #[test]
+    fn properties_to_string_should_decode_aarch64_features() {
+        let descriptor: Vec<u8> = vec![
+            0x00, 0x00, 0x00, 0xC0, 0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let result = properties_to_string(&descriptor, Machine::EMAARCH64, false, true);
+        assert_eq!(result, "Properties: AArch64 feature: BTI, PAC");
+    }
+}
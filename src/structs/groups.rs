@@ -0,0 +1,123 @@
+use crate::bits::*;
+use crate::structs::sectionheader::SectionHeader;
+use crate::structs::sectionheadertype::SectionHeaderType;
+use crate::structs::symbols::SymbolTable;
+use crate::termcolors;
+
+pub const GRP_COMDAT: u32 = 0x1;
+pub const GRP_MASKOS: u32 = 0x0FF00000;
+pub const GRP_MASKPROC: u32 = 0xF0000000;
+
+#[derive(Debug)]
+pub struct SectionGroup {
+    index: usize,
+    name: String,
+    signature: String,
+    flags: u32,
+    members: Vec<(u32, String)>,
+}
+
+impl SectionGroup {
+    pub fn build(payload: &[u8], index: usize, sections: &SectionHeader, symbols: Option<&SymbolTable>, is_little_endian: bool) -> Result<SectionGroup, &'static str> {
+        let section = sections.get(index).ok_or("Section group index out of range")?;
+        if !matches!(section.section_type(), SectionHeaderType::ShtGroup) {
+            return Err("Section is not a section group");
+        }
+
+        let start = section.offset().to_u64()? as usize;
+        let contents = start.checked_add(section.size().to_u64()? as usize)
+            .and_then(|end| payload.get(start..end))
+            .filter(|contents| contents.len() >= 4)
+            .ok_or("Section group too short")?;
+
+        // The first word holds the group flags, the remaining ones are member section indices
+        let mut words = contents.chunks_exact(4)
+            .map(|chunk| to_u32_from_slice(chunk.try_into().unwrap(), is_little_endian));
+        let flags = words.next().ok_or("Section group too short")?;
+
+        let members = words.map(|member| {
+            let name = sections.get(member as usize)
+                .map(|member_section| member_section.name().to_string())
+                .unwrap_or(String::from("<corrupt>"));
+            (member, name)
+        }).collect();
+
+        // sh_link names the symbol table and sh_info the signature symbol within it
+        let signature = symbols
+            .and_then(|table| table.entries().get(section.info() as usize))
+            .map(|symbol| symbol.name().clone())
+            .unwrap_or(String::from("<corrupt>"));
+
+        Ok(SectionGroup{index, name: section.name().to_string(), signature, flags, members})
+    }
+
+    pub fn print(&self) {
+        println!("{}{} section [{:5}] `{}' [{}{}{}] contains {} sections:{}",
+            termcolors::purple(),
+            group_flags_to_string(self.flags),
+            self.index,
+            self.name,
+            termcolors::yellow(),
+            self.signature,
+            termcolors::purple(),
+            self.members.len(),
+            termcolors::default());
+        println!("   {}[Index]    {}Name", termcolors::red(), termcolors::white());
+        for (index, name) in self.members.iter() {
+            println!("   {}[{:5}]   {}{}", termcolors::red(), index, termcolors::white(), name);
+        }
+        print!("{}", termcolors::default());
+    }
+}
+
+fn group_flags_to_string(flags: u32) -> String {
+    let mut result: Vec<String> = Vec::new();
+
+    if flags & GRP_COMDAT != 0 {
+        result.push(String::from("COMDAT"));
+    }
+
+    if flags & GRP_MASKOS != 0 {
+        result.push(format!("OS specific (0x{:x})", flags & GRP_MASKOS));
+    }
+
+    if flags & GRP_MASKPROC != 0 {
+        result.push(format!("Processor specific (0x{:x})", flags & GRP_MASKPROC));
+    }
+
+    let unknown = flags & !(GRP_COMDAT | GRP_MASKOS | GRP_MASKPROC);
+    if unknown != 0 {
+        result.push(format!("<unknown: 0x{:x}>", unknown));
+    }
+
+    if result.is_empty() {
+        String::from("group")
+    } else {
+        format!("{} group", result.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elffile::Source;
+    use crate::structs::machine::Machine;
+
+    #[test]
+    fn build_should_reject_groups_outside_the_file() {
+        let mut table = [0u8; 0x40];
+        table[0x04..0x08].copy_from_slice(&17u32.to_le_bytes()); // SHT_GROUP
+        table[0x18..0x20].copy_from_slice(&u64::MAX.to_le_bytes());
+        table[0x20..0x28].copy_from_slice(&0x8u64.to_le_bytes());
+        let sections = SectionHeader::from_table(&table, 0x40, b"", Source::Memory(&[]), Machine::EMX8664, false, true);
+        assert_eq!(SectionGroup::build(&[0u8; 0x40], 0, &sections, None, true).unwrap_err(), "Section group too short");
+    }
+
+    #[test]
+    fn group_flags_to_string_should_describe_comdat() {
+        assert_eq!(group_flags_to_string(0), "group");
+        assert_eq!(group_flags_to_string(GRP_COMDAT), "COMDAT group");
+        assert_eq!(group_flags_to_string(GRP_COMDAT | 0x10000000), "COMDAT Processor specific (0x10000000) group");
+        assert_eq!(group_flags_to_string(0x2), "<unknown: 0x2> group");
+    }
+}
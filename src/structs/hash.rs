@@ -0,0 +1,332 @@
+use crate::bits::*;
+use crate::structs::sectionheader::SectionHeaderEntry;
+use crate::structs::sectionheadertype::SectionHeaderType;
+use crate::structs::symbols::{SymbolEntry, SymbolTable, SHN_UNDEF};
+use crate::termcolors;
+
+#[derive(Debug)]
+pub struct SysvHashTable {
+    buckets: Vec<u32>,
+    chains: Vec<u32>,
+}
+
+impl SysvHashTable {
+    fn build(words: &[u32]) -> Result<SysvHashTable, &'static str> {
+        if words.len() < 2 {
+            return Err("Hash table too short");
+        }
+
+        let nbucket = words[0] as usize;
+        let nchain = words[1] as usize;
+
+        if words.len() < 2 + nbucket + nchain {
+            return Err("Hash table buckets or chains exceed its section");
+        }
+
+        Ok(SysvHashTable{
+            buckets: words[2..2 + nbucket].to_vec(),
+            chains: words[2 + nbucket..2 + nbucket + nchain].to_vec()})
+    }
+
+    fn lookup(&self, name: &str, symbols: &SymbolTable) -> Option<usize> {
+        if self.buckets.is_empty() {
+            return None;
+        }
+
+        let mut index = self.buckets[sysv_hash(name) as usize % self.buckets.len()] as usize;
+        let mut visited = 0;
+
+        // Chains are terminated by STN_UNDEF, the visit counter only guards against corrupt loops
+        while index != 0 && visited < self.chains.len() {
+            if symbols.entries().get(index).is_some_and(|symbol| is_matching_definition(symbol, name)) {
+                return Some(index);
+            }
+            index = *self.chains.get(index)? as usize;
+            visited += 1;
+        }
+
+        None
+    }
+
+    fn chain_lengths(&self) -> Vec<usize> {
+        self.buckets.iter().map(|&bucket| {
+            let mut index = bucket as usize;
+            let mut length = 0;
+            while index != 0 && index < self.chains.len() && length < self.chains.len() {
+                length += 1;
+                index = self.chains[index] as usize;
+            }
+            length
+        }).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct GnuHashTable {
+    symbol_offset: u32,
+    bloom_shift: u32,
+    bloom: Vec<u64>,
+    bloom_word_bits: u32,
+    buckets: Vec<u32>,
+    chains: Vec<u32>,
+}
+
+impl GnuHashTable {
+    fn build(payload: &[u8], symbol_count: usize, is_32bit: bool, is_little_endian: bool) -> Result<GnuHashTable, &'static str> {
+        if payload.len() < 16 {
+            return Err("GNU hash table too short");
+        }
+
+        let word = |offset: usize| to_u32_from_slice(&payload[offset..offset+4].try_into().unwrap(), is_little_endian);
+        let nbuckets = word(0) as usize;
+        let symbol_offset = word(4);
+        let bloom_size = word(8) as usize;
+        let bloom_shift = word(12);
+
+        // The bloom filter is made of ELF class sized words
+        let bloom_word_size = if is_32bit { 4 } else { 8 };
+        let buckets_offset = 16 + bloom_size * bloom_word_size;
+        let chains_offset = buckets_offset + nbuckets * 4;
+        let chain_count = symbol_count.saturating_sub(symbol_offset as usize);
+
+        if payload.len() < chains_offset + chain_count * 4 {
+            return Err("GNU hash table buckets or chains exceed its section");
+        }
+
+        let bloom = (0..bloom_size).map(|index| {
+            let offset = 16 + index * bloom_word_size;
+            if is_32bit {
+                word(offset) as u64
+            } else {
+                to_u64_from_slice(&payload[offset..offset+8].try_into().unwrap(), is_little_endian)
+            }
+        }).collect();
+
+        Ok(GnuHashTable{
+            symbol_offset,
+            bloom_shift,
+            bloom,
+            bloom_word_bits: bloom_word_size as u32 * 8,
+            buckets: (0..nbuckets).map(|index| word(buckets_offset + index * 4)).collect(),
+            chains: (0..chain_count).map(|index| word(chains_offset + index * 4)).collect()})
+    }
+
+    // The shift comes from the file, a corrupt one of 32 or more shifts every bit out
+    fn bloom_mask(&self, hash: u32) -> u64 {
+        (1u64 << (hash % self.bloom_word_bits))
+            | (1u64 << (hash.checked_shr(self.bloom_shift).unwrap_or(0) % self.bloom_word_bits))
+    }
+
+    fn lookup(&self, name: &str, symbols: &SymbolTable) -> Option<usize> {
+        if self.buckets.is_empty() || self.bloom.is_empty() {
+            return None;
+        }
+
+        let hash = gnu_hash(name);
+        let bloom_word = self.bloom[(hash / self.bloom_word_bits) as usize % self.bloom.len()];
+        let mask = self.bloom_mask(hash);
+
+        // Both bits must be set for the symbol to possibly be present
+        if bloom_word & mask != mask {
+            return None;
+        }
+
+        let mut index = self.buckets[hash as usize % self.buckets.len()] as usize;
+        if index < self.symbol_offset as usize {
+            return None;
+        }
+
+        loop {
+            let chain_hash = *self.chains.get(index - self.symbol_offset as usize)?;
+
+            // The lowest bit marks the end of the chain, so it is ignored when comparing hashes
+            if (hash | 1) == (chain_hash | 1)
+                && symbols.entries().get(index).is_some_and(|symbol| is_matching_definition(symbol, name)) {
+                return Some(index);
+            }
+
+            if chain_hash & 1 != 0 {
+                return None;
+            }
+            index += 1;
+        }
+    }
+
+    fn chain_lengths(&self) -> Vec<usize> {
+        self.buckets.iter().map(|&bucket| {
+            if bucket < self.symbol_offset {
+                return 0;
+            }
+
+            let start = (bucket - self.symbol_offset) as usize;
+            match self.chains.iter().skip(start).position(|chain_hash| chain_hash & 1 != 0) {
+                Some(position) => position + 1,
+                None => self.chains.len().saturating_sub(start),
+            }
+        }).collect()
+    }
+}
+
+#[derive(Debug)]
+pub enum HashTable {
+    Sysv(String, SysvHashTable),
+    Gnu(String, GnuHashTable),
+}
+
+impl HashTable {
+    pub fn build(payload: &[u8], section: &SectionHeaderEntry, symbol_count: usize, is_32bit: bool, is_little_endian: bool) -> Result<HashTable, &'static str> {
+        let start = section.offset().to_u64()? as usize;
+        let contents = start.checked_add(section.size().to_u64()? as usize)
+            .and_then(|end| payload.get(start..end))
+            .ok_or("Hash section too short")?;
+
+        match section.section_type() {
+            SectionHeaderType::ShtHash => {
+                let words: Vec<u32> = contents.chunks_exact(4)
+                    .map(|chunk| to_u32_from_slice(chunk.try_into().unwrap(), is_little_endian))
+                    .collect();
+                Ok(HashTable::Sysv(section.name().to_string(), SysvHashTable::build(&words)?))
+            },
+            SectionHeaderType::ShtGnuHash => Ok(HashTable::Gnu(section.name().to_string(), GnuHashTable::build(contents, symbol_count, is_32bit, is_little_endian)?)),
+            _ => Err("Section is not a hash table"),
+        }
+    }
+
+    // Returns the index of the defined dynamic symbol, walking the table the same way ld.so does
+    pub fn lookup(&self, name: &str, symbols: &SymbolTable) -> Option<usize> {
+        match self {
+            HashTable::Sysv(_, table) => table.lookup(name, symbols),
+            HashTable::Gnu(_, table) => table.lookup(name, symbols),
+        }
+    }
+
+    pub fn print_histogram(&self) {
+        let (name, lengths) = match self {
+            HashTable::Sysv(name, table) => (name, table.chain_lengths()),
+            HashTable::Gnu(name, table) => (name, table.chain_lengths()),
+        };
+
+        println!("{}Histogram for '{}' bucket list length (total of {} buckets):{}", termcolors::purple(), name, lengths.len(), termcolors::default());
+        println!(" {}Length  {}Number     {}% of total  {}Coverage",
+            termcolors::green(),
+            termcolors::yellow(),
+            termcolors::blue(),
+            termcolors::white());
+
+        let histogram = chain_length_histogram(&lengths);
+        let total_symbols: usize = lengths.iter().sum();
+        let mut covered_symbols = 0;
+
+        for (length, &count) in histogram.iter().enumerate() {
+            print!(" {}{:6}  {}{:<10} {}({:5.1}%)",
+                termcolors::green(),
+                length,
+                termcolors::yellow(),
+                count,
+                termcolors::blue(),
+                percentage(count, lengths.len()));
+
+            if length > 0 {
+                covered_symbols += length * count;
+                print!("    {}{:5.1}%", termcolors::white(), percentage(covered_symbols, total_symbols));
+            }
+            println!();
+        }
+        print!("{}", termcolors::default());
+    }
+}
+
+fn is_matching_definition(symbol: &SymbolEntry, name: &str) -> bool {
+    // Undefined symbols and hidden versions cannot satisfy an unversioned lookup
+    symbol.section_index() != SHN_UNDEF
+        && !symbol.version().is_some_and(|version| version.hidden)
+        && symbol.name() == name
+}
+
+fn chain_length_histogram(lengths: &[usize]) -> Vec<usize> {
+    let mut histogram = vec![0; lengths.iter().max().map_or(0, |max| max + 1)];
+    for &length in lengths {
+        histogram[length] += 1;
+    }
+    histogram
+}
+
+fn percentage(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
+
+pub fn sysv_hash(name: &str) -> u32 {
+    name.bytes().fold(0u32, |hash, byte| {
+        let hash = (hash << 4).wrapping_add(byte as u32);
+        let high = hash & 0xF0000000;
+        (hash ^ (high >> 24)) & !high
+    })
+}
+
+pub fn gnu_hash(name: &str) -> u32 {
+    name.bytes().fold(5381u32, |hash, byte| hash.wrapping_mul(33).wrapping_add(byte as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::machine::Machine;
+
+    #[test]
+    fn hash_functions_should_match_reference_values() {
+        assert_eq!(sysv_hash(""), 0);
+        assert_eq!(sysv_hash("printf"), 0x077905a6);
+        assert_eq!(sysv_hash("freelocale"), 0x0c335095);
+        assert_eq!(gnu_hash(""), 0x00001505);
+        assert_eq!(gnu_hash("printf"), 0x156b2bb8);
+        assert_eq!(gnu_hash("freelocale"), 0x49feb217);
+    }
+
+    #[test]
+    fn build_should_reject_sections_outside_the_file() {
+        let mut header = [0u8; 0x40];
+        header[0x04..0x08].copy_from_slice(&5u32.to_le_bytes()); // SHT_HASH
+        header[0x18..0x20].copy_from_slice(&u64::MAX.to_le_bytes());
+        header[0x20..0x28].copy_from_slice(&0x10u64.to_le_bytes());
+        let section = SectionHeaderEntry::build(&header, 0, Machine::EMX8664, false, true);
+        assert_eq!(HashTable::build(&[0u8; 0x40], &section, 0, false, true).unwrap_err(), "Hash section too short");
+    }
+
+    #[test]
+    fn sysv_chain_lengths_should_follow_chains() {
+        // Two buckets: the first holds symbols 1 -> 3, the second is empty
+        let table = SysvHashTable::build(&[2, 4, 1, 0, 0, 3, 0, 0]).expect("Should parse hash table");
+        assert_eq!(table.chain_lengths(), vec![2, 0]);
+        assert_eq!(chain_length_histogram(&table.chain_lengths()), vec![1, 0, 1]);
+    }
+
+    #[test]
+    fn gnu_chain_lengths_should_stop_at_marked_hash() {
+        let table = GnuHashTable{
+            symbol_offset: 2,
+            bloom_shift: 6,
+            bloom: vec![0],
+            bloom_word_bits: 64,
+            buckets: vec![2, 0, 4],
+            chains: vec![0x10, 0x21, 0x31]};
+        assert_eq!(table.chain_lengths(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn gnu_bloom_mask_should_survive_oversized_shift() {
+        let mut table = GnuHashTable{
+            symbol_offset: 1,
+            bloom_shift: 6,
+            bloom: vec![0],
+            bloom_word_bits: 64,
+            buckets: vec![1],
+            chains: vec![0]};
+        assert_eq!(table.bloom_mask(0x81), (1 << 1) | (1 << 2));
+        table.bloom_shift = 40;
+        assert_eq!(table.bloom_mask(0x81), (1 << 1) | 1);
+    }
+}
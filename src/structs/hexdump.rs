@@ -0,0 +1,65 @@
+use crate::termcolors;
+
+const BYTES_PER_LINE: usize = 16;
+
+// Equivalent of readelf -x: 16 bytes per line in four words followed by their ASCII rendering
+pub fn print_hex_dump(title: &str, address: u64, data: &[u8]) {
+    println!("{}Hex dump of {}:{}", termcolors::purple(), title, termcolors::default());
+    for (index, line) in data.chunks(BYTES_PER_LINE).enumerate() {
+        println!("{}", format_line(address.wrapping_add((index * BYTES_PER_LINE) as u64), line));
+    }
+    print!("{}", termcolors::default());
+}
+
+// Zero bytes and non-printable characters are dimmed so the interesting data stands out
+fn format_line(address: u64, bytes: &[u8]) -> String {
+    let mut line = format!("  {}0x{:08x} ", termcolors::green(), address);
+
+    for index in 0..BYTES_PER_LINE {
+        match bytes.get(index) {
+            Some(0) => line += &format!("{}00", termcolors::gray()),
+            Some(byte) => line += &format!("{}{:02x}", termcolors::white(), byte),
+            None => line += "  ",
+        }
+        if index % 4 == 3 {
+            line += " ";
+        }
+    }
+
+    for byte in bytes.iter() {
+        match byte {
+            0x20..=0x7E => line += &format!("{}{}", termcolors::white(), *byte as char),
+            _ => line += &format!("{}.", termcolors::gray()),
+        }
+    }
+
+    line + termcolors::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip_colors(line: &str) -> String {
+        let mut result = String::new();
+        let mut in_escape = false;
+        for c in line.chars() {
+            match (in_escape, c) {
+                (false, '\x1b') => in_escape = true,
+                (true, 'm') => in_escape = false,
+                (false, c) => result.push(c),
+                _ => (),
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn format_line_should_match_readelf_layout() {
+        let line = strip_colors(&format_line(0x2000, b"GCC: (Debian 12."));
+        assert_eq!(line, "  0x00002000 4743433a 20284465 6269616e 2031322e GCC: (Debian 12.");
+
+        let line = strip_colors(&format_line(0x10, &[0x00, 0x41, 0x0A]));
+        assert_eq!(line, "  0x00000010 00410a                              .A.");
+    }
+}
@@ -0,0 +1,185 @@
+use crate::bits::*;
+use crate::structs::relocation::RelocationTable;
+use crate::structs::sectionheader::SectionHeaderEntry;
+use crate::structs::sectionheadertype::SectionHeaderType;
+use crate::structs::symbols::{SymbolTable, SHN_UNDEF};
+use crate::structs::symboltype::SymbolType;
+use crate::termcolors;
+use crate::types;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ArrayKind {
+    Preinit,
+    Init,
+    Fini,
+}
+
+#[derive(Debug)]
+struct InitArrayEntry {
+    target: u64,
+    // Section of the target, only known in relocatable files where every section starts at zero
+    target_section: Option<types::Elf32Half>,
+    symbol: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct InitArray {
+    name: String,
+    kind: ArrayKind,
+    address: u64,
+    word_size: usize,
+    entries: Vec<InitArrayEntry>,
+}
+
+impl InitArray {
+    pub fn build(section: &SectionHeaderEntry, data: &[u8], is_32bit: bool, is_little_endian: bool) -> Result<InitArray, &'static str> {
+        let kind = match section.section_type() {
+            SectionHeaderType::ShtPreinitArray => ArrayKind::Preinit,
+            SectionHeaderType::ShtInitArray => ArrayKind::Init,
+            SectionHeaderType::ShtFiniArray => ArrayKind::Fini,
+            _ => return Err("Section is not an initialization or termination array"),
+        };
+
+        let word_size = if is_32bit { 4 } else { 8 };
+        if !data.len().is_multiple_of(word_size) {
+            return Err("Array size is not a multiple of the pointer size");
+        }
+
+        Ok(InitArray {
+            name: section.name().to_string(),
+            kind,
+            address: section.address().to_u64().unwrap(),
+            word_size,
+            entries: read_pointers(data, is_32bit, is_little_endian).into_iter()
+                .map(|target| InitArrayEntry { target, target_section: None, symbol: None })
+                .collect(),
+        })
+    }
+
+    // Fills in slots from relocations whose offsets are relative to base: the section address for dynamic
+    // relocations of linked files, zero for the section's own relocations in relocatable files
+    pub fn apply_relocations(&mut self, table: &RelocationTable, symbols: Option<&SymbolTable>, base: u64) {
+        for relocation in table.entries() {
+            let offset = relocation.offset().wrapping_sub(base);
+            let slot = (offset / self.word_size as u64) as usize;
+            if !offset.is_multiple_of(self.word_size as u64) || slot >= self.entries.len() {
+                continue;
+            }
+
+            let entry = &mut self.entries[slot];
+            let addend = relocation.addend().unwrap_or(entry.target as i64) as u64;
+            if relocation.type_name().ends_with("_RELATIVE") {
+                entry.target = addend;
+                continue;
+            }
+
+            let symbol = match symbols.and_then(|table| table.entries().get(relocation.symbol_index() as usize)) {
+                Some(symbol) => symbol,
+                None => continue,
+            };
+            entry.target = symbol.value().to_u64().unwrap().wrapping_add(addend);
+            entry.target_section = Some(symbol.section_index());
+            if symbol.section_index() == SHN_UNDEF {
+                entry.symbol = Some(symbol.name().clone());
+            }
+        }
+    }
+
+    // Names each target after the function that starts at or contains it
+    pub fn resolve_symbols(&mut self, symbols: &SymbolTable) {
+        for entry in self.entries.iter_mut().filter(|entry| entry.symbol.is_none()) {
+            let candidates = symbols.entries().iter().filter(|symbol| {
+                symbol.section_index() != SHN_UNDEF
+                    && matches!(symbol.symbol_type(), SymbolType::SttFunc | SymbolType::SttGnuIfunc)
+                    && entry.target_section.is_none_or(|section| section == symbol.section_index())
+            });
+
+            let mut best: Option<(u64, &String)> = None;
+            for symbol in candidates {
+                let value = symbol.value().to_u64().unwrap();
+                let size = symbol.size().to_u64().unwrap();
+                let contains = entry.target == value || (entry.target > value && entry.target < value + size);
+                if contains && best.is_none_or(|(start, _)| value > start) {
+                    best = Some((value, symbol.name()));
+                }
+            }
+
+            entry.symbol = best.map(|(value, name)| match entry.target - value {
+                0 => name.clone(),
+                offset => format!("{}+0x{:x}", name, offset),
+            });
+        }
+    }
+
+    pub fn print(&self) {
+        let order = match self.kind {
+            ArrayKind::Fini => "last to first",
+            _ => "first to last",
+        };
+        println!("{}Section '{}' at 0x{:x} contains {} {}, run {}:{}",
+            termcolors::purple(),
+            self.name,
+            self.address,
+            self.entries.len(),
+            if self.entries.len() == 1 { "entry" } else { "entries" },
+            order,
+            termcolors::default());
+
+        let width = self.word_size * 2;
+        let mut entries: Vec<(usize, &InitArrayEntry)> = self.entries.iter().enumerate().collect();
+        if self.kind == ArrayKind::Fini {
+            entries.reverse();
+        }
+        for (index, entry) in entries {
+            println!("  {}[{:2}] {}0x{:0width$x}  {}{}{}",
+                termcolors::gray(),
+                index,
+                termcolors::green(),
+                entry.target,
+                termcolors::white(),
+                entry.symbol.as_deref().unwrap_or("??"),
+                termcolors::default());
+        }
+    }
+}
+
+// Preinit arrays run first and fini arrays last; within a kind, the linker places .init_array.NNNNN
+// sections of relocatable files by ascending priority ahead of the plain section
+pub fn sort_by_execution_order(arrays: &mut [InitArray]) {
+    arrays.sort_by(|a, b| {
+        a.kind.cmp(&b.kind).then(priority(&a.name).cmp(&priority(&b.name)))
+    });
+}
+
+fn priority(name: &str) -> u32 {
+    name.rsplit_once('.')
+        .and_then(|(_, suffix)| suffix.parse::<u32>().ok())
+        .unwrap_or(u32::MAX)
+}
+
+fn read_pointers(data: &[u8], is_32bit: bool, is_little_endian: bool) -> Vec<u64> {
+    if is_32bit {
+        data.chunks_exact(4).map(|word| to_u32_from_slice(word.try_into().unwrap(), is_little_endian) as u64).collect()
+    } else {
+        data.chunks_exact(8).map(|word| to_u64_from_slice(word.try_into().unwrap(), is_little_endian)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_pointers_should_honour_class_and_endianness() {
+        let data = [0x20, 0x11, 0, 0, 0, 0, 0, 0, 0xE0, 0x10, 0, 0, 0, 0, 0, 0];
+        assert_eq!(read_pointers(&data, false, true), vec![0x1120, 0x10E0]);
+        assert_eq!(read_pointers(&data[..8], true, false), vec![0x20110000, 0]);
+    }
+
+    #[test]
+    fn priority_should_order_numbered_sections_first() {
+        assert_eq!(priority(".init_array.00101"), 101);
+        assert_eq!(priority(".init_array"), u32::MAX);
+        assert!(priority(".init_array.65535") < priority(".init_array"));
+    }
+}
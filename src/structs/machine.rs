@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Machine {
     EMNONE, 
     EMM32, 
@@ -82,6 +82,8 @@ pub enum Machine {
     EMTPC, 
     EMSNP1K, 
     EMST200,
+    EMAARCH64,
+    EMRISCV,
     Reserved
 }
 
@@ -174,6 +176,8 @@ impl Machine{
     98 => Some(Machine::EMTPC),	
     99 => Some(Machine::EMSNP1K),	
     100 => Some(Machine::EMST200),	
+    183 => Some(Machine::EMAARCH64),	
+    243 => Some(Machine::EMRISCV),	
             _ => None,
         }
     }
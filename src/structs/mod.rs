@@ -12,3 +12,29 @@ pub mod segmentflags;
 pub mod sectionheader;
 pub mod sectionheadertype;
 pub mod sectionheaderflags;
+pub mod symbols;
+pub mod symbolbinding;
+pub mod symboltype;
+pub mod symbolvisibility;
+pub mod dynamic;
+pub mod dynamictag;
+pub mod dynamicflags;
+pub mod relocation;
+pub mod relocationtype;
+pub mod notes;
+pub mod gnuproperty;
+pub mod versions;
+pub mod hash;
+pub mod groups;
+pub mod compression;
+pub mod dwarfreader;
+pub mod dwarf;
+pub mod debugline;
+pub mod ehframe;
+pub mod coredump;
+pub mod hexdump;
+pub mod x86decoder;
+pub mod disassembly;
+pub mod initarray;
+pub mod segmentmapping;
+pub mod resolution;
@@ -0,0 +1,237 @@
+use crate::bits::*;
+use crate::elffile::Source;
+use crate::structs::machine::Machine;
+use crate::termcolors;
+
+use super::gnuproperty;
+
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_HWCAP: u32 = 2;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+pub const NT_PRSTATUS: u32 = 1;
+pub const NT_FPREGSET: u32 = 2;
+pub const NT_PRPSINFO: u32 = 3;
+pub const NT_AUXV: u32 = 6;
+pub const NT_SIGINFO: u32 = 0x53494749;
+pub const NT_FILE: u32 = 0x46494C45;
+pub const NT_X86_XSTATE: u32 = 0x202;
+
+const NOTE_HEADER_SIZE: usize = 12;
+
+#[derive(Debug)]
+pub struct Note {
+    n_name: String,
+    n_type: u32,
+    n_desc: Vec<u8>,
+}
+
+impl Note {
+    pub fn print(&self, machine: Machine, is_32bit: bool, is_little_endian: bool) {
+        println!("  {}{:20} {}0x{:08x}\t{}{}",
+            termcolors::green(),
+            self.n_name,
+            termcolors::blue(),
+            self.n_desc.len(),
+            termcolors::yellow(),
+            self.type_to_string());
+
+        if let Some(description) = self.describe(machine, is_32bit, is_little_endian) {
+            println!("    {}{}", termcolors::white(), description);
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.n_name
+    }
+
+    pub fn note_type(&self) -> u32 {
+        self.n_type
+    }
+
+    pub fn descriptor(&self) -> &[u8] {
+        &self.n_desc
+    }
+
+    pub fn build_id(&self) -> Option<String> {
+        if self.n_name == "GNU" && self.n_type == NT_GNU_BUILD_ID {
+            Some(to_hex_string(&self.n_desc))
+        } else {
+            None
+        }
+    }
+
+    fn type_to_string(&self) -> String {
+        match (self.n_name.as_str(), self.n_type) {
+            ("GNU", NT_GNU_ABI_TAG) => String::from("NT_GNU_ABI_TAG (ABI version tag)"),
+            ("GNU", NT_GNU_HWCAP) => String::from("NT_GNU_HWCAP (DSO-supplied software HWCAP info)"),
+            ("GNU", NT_GNU_BUILD_ID) => String::from("NT_GNU_BUILD_ID (unique build ID bitstring)"),
+            ("GNU", NT_GNU_GOLD_VERSION) => String::from("NT_GNU_GOLD_VERSION (gold version)"),
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => String::from("NT_GNU_PROPERTY_TYPE_0"),
+            ("CORE", NT_PRSTATUS) => String::from("NT_PRSTATUS (prstatus structure)"),
+            ("CORE", NT_FPREGSET) => String::from("NT_FPREGSET (floating point registers)"),
+            ("CORE", NT_PRPSINFO) => String::from("NT_PRPSINFO (prpsinfo structure)"),
+            ("CORE", NT_AUXV) => String::from("NT_AUXV (auxiliary vector)"),
+            ("CORE", NT_SIGINFO) => String::from("NT_SIGINFO (siginfo_t data)"),
+            ("CORE", NT_FILE) => String::from("NT_FILE (mapped files)"),
+            ("LINUX", NT_X86_XSTATE) => String::from("NT_X86_XSTATE (x86 XSAVE extended state)"),
+            (_, value) => format!("Unknown note type: (0x{:08x})", value),
+        }
+    }
+
+    fn describe(&self, machine: Machine, is_32bit: bool, is_little_endian: bool) -> Option<String> {
+        if self.n_name != "GNU" {
+            return None;
+        }
+
+        match self.n_type {
+            NT_GNU_ABI_TAG if self.n_desc.len() >= 16 => {
+                let word = |index: usize| to_u32_from_slice(&self.n_desc[index*4..index*4+4].try_into().unwrap(), is_little_endian);
+                let os = match word(0) {
+                    0 => String::from("Linux"),
+                    1 => String::from("Hurd"),
+                    2 => String::from("Solaris"),
+                    3 => String::from("FreeBSD"),
+                    4 => String::from("NetBSD"),
+                    5 => String::from("Syllable"),
+                    6 => String::from("NaCl"),
+                    value => format!("Unknown ({})", value),
+                };
+                Some(format!("OS: {}, ABI: {}.{}.{}", os, word(1), word(2), word(3)))
+            },
+            NT_GNU_BUILD_ID => self.build_id().map(|id| format!("Build ID: {}", id)),
+            NT_GNU_GOLD_VERSION => {
+                let end = self.n_desc.iter().position(|&b| b == 0).unwrap_or(self.n_desc.len());
+                Some(format!("Version: {}", String::from_utf8_lossy(&self.n_desc[..end])))
+            },
+            NT_GNU_PROPERTY_TYPE_0 => Some(gnuproperty::properties_to_string(&self.n_desc, machine, is_32bit, is_little_endian)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NoteSection {
+    name: String,
+    entries: Vec<Note>,
+}
+
+impl NoteSection {
+    pub fn build(source: Source, name: String, offset: u64, size: u64, alignment: u64, is_little_endian: bool) -> Result<NoteSection, &'static str> {
+        let payload = source.read_at(offset, size).map_err(|_| "Note section too short")?;
+        let end = payload.len();
+
+        // Notes are 4-byte aligned, except for the 8-byte aligned ones such as .note.gnu.property
+        let alignment = if alignment == 8 { 8 } else { 4 };
+        let mut entries: Vec<Note> = Vec::new();
+        let mut note_offset = 0;
+
+        while note_offset + NOTE_HEADER_SIZE <= end {
+            let namesz = to_u32_from_slice(&payload[note_offset..note_offset+4].try_into().unwrap(), is_little_endian) as usize;
+            let descsz = to_u32_from_slice(&payload[note_offset+4..note_offset+8].try_into().unwrap(), is_little_endian) as usize;
+            let n_type = to_u32_from_slice(&payload[note_offset+8..note_offset+12].try_into().unwrap(), is_little_endian);
+
+            let name_offset = note_offset + NOTE_HEADER_SIZE;
+            let (desc_offset, desc_end, next_offset) = note_layout(note_offset, namesz, descsz, alignment)
+                .filter(|(_, desc_end, _)| *desc_end <= end)
+                .ok_or("Note entry exceeds its section")?;
+
+            let name_bytes = &payload[name_offset..name_offset+namesz];
+            let name_end = name_bytes.iter().position(|&b| b == 0).unwrap_or(namesz);
+
+            entries.push(Note{
+                n_name: String::from_utf8_lossy(&name_bytes[..name_end]).into_owned(),
+                n_type,
+                n_desc: payload[desc_offset..desc_end].to_vec()});
+
+            note_offset = next_offset;
+        }
+
+        Ok(NoteSection{name, entries})
+    }
+
+    pub fn entries(&self) -> &[Note] {
+        &self.entries
+    }
+
+    pub fn print(&self, machine: Machine, is_32bit: bool, is_little_endian: bool) {
+        println!("{}Displaying notes found in: {}{}", termcolors::purple(), self.name, termcolors::default());
+        println!("  {}Owner\t\t       {}Data size\t{}Description",
+            termcolors::green(),
+            termcolors::blue(),
+            termcolors::yellow());
+        for entry in self.entries.iter() {
+            entry.print(machine, is_32bit, is_little_endian);
+        }
+        print!("{}", termcolors::default());
+    }
+}
+
+fn to_hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Offsets of the descriptor, its end and the next note. Padding is computed relative to the start of the note,
+// not the name or descriptor, and sizes from the file may be large enough to overflow
+fn note_layout(note_offset: usize, namesz: usize, descsz: usize, alignment: usize) -> Option<(usize, usize, usize)> {
+    let desc_offset = note_offset.checked_add(NOTE_HEADER_SIZE.checked_add(namesz)?.checked_next_multiple_of(alignment)?)?;
+    let desc_end = desc_offset.checked_add(descsz)?;
+    let next_offset = note_offset.checked_add((desc_end - note_offset).checked_next_multiple_of(alignment)?)?;
+    Some((desc_offset, desc_end, next_offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_id_note() -> Vec<u8> {
+        vec![
+            0x04, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+            0x47, 0x4E, 0x55, 0x00,
+            0xDE, 0xAD, 0xBE, 0xEF,
+        ]
+    }
+
+    #[test]
+    fn note_section_should_parse_build_id() {
+        let payload = build_id_note();
+        let notes = NoteSection::build(Source::Memory(&payload), String::from(".note.gnu.build-id"), 0, payload.len() as u64, 4, true).expect("Should parse note");
+        assert_eq!(notes.entries.len(), 1);
+        assert_eq!(notes.entries[0].n_name, "GNU");
+        assert_eq!(notes.entries[0].n_type, NT_GNU_BUILD_ID);
+        assert_eq!(notes.entries[0].n_desc, [0xDE, 0xAD, 0xBE, 0xEF]);
+        assert_eq!(notes.entries[0].build_id(), Some(String::from("deadbeef")));
+    }
+
+    #[test]
+    fn note_section_should_pad_relative_to_note_start_when_8_byte_aligned() {
+        let mut payload: Vec<u8> = vec![
+            0x04, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00,
+            0x47, 0x4E, 0x55, 0x00,
+            0x01, 0x02, 0x03, 0x04, 0x00, 0x00, 0x00, 0x00,
+        ];
+        payload.extend(build_id_note());
+        let notes = NoteSection::build(Source::Memory(&payload), String::from(".note.gnu.property"), 0, payload.len() as u64, 8, true).expect("Should parse notes");
+        assert_eq!(notes.entries.len(), 2);
+        assert_eq!(notes.entries[0].n_desc, [0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(notes.entries[1].build_id(), Some(String::from("deadbeef")));
+    }
+
+    #[test]
+    fn note_section_should_reject_truncated_descriptor() {
+        let mut payload = build_id_note();
+        payload.truncate(18);
+        let notes = NoteSection::build(Source::Memory(&payload), String::from(".note"), 0, payload.len() as u64, 4, true);
+        assert!(notes.is_err());
+    }
+
+    #[test]
+    fn note_layout_should_not_overflow_on_huge_sizes() {
+        assert_eq!(note_layout(0, 4, 4, 4), Some((16, 20, 20)));
+        assert_eq!(note_layout(0x10, usize::MAX - 8, 0, 4), None);
+        assert_eq!(note_layout(0x10, 4, usize::MAX - 0x10, 4), None);
+        assert!(NoteSection::build(Source::Memory(&build_id_note()), String::from(".note"), u64::MAX, 2, 4, true).is_err());
+    }
+}
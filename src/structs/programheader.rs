@@ -3,11 +3,16 @@ use crate::structs::word::Word;
 use crate::termcolors;
 use crate::termcolors::*;
 use crate::types;
+use crate::structs::sectionheader::string_until_null;
+use crate::elffile::{check_table, Source, PROGRAM_HEADER32_SIZE, PROGRAM_HEADER64_SIZE};
 
 use super::segmentflags;
+use super::machine::Machine;
 use super::segmenttype::SegmentType;
 
-#[derive(Debug)]
+use std::borrow::Cow;
+
+#[derive(Debug, Clone)]
 pub struct ProgramHeaderEntry {
     p_type: SegmentType,
     p_flags: types::Elf32Word,
@@ -17,9 +22,69 @@ pub struct ProgramHeaderEntry {
     p_filesz: Word,
     p_memsz: Word,
     p_align: Word,
+    p_interpreter: Option<Result<String, String>>,
 }
 
 impl ProgramHeaderEntry {
+    // Decodes the entry at entry_offset, the caller checks that the whole table lies inside payload.
+    // The interpreter path of PT_INTERP segments lives elsewhere in the file and is left to ProgramHeader
+    pub fn build(payload: &[u8], entry_offset: usize, machine: Machine, is_32bit: bool, is_little_endian: bool) -> ProgramHeaderEntry {
+        const FIELD_SIZE : usize = 4;
+
+        let p_type = SegmentType::from_u32(&payload[entry_offset..entry_offset+FIELD_SIZE].try_into().unwrap(), machine, is_little_endian);
+        let p_flags;
+        let p_offset;
+        let p_vaddr;
+        let p_paddr;
+        let p_filesz;
+        let p_memsz;
+        let p_align;
+
+        if is_32bit {
+            let p_flags_offset = entry_offset + 0x18;
+            p_flags = to_u32_from_slice(&payload[p_flags_offset..p_flags_offset+4].try_into().unwrap(), is_little_endian);
+            let p_offset_offset = entry_offset + 0x04;
+            p_offset = Word::Bits32(to_u32_from_slice(&payload[p_offset_offset..p_offset_offset+4].try_into().unwrap(), is_little_endian));
+            let p_vaddr_offset = entry_offset + 0x08;
+            p_vaddr = Word::Bits32(to_u32_from_slice(&payload[p_vaddr_offset..p_vaddr_offset+4].try_into().unwrap(), is_little_endian));
+            let p_paddr_offset = entry_offset + 0x0C;
+            p_paddr = Word::Bits32(to_u32_from_slice(&payload[p_paddr_offset..p_paddr_offset+4].try_into().unwrap(), is_little_endian));
+            let p_filesz_offset = entry_offset + 0x10;
+            p_filesz = Word::Bits32(to_u32_from_slice(&payload[p_filesz_offset..p_filesz_offset+4].try_into().unwrap(), is_little_endian));
+            let p_memsz_offset = entry_offset + 0x14;
+            p_memsz = Word::Bits32(to_u32_from_slice(&payload[p_memsz_offset..p_memsz_offset+4].try_into().unwrap(), is_little_endian));
+            let p_align_offset = entry_offset + 0x1C;
+            p_align = Word::Bits32(to_u32_from_slice(&payload[p_align_offset..p_align_offset+4].try_into().unwrap(), is_little_endian));
+
+        } else {
+            let p_flags_offset = entry_offset + 0x4;
+            p_flags = to_u32_from_slice(&payload[p_flags_offset..p_flags_offset+4].try_into().unwrap(), is_little_endian);
+            let p_offset_offset = entry_offset + 0x08;
+            p_offset = Word::Bits64(to_u64_from_slice(&payload[p_offset_offset..p_offset_offset+8].try_into().unwrap(), is_little_endian));
+            let p_vaddr_offset = entry_offset + 0x10;
+            p_vaddr = Word::Bits64(to_u64_from_slice(&payload[p_vaddr_offset..p_vaddr_offset+8].try_into().unwrap(), is_little_endian));
+            let p_paddr_offset = entry_offset + 0x18;
+            p_paddr = Word::Bits64(to_u64_from_slice(&payload[p_paddr_offset..p_paddr_offset+8].try_into().unwrap(), is_little_endian));
+            let p_filesz_offset = entry_offset + 0x20;
+            p_filesz = Word::Bits64(to_u64_from_slice(&payload[p_filesz_offset..p_filesz_offset+8].try_into().unwrap(), is_little_endian));
+            let p_memsz_offset = entry_offset + 0x28;
+            p_memsz = Word::Bits64(to_u64_from_slice(&payload[p_memsz_offset..p_memsz_offset+8].try_into().unwrap(), is_little_endian));
+            let p_align_offset = entry_offset + 0x30;
+            p_align = Word::Bits64(to_u64_from_slice(&payload[p_align_offset..p_align_offset+8].try_into().unwrap(), is_little_endian));
+        }
+
+        ProgramHeaderEntry{
+            p_type: p_type,
+            p_flags: p_flags,
+            p_offset: p_offset,
+            p_vaddr: p_vaddr,
+            p_paddr: p_paddr,
+            p_filesz: p_filesz,
+            p_memsz: p_memsz,
+            p_align: p_align,
+            p_interpreter: None}
+    }
+
     pub fn print(&self) {
         println!("{:8x}\t{:8x}\t{:8x}\t{:8x}\t{:8x}\t{:4}\t{:8x}\t{}",
             self.p_offset,
@@ -30,90 +95,110 @@ impl ProgramHeaderEntry {
             segmentflags::flags_to_string(self.p_flags),
             self.p_align,
             self.p_type);
+
+        match &self.p_interpreter {
+            Some(Ok(path)) => println!("\t\t{}[Requesting program interpreter: {}]{}", termcolors::green(), path, termcolors::default()),
+            Some(Err(diagnostic)) => println!("\t\t{}[{}]{}", termcolors::red(), diagnostic, termcolors::default()),
+            None => (),
+        }
+    }
+
+    pub fn segment_type(&self) -> &SegmentType {
+        &self.p_type
+    }
+
+    pub fn flags(&self) -> types::Elf32Word {
+        self.p_flags
+    }
+
+    pub fn offset(&self) -> Word {
+        self.p_offset
+    }
+
+    pub fn virtual_address(&self) -> Word {
+        self.p_vaddr
+    }
+
+    pub fn physical_address(&self) -> Word {
+        self.p_paddr
+    }
+
+    pub fn file_size(&self) -> Word {
+        self.p_filesz
+    }
+
+    pub fn memory_size(&self) -> Word {
+        self.p_memsz
+    }
+
+    pub fn alignment(&self) -> Word {
+        self.p_align
+    }
+
+    // Only PT_INTERP segments have one, the error describes why the path could not be read
+    pub fn interpreter(&self) -> Option<&Result<String, String>> {
+        self.p_interpreter.as_ref()
     }
 }
 
 pub struct ProgramHeaderInfo{
     pub offset: Word,
-    pub entries: types::Elf32Half,
+    pub entries: types::Elf32Word,
     pub size: types::Elf32Half,
 }
 
-#[derive(Debug)]
-pub struct ProgramHeader{
-    entries: Vec<ProgramHeaderEntry>
+// Program header table viewed in place, entries are decoded each time they are asked for
+#[derive(Debug, Clone, Copy)]
+pub struct ProgramHeader<'data> {
+    table: &'data [u8],
+    entry_size: usize,
+    source: Source<'data>,
+    machine: Machine,
+    is_32bit: bool,
+    is_little_endian: bool,
 }
 
-impl ProgramHeader {
-    pub fn build(payload: &[u8], info: &ProgramHeaderInfo, is_32bit: bool, is_little_endian: bool) -> Result<ProgramHeader, &'static str> {
-        if payload.len() < info.offset.to_u64().unwrap() as usize + info.size as usize { 
-            return Err("Program header too short");
-        }
+impl<'data> ProgramHeader<'data> {
+    pub fn build(payload: &'data [u8], info: &ProgramHeaderInfo, machine: Machine, is_32bit: bool, is_little_endian: bool) -> Result<ProgramHeader<'data>, &'static str> {
+        let minimum_entry_size = if is_32bit { PROGRAM_HEADER32_SIZE } else { PROGRAM_HEADER64_SIZE };
+        let offset = info.offset.to_u64()?;
+        check_table(payload.len() as u64, offset, info.size, minimum_entry_size, info.entries)?;
 
-        let mut entries: Vec<ProgramHeaderEntry> = Vec::new();
-        let first_entry_offset: u64 = info.offset.to_u64()?;
-        
-        const FIELD_SIZE : usize = 4;
+        let table = match info.entries {
+            0 => &[],
+            entries => &payload[offset as usize..offset as usize + info.size as usize * entries as usize],
+        };
+        Ok(ProgramHeader::from_table(table, info.size, Source::Memory(payload), machine, is_32bit, is_little_endian))
+    }
 
-        for i in 0..info.entries {
-            let entry_offset = (first_entry_offset + info.size as u64 * i as u64) as usize;
-
-            let p_type = SegmentType::from_u32(&payload[entry_offset..entry_offset+FIELD_SIZE].try_into().unwrap(), is_little_endian).expect("Tried to get segment type");
-            let p_flags;
-            let p_offset;
-            let p_vaddr;
-            let p_paddr;
-            let p_filesz;
-            let p_memsz;
-            let p_align;
-
-            if is_32bit {
-                let p_flags_offset = entry_offset + 0x18;
-                p_flags = to_u32_from_slice(&payload[p_flags_offset..p_flags_offset+4].try_into().unwrap(), is_little_endian);
-                let p_offset_offset = entry_offset + 0x04;
-                p_offset = Word::Bits32(to_u32_from_slice(&payload[p_offset_offset..p_offset_offset+4].try_into().unwrap(), is_little_endian));
-                let p_vaddr_offset = entry_offset + 0x08;
-                p_vaddr = Word::Bits32(to_u32_from_slice(&payload[p_vaddr_offset..p_vaddr_offset+4].try_into().unwrap(), is_little_endian));
-                let p_paddr_offset = entry_offset + 0x0C;
-                p_paddr = Word::Bits32(to_u32_from_slice(&payload[p_paddr_offset..p_paddr_offset+4].try_into().unwrap(), is_little_endian));
-                let p_filesz_offset = entry_offset + 0x10;
-                p_filesz = Word::Bits32(to_u32_from_slice(&payload[p_filesz_offset..p_filesz_offset+4].try_into().unwrap(), is_little_endian));
-                let p_memsz_offset = entry_offset + 0x14;
-                p_memsz = Word::Bits32(to_u32_from_slice(&payload[p_memsz_offset..p_memsz_offset+4].try_into().unwrap(), is_little_endian));
-                let p_align_offset = entry_offset + 0x1C;
-                p_align = Word::Bits32(to_u32_from_slice(&payload[p_align_offset..p_align_offset+4].try_into().unwrap(), is_little_endian));
-
-            } else {
-                let p_flags_offset = entry_offset + 0x4;
-                p_flags = to_u32_from_slice(&payload[p_flags_offset..p_flags_offset+4].try_into().unwrap(), is_little_endian);
-                let p_offset_offset = entry_offset + 0x08;
-                p_offset = Word::Bits64(to_u64_from_slice(&payload[p_offset_offset..p_offset_offset+8].try_into().unwrap(), is_little_endian));
-                let p_vaddr_offset = entry_offset + 0x10;
-                p_vaddr = Word::Bits64(to_u64_from_slice(&payload[p_vaddr_offset..p_vaddr_offset+8].try_into().unwrap(), is_little_endian));
-                let p_paddr_offset = entry_offset + 0x18;
-                p_paddr = Word::Bits64(to_u64_from_slice(&payload[p_paddr_offset..p_paddr_offset+8].try_into().unwrap(), is_little_endian));
-                let p_filesz_offset = entry_offset + 0x20;
-                p_filesz = Word::Bits64(to_u64_from_slice(&payload[p_filesz_offset..p_filesz_offset+8].try_into().unwrap(), is_little_endian));
-                let p_memsz_offset = entry_offset + 0x28;
-                p_memsz = Word::Bits64(to_u64_from_slice(&payload[p_memsz_offset..p_memsz_offset+8].try_into().unwrap(), is_little_endian));
-                let p_align_offset = entry_offset + 0x30;
-                p_align = Word::Bits64(to_u64_from_slice(&payload[p_align_offset..p_align_offset+8].try_into().unwrap(), is_little_endian));
-            }
-
-            let entry = ProgramHeaderEntry{
-                p_type: p_type,
-                p_flags: p_flags,
-                p_offset: p_offset,
-                p_vaddr: p_vaddr,
-                p_paddr: p_paddr,
-                p_filesz: p_filesz,
-                p_memsz: p_memsz,
-                p_align: p_align};
-
-            entries.push(entry);
+    // Table that was already read and checked, source is where the interpreter paths are fetched from
+    pub(crate) fn from_table(table: &'data [u8], entry_size: types::Elf32Half, source: Source<'data>, machine: Machine, is_32bit: bool, is_little_endian: bool) -> ProgramHeader<'data> {
+        ProgramHeader{table, entry_size: entry_size as usize, source, machine, is_32bit, is_little_endian}
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len().checked_div(self.entry_size).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<ProgramHeaderEntry> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let mut entry = ProgramHeaderEntry::build(self.table, index * self.entry_size, self.machine, self.is_32bit, self.is_little_endian);
+        if matches!(entry.p_type, SegmentType::PtInterp) {
+            entry.p_interpreter = Some(read_interpreter(self.source, entry.p_offset.to_u64().unwrap(), entry.p_filesz.to_u64().unwrap()));
         }
+        Some(entry)
+    }
 
-        Ok(ProgramHeader{entries: entries})
+    pub fn entries(&self) -> impl Iterator<Item = ProgramHeaderEntry> + 'data {
+        let header = *self;
+        (0..header.len()).filter_map(move |index| header.get(index))
     }
 
     pub fn print(&self) {
@@ -129,9 +214,109 @@ impl ProgramHeader {
             termcolors::gray(),
             termcolors::red(),
             termcolors::default());
-        for (index, entry) in self.entries.iter().enumerate() {
+        for (index, entry) in self.entries().enumerate() {
             print!("{}{index:3}\t{}", termcolors::red(), termcolors::default());
             entry.print();
         }
     }
+
+    pub fn virtual_address_to_offset(&self, address: u64) -> Option<u64> {
+        self.entries()
+            .filter(|entry| matches!(entry.p_type, SegmentType::PtLoad))
+            .find_map(|entry| {
+                let vaddr = entry.p_vaddr.to_u64().unwrap();
+                let filesz = entry.p_filesz.to_u64().unwrap();
+                if address >= vaddr && address < vaddr + filesz {
+                    Some(entry.p_offset.to_u64().unwrap() + (address - vaddr))
+                } else {
+                    None
+                }
+            })
+    }
+
+    pub fn offset_to_virtual_address(&self, offset: u64) -> Option<u64> {
+        self.entries()
+            .filter(|entry| matches!(entry.p_type, SegmentType::PtLoad))
+            .find_map(|entry| {
+                let file_offset = entry.p_offset.to_u64().unwrap();
+                let filesz = entry.p_filesz.to_u64().unwrap();
+                if offset >= file_offset && offset < file_offset + filesz {
+                    Some(entry.p_vaddr.to_u64().unwrap() + (offset - file_offset))
+                } else {
+                    None
+                }
+            })
+    }
+
+    // File offset of [start, end), provided a single PT_LOAD segment backs the whole range from the file
+    pub fn virtual_range_to_offset(&self, start: u64, end: u64) -> Option<u64> {
+        self.entries()
+            .filter(|entry| matches!(entry.p_type, SegmentType::PtLoad))
+            .find_map(|entry| {
+                let vaddr = entry.p_vaddr.to_u64().unwrap();
+                let filesz = entry.p_filesz.to_u64().unwrap();
+                if start >= vaddr && vaddr.checked_add(filesz).is_some_and(|segment_end| end <= segment_end) {
+                    entry.p_offset.to_u64().unwrap().checked_add(start - vaddr)
+                } else {
+                    None
+                }
+            })
+    }
+
+    // Up to length bytes mapped at address, cut short where the file ends
+    pub fn read_virtual(&self, address: u64, length: u64) -> Option<Cow<'data, [u8]>> {
+        let offset = self.virtual_address_to_offset(address)?;
+        self.source.read_at(offset, length.min(self.source.size().saturating_sub(offset))).ok()
+    }
+}
+
+fn read_interpreter(source: Source, offset: u64, size: u64) -> Result<String, String> {
+    match source.read_at(offset, size) {
+        Ok(bytes) => Ok(string_until_null(&bytes)),
+        Err(_) => Err(format!("Program interpreter segment at 0x{:x} (size 0x{:x}) lies outside the file (size 0x{:x})", offset, size, source.size())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_interpreter_should_stop_at_null() {
+        let payload = b"XX/lib/ld.so\0YY";
+        let result = read_interpreter(Source::Memory(payload), 2, 12);
+        assert_eq!(result, Ok(String::from("/lib/ld.so")));
+    }
+
+    #[test]
+    fn read_interpreter_should_report_segment_outside_file() {
+        let payload = b"/lib/ld.so\0";
+        assert!(read_interpreter(Source::Memory(payload), 4, 64).is_err());
+        assert!(read_interpreter(Source::Memory(payload), u64::MAX, 2).is_err());
+    }
+
+    #[test]
+    fn virtual_range_to_offset_should_reject_segments_wrapping_the_address_space() {
+        let load_segment = |offset: u64, vaddr: u64, filesz: u64| {
+            let mut payload = vec![0u8; PROGRAM_HEADER64_SIZE as usize];
+            payload[0..4].copy_from_slice(&1u32.to_le_bytes());
+            payload[8..16].copy_from_slice(&offset.to_le_bytes());
+            payload[16..24].copy_from_slice(&vaddr.to_le_bytes());
+            payload[32..40].copy_from_slice(&filesz.to_le_bytes());
+            payload
+        };
+        let info = ProgramHeaderInfo {offset: Word::Bits64(0), entries: 1, size: PROGRAM_HEADER64_SIZE as u16};
+
+        let payload = load_segment(0x40, 0x1000, 0x100);
+        let program_header = ProgramHeader::build(&payload, &info, Machine::EMX8664, false, true).unwrap();
+        assert_eq!(program_header.virtual_range_to_offset(0x1010, 0x1020), Some(0x50));
+
+        let payload = load_segment(0x40, u64::MAX - 0xf, 0x100);
+        let program_header = ProgramHeader::build(&payload, &info, Machine::EMX8664, false, true).unwrap();
+        assert_eq!(program_header.virtual_range_to_offset(u64::MAX - 0xf, u64::MAX), None);
+
+        let payload = load_segment(u64::MAX, 0x1000, 0x100);
+        let program_header = ProgramHeader::build(&payload, &info, Machine::EMX8664, false, true).unwrap();
+        assert_eq!(program_header.virtual_range_to_offset(0x1010, 0x1020), None);
+    }
 }
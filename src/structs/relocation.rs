@@ -0,0 +1,327 @@
+use crate::bits::*;
+use crate::structs::machine::Machine;
+use crate::structs::sectionheader::SectionHeaderEntry;
+use crate::structs::sectionheadertype::SectionHeaderType;
+use crate::structs::symbols::SymbolTable;
+use crate::structs::word::Word;
+use crate::termcolors;
+
+use super::relocationtype::relocation_type_to_string;
+
+#[derive(Debug)]
+pub struct RelocationEntry {
+    r_offset: Word,
+    r_info: Word,
+    r_addend: Option<i64>,
+    r_type: u32,
+    r_type_str: String,
+    symbol: Option<(String, Word)>,
+}
+
+impl RelocationEntry {
+    pub fn offset(&self) -> u64 {
+        self.r_offset.to_u64().unwrap()
+    }
+
+    pub fn addend(&self) -> Option<i64> {
+        self.r_addend
+    }
+
+    pub fn type_name(&self) -> &String {
+        &self.r_type_str
+    }
+
+    pub fn symbol_index(&self) -> u64 {
+        match self.r_info {
+            Word::Bits32(info) => (info >> 8) as u64,
+            Word::Bits64(info) => info >> 32,
+        }
+    }
+
+    pub fn symbol_name(&self) -> Option<&String> {
+        self.symbol.as_ref().map(|(name, _)| name)
+    }
+
+    pub fn print(&self) {
+        let width = match self.r_offset {
+            Word::Bits32(_) => 8,
+            Word::Bits64(_) => 16,
+        };
+
+        print!("{}{:0width$x}  {}{:0width$x} {}{:24}",
+            termcolors::green(),
+            self.r_offset.to_u64().unwrap(),
+            termcolors::gray(),
+            self.r_info.to_u64().unwrap(),
+            termcolors::yellow(),
+            self.r_type_str);
+
+        match (&self.symbol, self.r_addend) {
+            (Some((name, value)), Some(addend)) => println!(" {}{:0width$x} {}{} {} {:x}",
+                termcolors::blue(),
+                value.to_u64().unwrap(),
+                termcolors::white(),
+                name,
+                if addend < 0 { "-" } else { "+" },
+                addend.unsigned_abs()),
+            (Some((name, value)), None) => println!(" {}{:0width$x} {}{}",
+                termcolors::blue(),
+                value.to_u64().unwrap(),
+                termcolors::white(),
+                name),
+            (None, Some(addend)) => println!(" {:width$} {}{}{:x}",
+                "",
+                termcolors::white(),
+                if addend < 0 { "-" } else { "" },
+                addend.unsigned_abs()),
+            (None, None) => println!(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RelocationTable {
+    name: String,
+    offset: u64,
+    entries: Vec<RelocationEntry>,
+}
+
+impl RelocationTable {
+    pub fn build(payload: &[u8], section: &SectionHeaderEntry, symbols: Option<&SymbolTable>, machine: Machine, is_32bit: bool, is_little_endian: bool) -> Result<RelocationTable, &'static str> {
+        let has_addend = match section.section_type() {
+            SectionHeaderType::ShtRela => true,
+            SectionHeaderType::ShtRel => false,
+            _ => return Err("Section is not a relocation table"),
+        };
+
+        let table_offset = section.offset().to_u64()? as usize;
+        let table_size = section.size().to_u64()? as usize;
+
+        if table_offset.checked_add(table_size).is_none_or(|end| payload.len() < end) {
+            return Err("Relocation table too short");
+        }
+
+        let field_size = if is_32bit { 4 } else { 8 };
+        let minimum_entry_size = if has_addend { 3 * field_size } else { 2 * field_size };
+        let entry_size = match section.entry_size().to_u64()? as usize {
+            0 => minimum_entry_size,
+            size if size < minimum_entry_size => return Err("Relocation entries are too small"),
+            size => size,
+        };
+
+        let mut entries: Vec<RelocationEntry> = Vec::new();
+
+        for i in 0..table_size / entry_size {
+            let entry_offset = table_offset + i * entry_size;
+            let r_offset;
+            let r_info;
+            let r_sym;
+            let r_type;
+            let mut r_addend = None;
+
+            if is_32bit {
+                r_offset = Word::Bits32(to_u32_from_slice(&payload[entry_offset..entry_offset+4].try_into().unwrap(), is_little_endian));
+                let info = to_u32_from_slice(&payload[entry_offset+4..entry_offset+8].try_into().unwrap(), is_little_endian);
+                r_info = Word::Bits32(info);
+                r_sym = info >> 8;
+                r_type = vec![info & 0xFF];
+                if has_addend {
+                    r_addend = Some(to_u32_from_slice(&payload[entry_offset+8..entry_offset+12].try_into().unwrap(), is_little_endian) as i32 as i64);
+                }
+            } else {
+                r_offset = Word::Bits64(to_u64_from_slice(&payload[entry_offset..entry_offset+8].try_into().unwrap(), is_little_endian));
+                let mut info = to_u64_from_slice(&payload[entry_offset+8..entry_offset+16].try_into().unwrap(), is_little_endian);
+                if machine == Machine::EMMIPS && is_little_endian {
+                    info = mips64_little_endian_info(info);
+                }
+                r_info = Word::Bits64(info);
+                r_sym = (info >> 32) as u32;
+                r_type = if machine == Machine::EMMIPS {
+                    // MIPS64 packs up to three relocation types into the lower word
+                    vec![(info & 0xFF) as u32, ((info >> 8) & 0xFF) as u32, ((info >> 16) & 0xFF) as u32]
+                } else {
+                    vec![(info & 0xFFFFFFFF) as u32]
+                };
+                if has_addend {
+                    r_addend = Some(to_u64_from_slice(&payload[entry_offset+16..entry_offset+24].try_into().unwrap(), is_little_endian) as i64);
+                }
+            }
+
+            let r_type_str = r_type.iter().enumerate()
+                .filter(|(index, value)| *index == 0 || **value != 0)
+                .map(|(_, value)| relocation_type_to_string(machine, *value))
+                .collect::<Vec<String>>()
+                .join(" / ");
+
+            let symbol = match (r_sym, symbols) {
+                (0, _) | (_, None) => None,
+                (index, Some(table)) => table.entries().get(index as usize)
+                    .map(|symbol| (symbol.versioned_name(), symbol.value())),
+            };
+
+            entries.push(RelocationEntry{r_offset, r_info, r_addend, r_type: r_type[0], r_type_str, symbol});
+        }
+
+        Ok(RelocationTable{name: section.name().to_string(), offset: table_offset as u64, entries})
+    }
+
+    // Resolves absolute data relocations in place, as needed for the non-allocated sections of relocatable objects
+    pub fn apply(&self, data: &mut [u8], machine: Machine, is_little_endian: bool) -> Result<(), &'static str> {
+        self.apply_matching(data, None, machine, is_little_endian)
+    }
+
+    // Resolves PC-relative relocations in place (S + A - P), as used by .eh_frame pointers of relocatable objects
+    pub fn apply_pc_relative(&self, data: &mut [u8], section_address: u64, machine: Machine, is_little_endian: bool) -> Result<(), &'static str> {
+        self.apply_matching(data, Some(section_address), machine, is_little_endian)
+    }
+
+    fn apply_matching(&self, data: &mut [u8], section_address: Option<u64>, machine: Machine, is_little_endian: bool) -> Result<(), &'static str> {
+        for entry in self.entries.iter() {
+            let width = match section_address {
+                None => absolute_relocation_width(machine, entry.r_type),
+                Some(_) => pc_relative_relocation_width(machine, entry.r_type),
+            };
+            let width = match width {
+                Some(width) => width,
+                None => continue,
+            };
+
+            let offset = entry.r_offset.to_u64()? as usize;
+            let target = data.get_mut(offset..offset + width).ok_or("Relocation offset outside of its section")?;
+            let symbol_value = match &entry.symbol {
+                Some((_, value)) => value.to_u64()?,
+                None => 0,
+            };
+
+            // REL relocations keep their addend in the relocated field itself
+            let addend = match entry.r_addend {
+                Some(addend) => addend as u64,
+                None if width == 4 => to_u32_from_slice(&(*target).try_into().unwrap(), is_little_endian) as u64,
+                None => to_u64_from_slice(&(*target).try_into().unwrap(), is_little_endian),
+            };
+
+            let mut value = symbol_value.wrapping_add(addend);
+            if let Some(address) = section_address {
+                value = value.wrapping_sub(address.wrapping_add(offset as u64));
+            }
+            match (width, is_little_endian) {
+                (4, true) => target.copy_from_slice(&(value as u32).to_le_bytes()),
+                (4, false) => target.copy_from_slice(&(value as u32).to_be_bytes()),
+                (_, true) => target.copy_from_slice(&value.to_le_bytes()),
+                (_, false) => target.copy_from_slice(&value.to_be_bytes()),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[RelocationEntry] {
+        &self.entries
+    }
+
+    pub fn print(&self) {
+        println!("{}Relocation section '{}' at offset 0x{:x} contains {} entries:{}", termcolors::purple(), self.name, self.offset, self.entries.len(), termcolors::default());
+        println!("{}Offset\t\t  {}Info\t\t   {}Type\t\t\t    {}Sym. Value\t     {}Sym. Name + Addend",
+            termcolors::green(),
+            termcolors::gray(),
+            termcolors::yellow(),
+            termcolors::blue(),
+            termcolors::white());
+        for entry in self.entries.iter() {
+            entry.print();
+        }
+        print!("{}", termcolors::default());
+    }
+}
+
+// Size in bytes of the relocations that simply store S + A, the only kind found in debug sections
+fn absolute_relocation_width(machine: Machine, r_type: u32) -> Option<usize> {
+    match (machine, r_type) {
+        (Machine::EMX8664, 1) => Some(8),           // R_X86_64_64
+        (Machine::EMX8664, 10 | 11) => Some(4),     // R_X86_64_32, R_X86_64_32S
+        (Machine::EM386, 1) => Some(4),             // R_386_32
+        (Machine::EMAARCH64, 257) => Some(8),       // R_AARCH64_ABS64
+        (Machine::EMAARCH64, 258) => Some(4),       // R_AARCH64_ABS32
+        (Machine::EMARM, 2) => Some(4),             // R_ARM_ABS32
+        (Machine::EMRISCV, 1) => Some(4),           // R_RISCV_32
+        (Machine::EMRISCV, 2) => Some(8),           // R_RISCV_64
+        (Machine::EMPPC64, 1) => Some(4),           // R_PPC64_ADDR32
+        (Machine::EMPPC64, 38) => Some(8),          // R_PPC64_ADDR64
+        (Machine::EMMIPS, 2) => Some(4),            // R_MIPS_32
+        (Machine::EMMIPS, 18) => Some(8),           // R_MIPS_64
+        _ => None,
+    }
+}
+
+// Size in bytes of the relocations that store S + A - P
+fn pc_relative_relocation_width(machine: Machine, r_type: u32) -> Option<usize> {
+    match (machine, r_type) {
+        (Machine::EMX8664, 2) => Some(4),           // R_X86_64_PC32
+        (Machine::EMX8664, 24) => Some(8),          // R_X86_64_PC64
+        (Machine::EM386, 2) => Some(4),             // R_386_PC32
+        (Machine::EMAARCH64, 260) => Some(8),       // R_AARCH64_PREL64
+        (Machine::EMAARCH64, 261) => Some(4),       // R_AARCH64_PREL32
+        (Machine::EMARM, 3) => Some(4),             // R_ARM_REL32
+        (Machine::EMRISCV, 57) => Some(4),          // R_RISCV_32_PCREL
+        (Machine::EMPPC64, 26) => Some(4),          // R_PPC64_REL32
+        (Machine::EMPPC64, 44) => Some(8),          // R_PPC64_REL64
+        _ => None,
+    }
+}
+
+// Little endian MIPS64 stores r_info as a little endian r_sym followed by single byte fields,
+// so reading it as one u64 scrambles the layout expected by ELF64_R_SYM / ELF64_R_TYPE
+fn mips64_little_endian_info(info: u64) -> u64 {
+    (info << 32)
+        | ((info >> 56) & 0xFF)
+        | ((info >> 40) & 0xFF00)
+        | ((info >> 24) & 0xFF0000)
+        | ((info >> 8) & 0xFF000000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relocation_table_apply_should_store_symbol_plus_addend() {
+        let table = RelocationTable{name: String::from(".rela.debug_info"), offset: 0, entries: vec![
+            RelocationEntry{r_offset: Word::Bits64(2), r_info: Word::Bits64(0x10000000A), r_addend: Some(0x20), r_type: 10,
+                r_type_str: String::from("R_X86_64_32"), symbol: Some((String::new(), Word::Bits64(0x100)))},
+            RelocationEntry{r_offset: Word::Bits64(6), r_info: Word::Bits64(0x100000002), r_addend: Some(0x20), r_type: 2,
+                r_type_str: String::from("R_X86_64_PC32"), symbol: Some((String::new(), Word::Bits64(0x100)))},
+        ]};
+        let mut data = vec![0u8; 10];
+        table.apply(&mut data, Machine::EMX8664, true).expect("Should apply relocations");
+        assert_eq!(data, [0, 0, 0x20, 0x01, 0, 0, 0, 0, 0, 0]);
+
+        // Only the PC32 entry is resolved against its place at section address 0x10 + 6
+        table.apply_pc_relative(&mut data, 0x10, Machine::EMX8664, true).expect("Should apply relocations");
+        assert_eq!(data, [0, 0, 0x20, 0x01, 0, 0, 0x0A, 0x01, 0, 0]);
+    }
+
+    #[test]
+    fn build_should_reject_tables_outside_the_file_and_undersized_entries() {
+        let mut header = [0u8; 0x40];
+        header[0x04..0x08].copy_from_slice(&4u32.to_le_bytes()); // SHT_RELA
+        header[0x18..0x20].copy_from_slice(&u64::MAX.to_le_bytes());
+        header[0x20..0x28].copy_from_slice(&0x30u64.to_le_bytes());
+        let section = SectionHeaderEntry::build(&header, 0, Machine::EMX8664, false, true);
+        let payload = [0u8; 0x40];
+        assert_eq!(RelocationTable::build(&payload, &section, None, Machine::EMX8664, false, true).unwrap_err(), "Relocation table too short");
+
+        header[0x18..0x20].copy_from_slice(&0u64.to_le_bytes());
+        header[0x38..0x40].copy_from_slice(&4u64.to_le_bytes());
+        let section = SectionHeaderEntry::build(&header, 0, Machine::EMX8664, false, true);
+        assert_eq!(RelocationTable::build(&payload, &section, None, Machine::EMX8664, false, true).unwrap_err(), "Relocation entries are too small");
+    }
+
+    #[test]
+    fn mips64_little_endian_info_should_move_symbol_to_upper_word() {
+        // r_sym = 0x12345678, r_ssym = 0x00, r_type3 = 0x00, r_type2 = 0x00, r_type = 0x03
+        let raw = u64::from_le_bytes([0x78, 0x56, 0x34, 0x12, 0x00, 0x00, 0x00, 0x03]);
+        let info = mips64_little_endian_info(raw);
+        assert_eq!(info >> 32, 0x12345678);
+        assert_eq!(info & 0xFF, 0x03);
+    }
+}
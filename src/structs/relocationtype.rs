@@ -0,0 +1,576 @@
+use super::machine::Machine;
+
+pub fn relocation_type_to_string(machine: Machine, value: u32) -> String {
+    let name = match machine {
+        Machine::EMX8664 => x86_64_type_name(value),
+        Machine::EM386 => i386_type_name(value),
+        Machine::EMAARCH64 => aarch64_type_name(value),
+        Machine::EMARM => arm_type_name(value),
+        Machine::EMRISCV => riscv_type_name(value),
+        Machine::EMPPC64 => ppc64_type_name(value),
+        Machine::EMMIPS | Machine::EMMIPSRS3LE => mips_type_name(value),
+        _ => None,
+    };
+
+    match name {
+        Some(name) => String::from(name),
+        None => format!("<unknown>: 0x{:x}", value),
+    }
+}
+
+fn x86_64_type_name(value: u32) -> Option<&'static str> {
+    match value {
+        0 => Some("R_X86_64_NONE"),
+        1 => Some("R_X86_64_64"),
+        2 => Some("R_X86_64_PC32"),
+        3 => Some("R_X86_64_GOT32"),
+        4 => Some("R_X86_64_PLT32"),
+        5 => Some("R_X86_64_COPY"),
+        6 => Some("R_X86_64_GLOB_DAT"),
+        7 => Some("R_X86_64_JUMP_SLOT"),
+        8 => Some("R_X86_64_RELATIVE"),
+        9 => Some("R_X86_64_GOTPCREL"),
+        10 => Some("R_X86_64_32"),
+        11 => Some("R_X86_64_32S"),
+        12 => Some("R_X86_64_16"),
+        13 => Some("R_X86_64_PC16"),
+        14 => Some("R_X86_64_8"),
+        15 => Some("R_X86_64_PC8"),
+        16 => Some("R_X86_64_DTPMOD64"),
+        17 => Some("R_X86_64_DTPOFF64"),
+        18 => Some("R_X86_64_TPOFF64"),
+        19 => Some("R_X86_64_TLSGD"),
+        20 => Some("R_X86_64_TLSLD"),
+        21 => Some("R_X86_64_DTPOFF32"),
+        22 => Some("R_X86_64_GOTTPOFF"),
+        23 => Some("R_X86_64_TPOFF32"),
+        24 => Some("R_X86_64_PC64"),
+        25 => Some("R_X86_64_GOTOFF64"),
+        26 => Some("R_X86_64_GOTPC32"),
+        27 => Some("R_X86_64_GOT64"),
+        28 => Some("R_X86_64_GOTPCREL64"),
+        29 => Some("R_X86_64_GOTPC64"),
+        30 => Some("R_X86_64_GOTPLT64"),
+        31 => Some("R_X86_64_PLTOFF64"),
+        32 => Some("R_X86_64_SIZE32"),
+        33 => Some("R_X86_64_SIZE64"),
+        34 => Some("R_X86_64_GOTPC32_TLSDESC"),
+        35 => Some("R_X86_64_TLSDESC_CALL"),
+        36 => Some("R_X86_64_TLSDESC"),
+        37 => Some("R_X86_64_IRELATIVE"),
+        38 => Some("R_X86_64_RELATIVE64"),
+        41 => Some("R_X86_64_GOTPCRELX"),
+        42 => Some("R_X86_64_REX_GOTPCRELX"),
+        43 => Some("R_X86_64_CODE_4_GOTPCRELX"),
+        44 => Some("R_X86_64_CODE_4_GOTTPOFF"),
+        45 => Some("R_X86_64_CODE_4_GOTPC32_TLSDESC"),
+        _ => None,
+    }
+}
+
+fn i386_type_name(value: u32) -> Option<&'static str> {
+    match value {
+        0 => Some("R_386_NONE"),
+        1 => Some("R_386_32"),
+        2 => Some("R_386_PC32"),
+        3 => Some("R_386_GOT32"),
+        4 => Some("R_386_PLT32"),
+        5 => Some("R_386_COPY"),
+        6 => Some("R_386_GLOB_DAT"),
+        7 => Some("R_386_JUMP_SLOT"),
+        8 => Some("R_386_RELATIVE"),
+        9 => Some("R_386_GOTOFF"),
+        10 => Some("R_386_GOTPC"),
+        11 => Some("R_386_32PLT"),
+        14 => Some("R_386_TLS_TPOFF"),
+        15 => Some("R_386_TLS_IE"),
+        16 => Some("R_386_TLS_GOTIE"),
+        17 => Some("R_386_TLS_LE"),
+        18 => Some("R_386_TLS_GD"),
+        19 => Some("R_386_TLS_LDM"),
+        20 => Some("R_386_16"),
+        21 => Some("R_386_PC16"),
+        22 => Some("R_386_8"),
+        23 => Some("R_386_PC8"),
+        24 => Some("R_386_TLS_GD_32"),
+        25 => Some("R_386_TLS_GD_PUSH"),
+        26 => Some("R_386_TLS_GD_CALL"),
+        27 => Some("R_386_TLS_GD_POP"),
+        28 => Some("R_386_TLS_LDM_32"),
+        29 => Some("R_386_TLS_LDM_PUSH"),
+        30 => Some("R_386_TLS_LDM_CALL"),
+        31 => Some("R_386_TLS_LDM_POP"),
+        32 => Some("R_386_TLS_LDO_32"),
+        33 => Some("R_386_TLS_IE_32"),
+        34 => Some("R_386_TLS_LE_32"),
+        35 => Some("R_386_TLS_DTPMOD32"),
+        36 => Some("R_386_TLS_DTPOFF32"),
+        37 => Some("R_386_TLS_TPOFF32"),
+        38 => Some("R_386_SIZE32"),
+        39 => Some("R_386_TLS_GOTDESC"),
+        40 => Some("R_386_TLS_DESC_CALL"),
+        41 => Some("R_386_TLS_DESC"),
+        42 => Some("R_386_IRELATIVE"),
+        43 => Some("R_386_GOT32X"),
+        _ => None,
+    }
+}
+
+fn aarch64_type_name(value: u32) -> Option<&'static str> {
+    match value {
+        0 => Some("R_AARCH64_NONE"),
+        257 => Some("R_AARCH64_ABS64"),
+        258 => Some("R_AARCH64_ABS32"),
+        259 => Some("R_AARCH64_ABS16"),
+        260 => Some("R_AARCH64_PREL64"),
+        261 => Some("R_AARCH64_PREL32"),
+        262 => Some("R_AARCH64_PREL16"),
+        263 => Some("R_AARCH64_MOVW_UABS_G0"),
+        264 => Some("R_AARCH64_MOVW_UABS_G0_NC"),
+        265 => Some("R_AARCH64_MOVW_UABS_G1"),
+        266 => Some("R_AARCH64_MOVW_UABS_G1_NC"),
+        267 => Some("R_AARCH64_MOVW_UABS_G2"),
+        268 => Some("R_AARCH64_MOVW_UABS_G2_NC"),
+        269 => Some("R_AARCH64_MOVW_UABS_G3"),
+        270 => Some("R_AARCH64_MOVW_SABS_G0"),
+        271 => Some("R_AARCH64_MOVW_SABS_G1"),
+        272 => Some("R_AARCH64_MOVW_SABS_G2"),
+        273 => Some("R_AARCH64_LD_PREL_LO19"),
+        274 => Some("R_AARCH64_ADR_PREL_LO21"),
+        275 => Some("R_AARCH64_ADR_PREL_PG_HI21"),
+        276 => Some("R_AARCH64_ADR_PREL_PG_HI21_NC"),
+        277 => Some("R_AARCH64_ADD_ABS_LO12_NC"),
+        278 => Some("R_AARCH64_LDST8_ABS_LO12_NC"),
+        279 => Some("R_AARCH64_TSTBR14"),
+        280 => Some("R_AARCH64_CONDBR19"),
+        282 => Some("R_AARCH64_JUMP26"),
+        283 => Some("R_AARCH64_CALL26"),
+        284 => Some("R_AARCH64_LDST16_ABS_LO12_NC"),
+        285 => Some("R_AARCH64_LDST32_ABS_LO12_NC"),
+        286 => Some("R_AARCH64_LDST64_ABS_LO12_NC"),
+        287 => Some("R_AARCH64_MOVW_PREL_G0"),
+        288 => Some("R_AARCH64_MOVW_PREL_G0_NC"),
+        289 => Some("R_AARCH64_MOVW_PREL_G1"),
+        290 => Some("R_AARCH64_MOVW_PREL_G1_NC"),
+        291 => Some("R_AARCH64_MOVW_PREL_G2"),
+        292 => Some("R_AARCH64_MOVW_PREL_G2_NC"),
+        293 => Some("R_AARCH64_MOVW_PREL_G3"),
+        299 => Some("R_AARCH64_LDST128_ABS_LO12_NC"),
+        300 => Some("R_AARCH64_MOVW_GOTOFF_G0"),
+        301 => Some("R_AARCH64_MOVW_GOTOFF_G0_NC"),
+        302 => Some("R_AARCH64_MOVW_GOTOFF_G1"),
+        303 => Some("R_AARCH64_MOVW_GOTOFF_G1_NC"),
+        304 => Some("R_AARCH64_MOVW_GOTOFF_G2"),
+        305 => Some("R_AARCH64_MOVW_GOTOFF_G2_NC"),
+        306 => Some("R_AARCH64_MOVW_GOTOFF_G3"),
+        307 => Some("R_AARCH64_GOTREL64"),
+        308 => Some("R_AARCH64_GOTREL32"),
+        309 => Some("R_AARCH64_GOT_LD_PREL19"),
+        310 => Some("R_AARCH64_LD64_GOTOFF_LO15"),
+        311 => Some("R_AARCH64_ADR_GOT_PAGE"),
+        312 => Some("R_AARCH64_LD64_GOT_LO12_NC"),
+        313 => Some("R_AARCH64_LD64_GOTPAGE_LO15"),
+        512 => Some("R_AARCH64_TLSGD_ADR_PREL21"),
+        513 => Some("R_AARCH64_TLSGD_ADR_PAGE21"),
+        514 => Some("R_AARCH64_TLSGD_ADD_LO12_NC"),
+        515 => Some("R_AARCH64_TLSGD_MOVW_G1"),
+        516 => Some("R_AARCH64_TLSGD_MOVW_G0_NC"),
+        517 => Some("R_AARCH64_TLSLD_ADR_PREL21"),
+        518 => Some("R_AARCH64_TLSLD_ADR_PAGE21"),
+        519 => Some("R_AARCH64_TLSLD_ADD_LO12_NC"),
+        539 => Some("R_AARCH64_TLSIE_MOVW_GOTTPREL_G1"),
+        540 => Some("R_AARCH64_TLSIE_MOVW_GOTTPREL_G0_NC"),
+        541 => Some("R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21"),
+        542 => Some("R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC"),
+        543 => Some("R_AARCH64_TLSIE_LD_GOTTPREL_PREL19"),
+        544 => Some("R_AARCH64_TLSLE_MOVW_TPREL_G2"),
+        545 => Some("R_AARCH64_TLSLE_MOVW_TPREL_G1"),
+        546 => Some("R_AARCH64_TLSLE_MOVW_TPREL_G1_NC"),
+        547 => Some("R_AARCH64_TLSLE_MOVW_TPREL_G0"),
+        548 => Some("R_AARCH64_TLSLE_MOVW_TPREL_G0_NC"),
+        549 => Some("R_AARCH64_TLSLE_ADD_TPREL_HI12"),
+        550 => Some("R_AARCH64_TLSLE_ADD_TPREL_LO12"),
+        551 => Some("R_AARCH64_TLSLE_ADD_TPREL_LO12_NC"),
+        560 => Some("R_AARCH64_TLSDESC_LD_PREL19"),
+        561 => Some("R_AARCH64_TLSDESC_ADR_PREL21"),
+        562 => Some("R_AARCH64_TLSDESC_ADR_PAGE21"),
+        563 => Some("R_AARCH64_TLSDESC_LD64_LO12"),
+        564 => Some("R_AARCH64_TLSDESC_ADD_LO12"),
+        565 => Some("R_AARCH64_TLSDESC_OFF_G1"),
+        566 => Some("R_AARCH64_TLSDESC_OFF_G0_NC"),
+        567 => Some("R_AARCH64_TLSDESC_LDR"),
+        568 => Some("R_AARCH64_TLSDESC_ADD"),
+        569 => Some("R_AARCH64_TLSDESC_CALL"),
+        1024 => Some("R_AARCH64_COPY"),
+        1025 => Some("R_AARCH64_GLOB_DAT"),
+        1026 => Some("R_AARCH64_JUMP_SLOT"),
+        1027 => Some("R_AARCH64_RELATIVE"),
+        1028 => Some("R_AARCH64_TLS_DTPMOD"),
+        1029 => Some("R_AARCH64_TLS_DTPREL"),
+        1030 => Some("R_AARCH64_TLS_TPREL"),
+        1031 => Some("R_AARCH64_TLSDESC"),
+        1032 => Some("R_AARCH64_IRELATIVE"),
+        _ => None,
+    }
+}
+
+fn arm_type_name(value: u32) -> Option<&'static str> {
+    match value {
+        0 => Some("R_ARM_NONE"),
+        1 => Some("R_ARM_PC24"),
+        2 => Some("R_ARM_ABS32"),
+        3 => Some("R_ARM_REL32"),
+        4 => Some("R_ARM_LDR_PC_G0"),
+        5 => Some("R_ARM_ABS16"),
+        6 => Some("R_ARM_ABS12"),
+        7 => Some("R_ARM_THM_ABS5"),
+        8 => Some("R_ARM_ABS8"),
+        9 => Some("R_ARM_SBREL32"),
+        10 => Some("R_ARM_THM_CALL"),
+        11 => Some("R_ARM_THM_PC8"),
+        12 => Some("R_ARM_BREL_ADJ"),
+        13 => Some("R_ARM_TLS_DESC"),
+        14 => Some("R_ARM_THM_SWI8"),
+        15 => Some("R_ARM_XPC25"),
+        16 => Some("R_ARM_THM_XPC22"),
+        17 => Some("R_ARM_TLS_DTPMOD32"),
+        18 => Some("R_ARM_TLS_DTPOFF32"),
+        19 => Some("R_ARM_TLS_TPOFF32"),
+        20 => Some("R_ARM_COPY"),
+        21 => Some("R_ARM_GLOB_DAT"),
+        22 => Some("R_ARM_JUMP_SLOT"),
+        23 => Some("R_ARM_RELATIVE"),
+        24 => Some("R_ARM_GOTOFF32"),
+        25 => Some("R_ARM_BASE_PREL"),
+        26 => Some("R_ARM_GOT_BREL"),
+        27 => Some("R_ARM_PLT32"),
+        28 => Some("R_ARM_CALL"),
+        29 => Some("R_ARM_JUMP24"),
+        30 => Some("R_ARM_THM_JUMP24"),
+        31 => Some("R_ARM_BASE_ABS"),
+        32 => Some("R_ARM_ALU_PCREL_7_0"),
+        33 => Some("R_ARM_ALU_PCREL_15_8"),
+        34 => Some("R_ARM_ALU_PCREL_23_15"),
+        35 => Some("R_ARM_LDR_SBREL_11_0_NC"),
+        36 => Some("R_ARM_ALU_SBREL_19_12_NC"),
+        37 => Some("R_ARM_ALU_SBREL_27_20_CK"),
+        38 => Some("R_ARM_TARGET1"),
+        39 => Some("R_ARM_SBREL31"),
+        40 => Some("R_ARM_V4BX"),
+        41 => Some("R_ARM_TARGET2"),
+        42 => Some("R_ARM_PREL31"),
+        43 => Some("R_ARM_MOVW_ABS_NC"),
+        44 => Some("R_ARM_MOVT_ABS"),
+        45 => Some("R_ARM_MOVW_PREL_NC"),
+        46 => Some("R_ARM_MOVT_PREL"),
+        47 => Some("R_ARM_THM_MOVW_ABS_NC"),
+        48 => Some("R_ARM_THM_MOVT_ABS"),
+        49 => Some("R_ARM_THM_MOVW_PREL_NC"),
+        50 => Some("R_ARM_THM_MOVT_PREL"),
+        51 => Some("R_ARM_THM_JUMP19"),
+        52 => Some("R_ARM_THM_JUMP6"),
+        53 => Some("R_ARM_THM_ALU_PREL_11_0"),
+        54 => Some("R_ARM_THM_PC12"),
+        55 => Some("R_ARM_ABS32_NOI"),
+        56 => Some("R_ARM_REL32_NOI"),
+        95 => Some("R_ARM_GOT_ABS"),
+        96 => Some("R_ARM_GOT_PREL"),
+        97 => Some("R_ARM_GOT_BREL12"),
+        98 => Some("R_ARM_GOTOFF12"),
+        99 => Some("R_ARM_GOTRELAX"),
+        100 => Some("R_ARM_GNU_VTENTRY"),
+        101 => Some("R_ARM_GNU_VTINHERIT"),
+        102 => Some("R_ARM_THM_JUMP11"),
+        103 => Some("R_ARM_THM_JUMP8"),
+        104 => Some("R_ARM_TLS_GD32"),
+        105 => Some("R_ARM_TLS_LDM32"),
+        106 => Some("R_ARM_TLS_LDO32"),
+        107 => Some("R_ARM_TLS_IE32"),
+        108 => Some("R_ARM_TLS_LE32"),
+        109 => Some("R_ARM_TLS_LDO12"),
+        110 => Some("R_ARM_TLS_LE12"),
+        111 => Some("R_ARM_TLS_IE12GP"),
+        128 => Some("R_ARM_ME_TOO"),
+        129 => Some("R_ARM_THM_TLS_DESCSEQ16"),
+        130 => Some("R_ARM_THM_TLS_DESCSEQ32"),
+        160 => Some("R_ARM_IRELATIVE"),
+        _ => None,
+    }
+}
+
+fn riscv_type_name(value: u32) -> Option<&'static str> {
+    match value {
+        0 => Some("R_RISCV_NONE"),
+        1 => Some("R_RISCV_32"),
+        2 => Some("R_RISCV_64"),
+        3 => Some("R_RISCV_RELATIVE"),
+        4 => Some("R_RISCV_COPY"),
+        5 => Some("R_RISCV_JUMP_SLOT"),
+        6 => Some("R_RISCV_TLS_DTPMOD32"),
+        7 => Some("R_RISCV_TLS_DTPMOD64"),
+        8 => Some("R_RISCV_TLS_DTPREL32"),
+        9 => Some("R_RISCV_TLS_DTPREL64"),
+        10 => Some("R_RISCV_TLS_TPREL32"),
+        11 => Some("R_RISCV_TLS_TPREL64"),
+        12 => Some("R_RISCV_TLSDESC"),
+        16 => Some("R_RISCV_BRANCH"),
+        17 => Some("R_RISCV_JAL"),
+        18 => Some("R_RISCV_CALL"),
+        19 => Some("R_RISCV_CALL_PLT"),
+        20 => Some("R_RISCV_GOT_HI20"),
+        21 => Some("R_RISCV_TLS_GOT_HI20"),
+        22 => Some("R_RISCV_TLS_GD_HI20"),
+        23 => Some("R_RISCV_PCREL_HI20"),
+        24 => Some("R_RISCV_PCREL_LO12_I"),
+        25 => Some("R_RISCV_PCREL_LO12_S"),
+        26 => Some("R_RISCV_HI20"),
+        27 => Some("R_RISCV_LO12_I"),
+        28 => Some("R_RISCV_LO12_S"),
+        29 => Some("R_RISCV_TPREL_HI20"),
+        30 => Some("R_RISCV_TPREL_LO12_I"),
+        31 => Some("R_RISCV_TPREL_LO12_S"),
+        32 => Some("R_RISCV_TPREL_ADD"),
+        33 => Some("R_RISCV_ADD8"),
+        34 => Some("R_RISCV_ADD16"),
+        35 => Some("R_RISCV_ADD32"),
+        36 => Some("R_RISCV_ADD64"),
+        37 => Some("R_RISCV_SUB8"),
+        38 => Some("R_RISCV_SUB16"),
+        39 => Some("R_RISCV_SUB32"),
+        40 => Some("R_RISCV_SUB64"),
+        41 => Some("R_RISCV_GOT32_PCREL"),
+        43 => Some("R_RISCV_ALIGN"),
+        44 => Some("R_RISCV_RVC_BRANCH"),
+        45 => Some("R_RISCV_RVC_JUMP"),
+        51 => Some("R_RISCV_RELAX"),
+        52 => Some("R_RISCV_SUB6"),
+        53 => Some("R_RISCV_SET6"),
+        54 => Some("R_RISCV_SET8"),
+        55 => Some("R_RISCV_SET16"),
+        56 => Some("R_RISCV_SET32"),
+        57 => Some("R_RISCV_32_PCREL"),
+        58 => Some("R_RISCV_IRELATIVE"),
+        59 => Some("R_RISCV_PLT32"),
+        60 => Some("R_RISCV_SET_ULEB128"),
+        61 => Some("R_RISCV_SUB_ULEB128"),
+        62 => Some("R_RISCV_TLSDESC_HI20"),
+        63 => Some("R_RISCV_TLSDESC_LOAD_LO12"),
+        64 => Some("R_RISCV_TLSDESC_ADD_LO12"),
+        65 => Some("R_RISCV_TLSDESC_CALL"),
+        _ => None,
+    }
+}
+
+fn ppc64_type_name(value: u32) -> Option<&'static str> {
+    match value {
+        0 => Some("R_PPC64_NONE"),
+        1 => Some("R_PPC64_ADDR32"),
+        2 => Some("R_PPC64_ADDR24"),
+        3 => Some("R_PPC64_ADDR16"),
+        4 => Some("R_PPC64_ADDR16_LO"),
+        5 => Some("R_PPC64_ADDR16_HI"),
+        6 => Some("R_PPC64_ADDR16_HA"),
+        7 => Some("R_PPC64_ADDR14"),
+        8 => Some("R_PPC64_ADDR14_BRTAKEN"),
+        9 => Some("R_PPC64_ADDR14_BRNTAKEN"),
+        10 => Some("R_PPC64_REL24"),
+        11 => Some("R_PPC64_REL14"),
+        12 => Some("R_PPC64_REL14_BRTAKEN"),
+        13 => Some("R_PPC64_REL14_BRNTAKEN"),
+        14 => Some("R_PPC64_GOT16"),
+        15 => Some("R_PPC64_GOT16_LO"),
+        16 => Some("R_PPC64_GOT16_HI"),
+        17 => Some("R_PPC64_GOT16_HA"),
+        19 => Some("R_PPC64_COPY"),
+        20 => Some("R_PPC64_GLOB_DAT"),
+        21 => Some("R_PPC64_JMP_SLOT"),
+        22 => Some("R_PPC64_RELATIVE"),
+        24 => Some("R_PPC64_UADDR32"),
+        25 => Some("R_PPC64_UADDR16"),
+        26 => Some("R_PPC64_REL32"),
+        27 => Some("R_PPC64_PLT32"),
+        28 => Some("R_PPC64_PLTREL32"),
+        29 => Some("R_PPC64_PLT16_LO"),
+        30 => Some("R_PPC64_PLT16_HI"),
+        31 => Some("R_PPC64_PLT16_HA"),
+        33 => Some("R_PPC64_SECTOFF"),
+        34 => Some("R_PPC64_SECTOFF_LO"),
+        35 => Some("R_PPC64_SECTOFF_HI"),
+        36 => Some("R_PPC64_SECTOFF_HA"),
+        37 => Some("R_PPC64_ADDR30"),
+        38 => Some("R_PPC64_ADDR64"),
+        39 => Some("R_PPC64_ADDR16_HIGHER"),
+        40 => Some("R_PPC64_ADDR16_HIGHERA"),
+        41 => Some("R_PPC64_ADDR16_HIGHEST"),
+        42 => Some("R_PPC64_ADDR16_HIGHESTA"),
+        43 => Some("R_PPC64_UADDR64"),
+        44 => Some("R_PPC64_REL64"),
+        45 => Some("R_PPC64_PLT64"),
+        46 => Some("R_PPC64_PLTREL64"),
+        47 => Some("R_PPC64_TOC16"),
+        48 => Some("R_PPC64_TOC16_LO"),
+        49 => Some("R_PPC64_TOC16_HI"),
+        50 => Some("R_PPC64_TOC16_HA"),
+        51 => Some("R_PPC64_TOC"),
+        52 => Some("R_PPC64_PLTGOT16"),
+        53 => Some("R_PPC64_PLTGOT16_LO"),
+        54 => Some("R_PPC64_PLTGOT16_HI"),
+        55 => Some("R_PPC64_PLTGOT16_HA"),
+        56 => Some("R_PPC64_ADDR16_DS"),
+        57 => Some("R_PPC64_ADDR16_LO_DS"),
+        58 => Some("R_PPC64_GOT16_DS"),
+        59 => Some("R_PPC64_GOT16_LO_DS"),
+        60 => Some("R_PPC64_PLT16_LO_DS"),
+        61 => Some("R_PPC64_SECTOFF_DS"),
+        62 => Some("R_PPC64_SECTOFF_LO_DS"),
+        63 => Some("R_PPC64_TOC16_DS"),
+        64 => Some("R_PPC64_TOC16_LO_DS"),
+        65 => Some("R_PPC64_PLTGOT16_DS"),
+        66 => Some("R_PPC64_PLTGOT16_LO_DS"),
+        67 => Some("R_PPC64_TLS"),
+        68 => Some("R_PPC64_DTPMOD64"),
+        69 => Some("R_PPC64_TPREL16"),
+        70 => Some("R_PPC64_TPREL16_LO"),
+        71 => Some("R_PPC64_TPREL16_HI"),
+        72 => Some("R_PPC64_TPREL16_HA"),
+        73 => Some("R_PPC64_TPREL64"),
+        74 => Some("R_PPC64_DTPREL16"),
+        75 => Some("R_PPC64_DTPREL16_LO"),
+        76 => Some("R_PPC64_DTPREL16_HI"),
+        77 => Some("R_PPC64_DTPREL16_HA"),
+        78 => Some("R_PPC64_DTPREL64"),
+        79 => Some("R_PPC64_GOT_TLSGD16"),
+        80 => Some("R_PPC64_GOT_TLSGD16_LO"),
+        81 => Some("R_PPC64_GOT_TLSGD16_HI"),
+        82 => Some("R_PPC64_GOT_TLSGD16_HA"),
+        83 => Some("R_PPC64_GOT_TLSLD16"),
+        84 => Some("R_PPC64_GOT_TLSLD16_LO"),
+        85 => Some("R_PPC64_GOT_TLSLD16_HI"),
+        86 => Some("R_PPC64_GOT_TLSLD16_HA"),
+        87 => Some("R_PPC64_GOT_TPREL16_DS"),
+        88 => Some("R_PPC64_GOT_TPREL16_LO_DS"),
+        89 => Some("R_PPC64_GOT_TPREL16_HI"),
+        90 => Some("R_PPC64_GOT_TPREL16_HA"),
+        91 => Some("R_PPC64_GOT_DTPREL16_DS"),
+        92 => Some("R_PPC64_GOT_DTPREL16_LO_DS"),
+        93 => Some("R_PPC64_GOT_DTPREL16_HI"),
+        94 => Some("R_PPC64_GOT_DTPREL16_HA"),
+        95 => Some("R_PPC64_TPREL16_DS"),
+        96 => Some("R_PPC64_TPREL16_LO_DS"),
+        97 => Some("R_PPC64_TPREL16_HIGHER"),
+        98 => Some("R_PPC64_TPREL16_HIGHERA"),
+        99 => Some("R_PPC64_TPREL16_HIGHEST"),
+        100 => Some("R_PPC64_TPREL16_HIGHESTA"),
+        101 => Some("R_PPC64_DTPREL16_DS"),
+        102 => Some("R_PPC64_DTPREL16_LO_DS"),
+        103 => Some("R_PPC64_DTPREL16_HIGHER"),
+        104 => Some("R_PPC64_DTPREL16_HIGHERA"),
+        105 => Some("R_PPC64_DTPREL16_HIGHEST"),
+        106 => Some("R_PPC64_DTPREL16_HIGHESTA"),
+        107 => Some("R_PPC64_TLSGD"),
+        108 => Some("R_PPC64_TLSLD"),
+        109 => Some("R_PPC64_TOCSAVE"),
+        110 => Some("R_PPC64_ADDR16_HIGH"),
+        111 => Some("R_PPC64_ADDR16_HIGHA"),
+        112 => Some("R_PPC64_TPREL16_HIGH"),
+        113 => Some("R_PPC64_TPREL16_HIGHA"),
+        114 => Some("R_PPC64_DTPREL16_HIGH"),
+        115 => Some("R_PPC64_DTPREL16_HIGHA"),
+        116 => Some("R_PPC64_REL24_NOTOC"),
+        117 => Some("R_PPC64_ADDR64_LOCAL"),
+        118 => Some("R_PPC64_ENTRY"),
+        119 => Some("R_PPC64_PLTSEQ"),
+        120 => Some("R_PPC64_PLTCALL"),
+        132 => Some("R_PPC64_PCREL34"),
+        133 => Some("R_PPC64_GOT_PCREL34"),
+        247 => Some("R_PPC64_JMP_IREL"),
+        248 => Some("R_PPC64_IRELATIVE"),
+        249 => Some("R_PPC64_REL16"),
+        250 => Some("R_PPC64_REL16_LO"),
+        251 => Some("R_PPC64_REL16_HI"),
+        252 => Some("R_PPC64_REL16_HA"),
+        _ => None,
+    }
+}
+
+fn mips_type_name(value: u32) -> Option<&'static str> {
+    match value {
+        0 => Some("R_MIPS_NONE"),
+        1 => Some("R_MIPS_16"),
+        2 => Some("R_MIPS_32"),
+        3 => Some("R_MIPS_REL32"),
+        4 => Some("R_MIPS_26"),
+        5 => Some("R_MIPS_HI16"),
+        6 => Some("R_MIPS_LO16"),
+        7 => Some("R_MIPS_GPREL16"),
+        8 => Some("R_MIPS_LITERAL"),
+        9 => Some("R_MIPS_GOT16"),
+        10 => Some("R_MIPS_PC16"),
+        11 => Some("R_MIPS_CALL16"),
+        12 => Some("R_MIPS_GPREL32"),
+        16 => Some("R_MIPS_SHIFT5"),
+        17 => Some("R_MIPS_SHIFT6"),
+        18 => Some("R_MIPS_64"),
+        19 => Some("R_MIPS_GOT_DISP"),
+        20 => Some("R_MIPS_GOT_PAGE"),
+        21 => Some("R_MIPS_GOT_OFST"),
+        22 => Some("R_MIPS_GOT_HI16"),
+        23 => Some("R_MIPS_GOT_LO16"),
+        24 => Some("R_MIPS_SUB"),
+        25 => Some("R_MIPS_INSERT_A"),
+        26 => Some("R_MIPS_INSERT_B"),
+        27 => Some("R_MIPS_DELETE"),
+        28 => Some("R_MIPS_HIGHER"),
+        29 => Some("R_MIPS_HIGHEST"),
+        30 => Some("R_MIPS_CALL_HI16"),
+        31 => Some("R_MIPS_CALL_LO16"),
+        32 => Some("R_MIPS_SCN_DISP"),
+        33 => Some("R_MIPS_REL16"),
+        34 => Some("R_MIPS_ADD_IMMEDIATE"),
+        35 => Some("R_MIPS_PJUMP"),
+        36 => Some("R_MIPS_RELGOT"),
+        37 => Some("R_MIPS_JALR"),
+        38 => Some("R_MIPS_TLS_DTPMOD32"),
+        39 => Some("R_MIPS_TLS_DTPREL32"),
+        40 => Some("R_MIPS_TLS_DTPMOD64"),
+        41 => Some("R_MIPS_TLS_DTPREL64"),
+        42 => Some("R_MIPS_TLS_GD"),
+        43 => Some("R_MIPS_TLS_LDM"),
+        44 => Some("R_MIPS_TLS_DTPREL_HI16"),
+        45 => Some("R_MIPS_TLS_DTPREL_LO16"),
+        46 => Some("R_MIPS_TLS_GOTTPREL"),
+        47 => Some("R_MIPS_TLS_TPREL32"),
+        48 => Some("R_MIPS_TLS_TPREL64"),
+        49 => Some("R_MIPS_TLS_TPREL_HI16"),
+        50 => Some("R_MIPS_TLS_TPREL_LO16"),
+        51 => Some("R_MIPS_GLOB_DAT"),
+        60 => Some("R_MIPS_PC21_S2"),
+        61 => Some("R_MIPS_PC26_S2"),
+        62 => Some("R_MIPS_PC18_S3"),
+        63 => Some("R_MIPS_PC19_S2"),
+        64 => Some("R_MIPS_PCHI16"),
+        65 => Some("R_MIPS_PCLO16"),
+        126 => Some("R_MIPS_COPY"),
+        127 => Some("R_MIPS_JUMP_SLOT"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relocation_type_should_be_named_per_machine() {
+        assert_eq!(relocation_type_to_string(Machine::EMX8664, 8), "R_X86_64_RELATIVE");
+        assert_eq!(relocation_type_to_string(Machine::EM386, 7), "R_386_JUMP_SLOT");
+        assert_eq!(relocation_type_to_string(Machine::EMAARCH64, 1026), "R_AARCH64_JUMP_SLOT");
+        assert_eq!(relocation_type_to_string(Machine::EMRISCV, 2), "R_RISCV_64");
+    }
+
+    #[test]
+    fn relocation_type_should_report_unknown_values() {
+        assert_eq!(relocation_type_to_string(Machine::EMX8664, 0xFF), "<unknown>: 0xff");
+        assert_eq!(relocation_type_to_string(Machine::EMSPARC, 1), "<unknown>: 0x1");
+    }
+}
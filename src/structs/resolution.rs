@@ -0,0 +1,85 @@
+use crate::structs::programheader::ProgramHeader;
+use crate::structs::sectionheader::SectionHeader;
+use crate::structs::symbols::SymbolTable;
+use crate::termcolors;
+
+// One reading of the value given to --resolve, each field is None when the file does not map it
+#[derive(Debug)]
+struct Location {
+    offset: Option<u64>,
+    address: Option<u64>,
+    section: Option<(usize, String, u64)>,
+    symbol: Option<(String, u64)>,
+}
+
+#[derive(Debug)]
+pub struct Resolution {
+    value: u64,
+    as_address: Location,
+    as_offset: Location,
+}
+
+impl Resolution {
+    // The value is read both as a virtual address and as a file offset since nothing on the command line tells them apart
+    pub fn build(value: u64, program_header: &ProgramHeader, section_header: &SectionHeader, symbols: Option<&SymbolTable>, is_relocatable: bool) -> Resolution {
+        let symbol_at = |address: u64, section: Option<usize>| {
+            // Symbol values in relocatable files only mean something relative to their own section
+            if is_relocatable && section.is_none() {
+                return None;
+            }
+            symbols
+                .and_then(|table| table.symbol_at_address(address, section.filter(|_| is_relocatable)))
+                .map(|(symbol, offset)| (symbol.name().clone(), offset))
+        };
+
+        let section = section_header.section_at_address(value)
+            .filter(|_| !is_relocatable)
+            .map(|(index, entry)| (index, entry.name().to_string(), value - entry.address().to_u64().unwrap()));
+        let as_address = Location {
+            offset: program_header.virtual_address_to_offset(value),
+            address: Some(value),
+            symbol: symbol_at(value, section.as_ref().map(|(index, _, _)| *index)),
+            section,
+        };
+
+        let section = section_header.section_at_offset(value)
+            .map(|(index, entry)| (index, entry.name().to_string(), value - entry.offset().to_u64().unwrap()));
+        let address = match (program_header.offset_to_virtual_address(value), &section) {
+            (Some(address), _) => Some(address),
+            (None, Some((index, _, delta))) if is_relocatable => section_header.get(*index).map(|entry| entry.address().to_u64().unwrap() + delta),
+            _ => None,
+        };
+        let as_offset = Location {
+            offset: Some(value),
+            address,
+            symbol: address.and_then(|address| symbol_at(address, section.as_ref().map(|(index, _, _)| *index))),
+            section,
+        };
+
+        Resolution { value, as_address, as_offset }
+    }
+
+    pub fn print(&self) {
+        println!("{}Resolving 0x{:x}:{}", termcolors::purple(), self.value, termcolors::default());
+        print_location("As virtual address", &self.as_address);
+        print_location("As file offset", &self.as_offset);
+    }
+}
+
+fn print_location(title: &str, location: &Location) {
+    let or_unmapped = |value: Option<String>| value.unwrap_or_else(|| String::from("-"));
+
+    println!("  {}{}:{}", termcolors::yellow(), title, termcolors::default());
+    println!("    {}Virtual address: {}{}", termcolors::gray(), termcolors::green(), or_unmapped(location.address.map(|address| format!("0x{:x}", address))));
+    println!("    {}File offset:     {}{}", termcolors::gray(), termcolors::blue(), or_unmapped(location.offset.map(|offset| format!("0x{:x}", offset))));
+    println!("    {}Section:         {}{}", termcolors::gray(), termcolors::white(), or_unmapped(location.section.as_ref().map(|(index, name, delta)| format!("[{}] {}+0x{:x}", index, name, delta))));
+    println!("    {}Symbol:          {}{}{}", termcolors::gray(), termcolors::white(), or_unmapped(location.symbol.as_ref().map(|(name, delta)| format_symbol(name, *delta))), termcolors::default());
+}
+
+fn format_symbol(name: &str, delta: u64) -> String {
+    match delta {
+        0 => name.to_string(),
+        delta => format!("{}+0x{:x}", name, delta),
+    }
+}
+
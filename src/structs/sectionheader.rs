@@ -2,13 +2,21 @@ use crate::structs::word::Word;
 use crate::termcolors;
 use crate::types;
 use crate::structs::sectionheadertype::SectionHeaderType;
+use crate::structs::machine::Machine;
 use crate::bits::*;
 use crate::structs::sectionheaderflags;
+use crate::structs::compression::CompressionHeader;
+use crate::elffile::{check_table, Source, SECTION_HEADER32_SIZE, SECTION_HEADER64_SIZE};
 
-#[derive(Debug)]
-pub struct SectionHeaderEntry {
+use std::borrow::Cow;
+
+// Largest compression header, the start of a compressed section is all that is read to recognize it
+const COMPRESSION_HEADER_SIZE: u64 = 0x18;
+
+#[derive(Debug, Clone)]
+pub struct SectionHeaderEntry<'data> {
     sh_name: types::Elf32Word,
-    sh_name_str: String,
+    sh_name_str: &'data str,
     sh_type: SectionHeaderType,
     sh_flags: Word,
     sh_addr: Word,
@@ -18,9 +26,91 @@ pub struct SectionHeaderEntry {
     sh_info: types::Elf32Word,
     sh_addralign: Word,
     sh_entsize: Word,
+    sh_compression: Option<Result<CompressionHeader, &'static str>>,
 }
 
-impl SectionHeaderEntry {
+impl<'data> SectionHeaderEntry<'data> {
+    // Decodes the entry at entry_offset, the caller checks that the whole table lies inside payload.
+    // The name stays unresolved since it lives in another section
+    pub fn build(payload: &[u8], entry_offset: usize, machine: Machine, is_32bit: bool, is_little_endian: bool) -> SectionHeaderEntry<'data> {
+        let sh_name = to_u32_from_slice(&payload[entry_offset..entry_offset+4].try_into().unwrap(), is_little_endian);
+        let sh_type_offset = entry_offset + 0x04;
+        let sh_type = SectionHeaderType::from_u32(&payload[sh_type_offset..sh_type_offset+4].try_into().unwrap(), machine, is_little_endian);
+
+        let sh_flags_offset = entry_offset + 0x08;
+        let sh_flags;
+        let sh_addr;
+        let sh_offset;
+        let sh_size;
+
+        let sh_link_offset;
+        let sh_info_offset;
+
+        let sh_addralign;
+        let sh_entsize;
+
+        if is_32bit {
+            sh_flags = Word::Bits32(to_u32_from_slice(&payload[sh_flags_offset..sh_flags_offset+4].try_into().unwrap(), is_little_endian));
+            let sh_addr_offset = entry_offset + 0x0C;
+            sh_addr = Word::Bits32(to_u32_from_slice(&payload[sh_addr_offset..sh_addr_offset+4].try_into().unwrap(), is_little_endian));
+            let sh_offset_offset = entry_offset + 0x10;
+            sh_offset = Word::Bits32(to_u32_from_slice(&payload[sh_offset_offset..sh_offset_offset+4].try_into().unwrap(), is_little_endian));
+            let sh_size_offset =  entry_offset + 0x14;
+            sh_size = Word::Bits32(to_u32_from_slice(&payload[sh_size_offset..sh_size_offset+4].try_into().unwrap(), is_little_endian));
+            sh_link_offset = entry_offset + 0x18;
+            sh_info_offset = entry_offset + 0x1C;
+            let sh_addralign_offset = entry_offset + 0x20;
+            sh_addralign = Word::Bits32(to_u32_from_slice(&payload[sh_addralign_offset..sh_addralign_offset+4].try_into().unwrap(), is_little_endian));
+            let sh_entsize_offset = entry_offset + 0x24;
+            sh_entsize = Word::Bits32(to_u32_from_slice(&payload[sh_entsize_offset..sh_entsize_offset+4].try_into().unwrap(), is_little_endian));
+
+        } else {
+            sh_flags = Word::Bits64(to_u64_from_slice(&payload[sh_flags_offset..sh_flags_offset+8].try_into().unwrap(), is_little_endian));
+            let sh_addr_offset = entry_offset + 0x10;
+            sh_addr = Word::Bits64(to_u64_from_slice(&payload[sh_addr_offset..sh_addr_offset+8].try_into().unwrap(), is_little_endian));
+            let sh_offset_offset = entry_offset + 0x18;
+            sh_offset = Word::Bits64(to_u64_from_slice(&payload[sh_offset_offset..sh_offset_offset+8].try_into().unwrap(), is_little_endian));
+            let sh_size_offset =  entry_offset + 0x20;
+            sh_size = Word::Bits64(to_u64_from_slice(&payload[sh_size_offset..sh_size_offset+8].try_into().unwrap(), is_little_endian));
+            sh_link_offset = entry_offset + 0x28;
+            sh_info_offset = entry_offset + 0x2C;
+            let sh_addralign_offset = entry_offset + 0x30;
+            sh_addralign = Word::Bits64(to_u64_from_slice(&payload[sh_addralign_offset..sh_addralign_offset+8].try_into().unwrap(), is_little_endian));
+            let sh_entsize_offset = entry_offset + 0x38;
+            sh_entsize = Word::Bits64(to_u64_from_slice(&payload[sh_entsize_offset..sh_entsize_offset+8].try_into().unwrap(), is_little_endian));
+        }
+
+        let sh_link = to_u32_from_slice(&payload[sh_link_offset..sh_link_offset+4].try_into().unwrap(), is_little_endian);
+        let sh_info = to_u32_from_slice(&payload[sh_info_offset..sh_info_offset+4].try_into().unwrap(), is_little_endian);
+
+        SectionHeaderEntry{
+            sh_name: sh_name,
+            sh_name_str: "UNKNOWN_NAME",
+            sh_type: sh_type, 
+            sh_flags: sh_flags,
+            sh_addr: sh_addr,
+            sh_offset: sh_offset,
+            sh_size: sh_size,
+            sh_link: sh_link,
+            sh_info: sh_info,
+            sh_addralign: sh_addralign,
+            sh_entsize: sh_entsize,
+            sh_compression: None}
+    }
+
+    // Reads the compression header of SHF_COMPRESSED sections and of .zdebug sections, which only their name identifies.
+    // read_start fetches the start of the section, whose compression header is all that is needed
+    fn detect_compression<'a>(&mut self, read_start: impl FnOnce(&SectionHeaderEntry) -> Result<Cow<'a, [u8]>, &'static str>, is_32bit: bool, is_little_endian: bool) {
+        let is_compressed = self.sh_flags.to_u64().unwrap() & sectionheaderflags::SHF_COMPRESSED as u64 != 0;
+        let is_legacy_compressed = self.sh_name_str.starts_with(".zdebug");
+        if !matches!(self.sh_type, SectionHeaderType::ShtNobits) && (is_compressed || is_legacy_compressed) {
+            self.sh_compression = Some(read_start(self).and_then(|data| match is_compressed {
+                true => CompressionHeader::build(&data, is_32bit, is_little_endian),
+                false => CompressionHeader::build_gnu(&data),
+            }));
+        }
+    }
+
     pub fn print(&self){
         println!("\t{}0x{:08X}\t{}0x{:08X}\t{}0x{:08X}\t{}0x{:08X}\t{}{:32}",
             termcolors::green(),
@@ -53,113 +143,213 @@ impl SectionHeaderEntry {
         } else {
             println!("\t{}[No flags]", termcolors::yellow());
         }
+
+        match &self.sh_compression {
+            Some(Ok(header)) => println!("\t{}[Compressed {}: 0x{:X} bytes, uncompressed 0x{:X} bytes, alignment 0x{:X}]",
+                termcolors::cyan(),
+                header.compression_type(),
+                self.sh_size.to_u64().unwrap(),
+                header.uncompressed_size(),
+                header.uncompressed_alignment()),
+            Some(Err(error)) => println!("\t{}[Compressed: {}]", termcolors::red(), error),
+            None => (),
+        }
+    }
+
+    pub fn name(&self) -> &'data str {
+        self.sh_name_str
+    }
+
+    // Offset of the name in the section name string table
+    pub fn name_offset(&self) -> types::Elf32Word {
+        self.sh_name
+    }
+
+    pub fn section_type(&self) -> &SectionHeaderType {
+        &self.sh_type
+    }
+
+    pub fn address(&self) -> Word {
+        self.sh_addr
+    }
+
+    pub fn flags(&self) -> Word {
+        self.sh_flags
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.sh_flags.to_u64().unwrap() & sectionheaderflags::SHF_EXECINSTR as u64 != 0
+    }
+
+    pub fn is_allocated(&self) -> bool {
+        self.sh_flags.to_u64().unwrap() & sectionheaderflags::SHF_ALLOC as u64 != 0
+    }
+
+    pub fn is_tls(&self) -> bool {
+        self.sh_flags.to_u64().unwrap() & sectionheaderflags::SHF_TLS as u64 != 0
+    }
+
+    pub fn offset(&self) -> Word {
+        self.sh_offset
+    }
+
+    pub fn size(&self) -> Word {
+        self.sh_size
+    }
+
+    pub fn link(&self) -> types::Elf32Word {
+        self.sh_link
+    }
+
+    pub fn info(&self) -> types::Elf32Word {
+        self.sh_info
+    }
+
+    pub fn alignment(&self) -> Word {
+        self.sh_addralign
+    }
+
+    pub fn entry_size(&self) -> Word {
+        self.sh_entsize
+    }
+
+    // Compression header of SHF_COMPRESSED and .zdebug sections, or why it could not be read
+    pub fn compression(&self) -> Option<&Result<CompressionHeader, &'static str>> {
+        self.sh_compression.as_ref()
+    }
+
+    // Section bytes as seen by consumers, transparently decompressing SHF_COMPRESSED and .zdebug sections
+    pub fn contents<'a>(&self, payload: &'a [u8]) -> Result<Cow<'a, [u8]>, &'static str> {
+        if matches!(self.sh_type, SectionHeaderType::ShtNobits) {
+            return Ok(Cow::Borrowed(&[]));
+        }
+
+        let data = self.raw_contents(payload)?;
+        match &self.sh_compression {
+            Some(Ok(header)) => Ok(Cow::Owned(header.decompress(data)?)),
+            Some(Err(error)) => Err(error),
+            None => Ok(Cow::Borrowed(data)),
+        }
+    }
+
+    // Equivalent of readelf -p: every run of text with its offset inside the section
+    pub fn print_strings(&self, payload: &[u8]) -> Result<(), &'static str> {
+        self.print_strings_from(&self.contents(payload)?);
+        Ok(())
+    }
+
+    // Same as print_strings for contents that were already read, as from a streamed file
+    pub fn print_strings_from(&self, contents: &[u8]) {
+        if contents.is_empty() {
+            println!("Section '{}' has no data to dump.", self.sh_name_str);
+            return;
+        }
+
+        println!("{}String dump of section '{}':{}", termcolors::purple(), self.sh_name_str, termcolors::default());
+        let strings = section_strings(contents);
+        if strings.is_empty() {
+            println!("  No strings found in this section.");
+        }
+
+        for (offset, string) in strings {
+            // Control characters are shown escaped so they cannot garble the terminal
+            let visible: String = string.chars().map(|c| match c {
+                '\n' => String::from("\\n"),
+                '\x00'..='\x1F' => format!("^{}", (c as u8 + 0x40) as char),
+                '\x7F' => String::from("^?"),
+                _ => c.to_string(),
+            }).collect();
+            println!("  {}[{:6x}]  {}{}", termcolors::green(), offset, termcolors::white(), visible);
+        }
+        print!("{}", termcolors::default());
+    }
+
+    pub(crate) fn raw_contents<'a>(&self, payload: &'a [u8]) -> Result<&'a [u8], &'static str> {
+        let start = self.sh_offset.to_u64()? as usize;
+        let end = start.checked_add(self.sh_size.to_u64()? as usize).ok_or("Section size overflows")?;
+        payload.get(start..end).ok_or("Section lies outside the file")
     }
 }
 
 pub struct SectionHeaderInfo{
     pub offset: Word,
-    pub entries: types::Elf32Half,
+    pub entries: types::Elf32Word,
     pub size: types::Elf32Half,
-    pub names_index: types::Elf32Half,
+    pub names_index: types::Elf32Word,
 }
 
-#[derive(Debug)]
-pub struct SectionHeader{
-    entries: Vec<SectionHeaderEntry>,
+// Section header table viewed in place, entries are decoded each time they are asked for and borrow their names
+#[derive(Debug, Clone, Copy)]
+pub struct SectionHeader<'data> {
+    table: &'data [u8],
+    entry_size: usize,
+    names: &'data [u8],
+    source: Source<'data>,
+    machine: Machine,
+    is_32bit: bool,
+    is_little_endian: bool,
 }
 
-impl SectionHeader {
-    pub fn build(payload: &[u8], info: &SectionHeaderInfo, is_32bit: bool, is_little_endian: bool) -> Result<SectionHeader, &'static str> {
-        if payload.len() < info.offset.to_u64().unwrap() as usize + info.size as usize { 
-            return Err("Section header too short");
-        }
+impl<'data> SectionHeader<'data> {
+    pub fn build(payload: &'data [u8], info: &SectionHeaderInfo, machine: Machine, is_32bit: bool, is_little_endian: bool) -> Result<SectionHeader<'data>, &'static str> {
+        let minimum_entry_size = if is_32bit { SECTION_HEADER32_SIZE } else { SECTION_HEADER64_SIZE };
+        let offset = info.offset.to_u64()?;
+        check_table(payload.len() as u64, offset, info.size, minimum_entry_size, info.entries)?;
 
-        let mut entries: Vec<SectionHeaderEntry> = Vec::new();
-        let first_entry_offset: u64 = info.offset.to_u64()?;
-
-        let mut string_section_offset = 0;
-
-        for i in 0..info.entries {
-            let entry_offset = (first_entry_offset + info.size as u64 * i as u64) as usize;
-            let sh_name = types::Elf32Word::from_ne_bytes(payload[entry_offset..entry_offset+4].try_into().unwrap());
-            let sh_type_offset = entry_offset + 0x04;
-            let sh_type = SectionHeaderType::from_u32(&payload[sh_type_offset..sh_type_offset+4].try_into().unwrap(), is_little_endian).expect("Tried to get section header type");
-
-            let sh_flags_offset = entry_offset + 0x08;
-            let sh_flags;
-            let sh_addr;
-            let sh_offset;
-            let sh_size;
-
-            let sh_link_offset;
-            let sh_info_offset;
-
-            let sh_addralign;
-            let sh_entsize;
-
-            if is_32bit {
-                sh_flags = Word::Bits32(to_u32_from_slice(&payload[sh_flags_offset..sh_flags_offset+4].try_into().unwrap(), is_little_endian));
-                let sh_addr_offset = entry_offset + 0x0C;
-                sh_addr = Word::Bits32(to_u32_from_slice(&payload[sh_addr_offset..sh_addr_offset+4].try_into().unwrap(), is_little_endian));
-                let sh_offset_offset = entry_offset + 0x10;
-                sh_offset = Word::Bits32(to_u32_from_slice(&payload[sh_offset_offset..sh_offset_offset+4].try_into().unwrap(), is_little_endian));
-                let sh_size_offset =  entry_offset + 0x14;
-                sh_size = Word::Bits32(to_u32_from_slice(&payload[sh_size_offset..sh_size_offset+4].try_into().unwrap(), is_little_endian));
-                sh_link_offset = entry_offset + 0x18;
-                sh_info_offset = entry_offset + 0x1C;
-                let sh_addralign_offset = entry_offset + 0x20;
-                sh_addralign = Word::Bits32(to_u32_from_slice(&payload[sh_addralign_offset..sh_addralign_offset+4].try_into().unwrap(), is_little_endian));
-                let sh_entsize_offset = entry_offset + 0x24;
-                sh_entsize = Word::Bits32(to_u32_from_slice(&payload[sh_entsize_offset..sh_entsize_offset+4].try_into().unwrap(), is_little_endian));
-
-            } else {
-                sh_flags = Word::Bits64(to_u64_from_slice(&payload[sh_flags_offset..sh_flags_offset+8].try_into().unwrap(), is_little_endian));
-                let sh_addr_offset = entry_offset + 0x10;
-                sh_addr = Word::Bits64(to_u64_from_slice(&payload[sh_addr_offset..sh_addr_offset+8].try_into().unwrap(), is_little_endian));
-                let sh_offset_offset = entry_offset + 0x18;
-                sh_offset = Word::Bits64(to_u64_from_slice(&payload[sh_offset_offset..sh_offset_offset+8].try_into().unwrap(), is_little_endian));
-                let sh_size_offset =  entry_offset + 0x20;
-                sh_size = Word::Bits64(to_u64_from_slice(&payload[sh_size_offset..sh_size_offset+8].try_into().unwrap(), is_little_endian));
-                sh_link_offset = entry_offset + 0x28;
-                sh_info_offset = entry_offset + 0x2C;
-                let sh_addralign_offset = entry_offset + 0x30;
-                sh_addralign = Word::Bits64(to_u64_from_slice(&payload[sh_addralign_offset..sh_addralign_offset+8].try_into().unwrap(), is_little_endian));
-                let sh_entsize_offset = entry_offset + 0x38;
-                sh_entsize = Word::Bits64(to_u64_from_slice(&payload[sh_entsize_offset..sh_entsize_offset+8].try_into().unwrap(), is_little_endian));
-            }
-
-            let sh_link = types::Elf32Word::from_ne_bytes(payload[sh_link_offset..sh_link_offset+4].try_into().unwrap());
-            let sh_info = types::Elf32Word::from_ne_bytes(payload[sh_info_offset..sh_info_offset+4].try_into().unwrap());
-
-            let sh_name_str = String::from("UNKNOWN_NAME");
-
-            if let SectionHeaderType::ShtStrtab = sh_type {
-                if i == info.names_index {
-                    string_section_offset = sh_offset.to_u64().unwrap() as usize;
-                }
-            }
-
-            entries.push(SectionHeaderEntry{
-                sh_name: sh_name,
-                sh_name_str: sh_name_str,
-                sh_type: sh_type, 
-                sh_flags: sh_flags,
-                sh_addr: sh_addr,
-                sh_offset: sh_offset,
-                sh_size: sh_size,
-                sh_link: sh_link,
-                sh_info: sh_info,
-                sh_addralign: sh_addralign,
-                sh_entsize: sh_entsize});
-        }
+        let table = match info.entries {
+            0 => &[],
+            entries => &payload[offset as usize..offset as usize + info.size as usize * entries as usize],
+        };
+        let mut section_header = SectionHeader::from_table(table, info.size, &[], Source::Memory(payload), machine, is_32bit, is_little_endian);
+        section_header.names = match section_header.decode(info.names_index as usize) {
+            Some(entry) if matches!(entry.sh_type, SectionHeaderType::ShtStrtab) => entry.raw_contents(payload).unwrap_or_default(),
+            _ => &[],
+        };
+        Ok(section_header)
+    }
+
+    // Table and section names that were already read and checked, source is where compression headers are fetched from
+    pub(crate) fn from_table(table: &'data [u8], entry_size: types::Elf32Half, names: &'data [u8], source: Source<'data>, machine: Machine, is_32bit: bool, is_little_endian: bool) -> SectionHeader<'data> {
+        SectionHeader{table, entry_size: entry_size as usize, names, source, machine, is_32bit, is_little_endian}
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len().checked_div(self.entry_size).unwrap_or(0)
+    }
 
-        for entry in entries.iter_mut(){
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<SectionHeaderEntry<'data>> {
+        let mut entry = self.decode(index)?;
+        let source = self.source;
+        entry.detect_compression(|entry| {
+            let length = entry.sh_size.to_u64()?.min(COMPRESSION_HEADER_SIZE);
+            source.read_at(entry.sh_offset.to_u64()?, length).map_err(|_| "Section lies outside the file")
+        }, self.is_32bit, self.is_little_endian);
+        Some(entry)
+    }
 
-            let offset = string_section_offset + entry.sh_name as usize;
-            entry.sh_name_str = string_until_null(payload[offset..].to_vec());
+    pub fn entries(&self) -> impl Iterator<Item = SectionHeaderEntry<'data>> + 'data {
+        let header = *self;
+        (0..header.len()).filter_map(move |index| header.get(index))
+    }
+
+    // Compares names before reading compression headers, so looking up one section stays cheap in files with many of them
+    pub fn index_by_name(&self, name: &str) -> Option<usize> {
+        (0..self.len()).find(|index| self.decode(*index).is_some_and(|entry| entry.sh_name_str == name))
+    }
+
+    // Fields and name of an entry, without the compression header that may have to be read from the section
+    fn decode(&self, index: usize) -> Option<SectionHeaderEntry<'data>> {
+        if index >= self.len() {
+            return None;
         }
 
-         Ok(SectionHeader{entries: entries})
+        let mut entry = SectionHeaderEntry::build(self.table, index * self.entry_size, self.machine, self.is_32bit, self.is_little_endian);
+        entry.sh_name_str = name_at(self.names, entry.sh_name);
+        Some(entry)
     }
 
     pub fn print(&self){
@@ -178,53 +368,122 @@ impl SectionHeader {
             termcolors::red(),
             termcolors::default());
         println!("\t{}Flags", termcolors::yellow());
-        for (index, entry) in self.entries.iter().enumerate() {
+        for (index, entry) in self.entries().enumerate() {
             print!("{}[{index:3}]", termcolors::red());
             entry.print();
             println!("");
         }
  
     }
+
+    // Section whose file contents hold offset, NOBITS sections occupy no file space
+    pub fn section_at_offset(&self, offset: u64) -> Option<(usize, SectionHeaderEntry<'data>)> {
+        self.entries().enumerate().skip(1).find(|(_, entry)| {
+            let start = entry.sh_offset.to_u64().unwrap();
+            !matches!(entry.sh_type, SectionHeaderType::ShtNobits)
+                && offset >= start && offset - start < entry.sh_size.to_u64().unwrap()
+        })
+    }
+
+    // Allocated section whose memory image holds address; .tbss is skipped because the sections after it reuse its addresses
+    pub fn section_at_address(&self, address: u64) -> Option<(usize, SectionHeaderEntry<'data>)> {
+        self.entries().enumerate().skip(1).find(|(_, entry)| {
+            let start = entry.sh_addr.to_u64().unwrap();
+            entry.is_allocated()
+                && !(entry.is_tls() && matches!(entry.sh_type, SectionHeaderType::ShtNobits))
+                && address >= start && address - start < entry.sh_size.to_u64().unwrap()
+        })
+    }
+}
+
+// Names that are not valid UTF-8 come out empty
+fn name_at(names: &[u8], offset: types::Elf32Word) -> &str {
+    let name = names.get(offset as usize..).unwrap_or_default();
+    let name = match name.iter().position(|&byte| byte == 0) {
+        Some(end) => &name[..end],
+        None => name,
+    };
+    std::str::from_utf8(name).unwrap_or_default()
 }
 
-fn string_until_null(vec: Vec<u8>) -> String {
-    let slice = match vec.iter().position(|&b| b == 0) {
-        Some(pos) => &vec[..pos],
-        None => &vec[..],
+pub fn string_until_null(bytes: &[u8]) -> String {
+    let slice = match bytes.iter().position(|&b| b == 0) {
+        Some(pos) => &bytes[..pos],
+        None => bytes,
     };
 
     String::from_utf8_lossy(slice).into_owned()
 }
 
+// Splits a section into the strings found by string_until_null, each one starting at a printable character
+pub fn section_strings(bytes: &[u8]) -> Vec<(usize, String)> {
+    let mut strings: Vec<(usize, String)> = Vec::new();
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+        if !(0x20..0x7F).contains(&bytes[offset]) {
+            offset += 1;
+            continue;
+        }
+
+        let string = string_until_null(&bytes[offset..]);
+        let length = bytes[offset..].iter().position(|&b| b == 0).unwrap_or(bytes.len() - offset);
+        strings.push((offset, string));
+        offset += length + 1;
+    }
+
+    strings
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn section_header_should_resolve_names_of_big_endian_files() {
+        let mut table = [0u8; 0x80];
+        table[0x40..0x44].copy_from_slice(&1u32.to_be_bytes());
+        let section_header = SectionHeader::from_table(&table, 0x40, b"\0.text\0", Source::Memory(&[]), Machine::EMPPC64, false, false);
+        let text = section_header.get(1).unwrap();
+        assert_eq!(text.name_offset(), 1);
+        assert_eq!(text.name(), ".text");
+        assert_eq!(section_header.index_by_name(".text"), Some(1));
+    }
+
     #[test]
     fn string_unitl_null_should_read_to_first_null() {
         let payload: Vec<u8> = vec![0x52, 0x41, 0x44, 0x00];
-        let result = string_until_null(payload);
+        let result = string_until_null(&payload);
         assert_eq!(result, String::from("RAD"));
     }
 
     #[test]
     fn string_unitl_null_should_hadle_empty_payload() {
         let payload: Vec<u8> = vec![];
-        let result = string_until_null(payload);
+        let result = string_until_null(&payload);
         assert_eq!(result, String::from(""));
     }
 
     #[test]
     fn string_unitl_null_should_hadle_only_first_null() {
         let payload: Vec<u8> = vec![0x72, 0x61, 0x64, 0x00, 0x52, 0x41, 0x44, 0x00];
-        let result = string_until_null(payload);
+        let result = string_until_null(&payload);
         assert_eq!(result, String::from("rad"));
     }
 
     #[test]
     fn string_unitl_null_should_hadle_only_nulls() {
         let payload: Vec<u8> = vec![0x00, 0x00, 0x00];
-        let result = string_until_null(payload);
+        let result = string_until_null(&payload);
         assert_eq!(result, String::from(""));
     }
+
+    #[test]
+    fn section_strings_should_list_strings_with_offsets() {
+        let payload = b"\0GCC: 13.2\0\x01\x02ab\tc\0line\nnext";
+        assert_eq!(section_strings(payload), vec![
+            (1, String::from("GCC: 13.2")),
+            (13, String::from("ab\tc")),
+            (18, String::from("line\nnext"))]);
+    }
 }
@@ -8,6 +8,7 @@ pub const SHF_LINK_ORDER: usize = 0x80;
 pub const SHF_OS_NONCONFORMING: usize = 0x100;
 pub const SHF_GROUP: usize = 0x200;
 pub const SHF_TLS: usize = 0x400;
+pub const SHF_COMPRESSED: usize = 0x800;
 pub const SHF_MASKOS: usize = 0x0FF00000;
 pub const SHF_MASKPROC: usize = 0xF0000000;
 pub const SHF_ORDERED: usize = 0x4000000;
@@ -61,6 +62,10 @@ pub fn flags_to_string(flags: u64) -> String {
         result.push("(SHF_TLS) Section hold thread-local data");
     }
 
+    if check_mask(flags, SHF_COMPRESSED) {
+        result.push("(SHF_COMPRESSED) Section with compressed data");
+    }
+
     if check_mask(flags, SHF_MASKOS) {
         result.push("(SHF_MASKOS) OS-specific");
     }
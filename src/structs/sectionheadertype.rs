@@ -1,6 +1,7 @@
 use crate::bits::to_u32_from_slice;
+use crate::structs::machine::Machine;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SectionHeaderType{
     ShtNull,
     ShtProgbits,
@@ -19,42 +20,144 @@ pub enum SectionHeaderType{
     ShtPreinitArray,
     ShtGroup,
     ShtSymtabShndx,
-    ShtNum,
-    ShtLoos(u32)
+    ShtRelr,
+    ShtAndroidRel,
+    ShtAndroidRela,
+    ShtLlvmOdrtab,
+    ShtLlvmLinkerOptions,
+    ShtLlvmAddrsig,
+    ShtLlvmDependentLibraries,
+    ShtLlvmSympart,
+    ShtLlvmPartEhdr,
+    ShtLlvmPartPhdr,
+    ShtLlvmBbAddrMapV0,
+    ShtLlvmCallGraphProfile,
+    ShtLlvmBbAddrMap,
+    ShtLlvmOffloading,
+    ShtLlvmLto,
+    ShtAndroidRelr,
+    ShtGnuSframe,
+    ShtGnuAttributes,
+    ShtGnuHash,
+    ShtGnuLiblist,
+    ShtChecksum,
+    ShtSunwMove,
+    ShtSunwComdat,
+    ShtSunwSyminfo,
+    ShtGnuVerdef,
+    ShtGnuVerneed,
+    ShtGnuVersym,
+    ShtArmExidx,
+    ShtArmPreemptmap,
+    ShtArmAttributes,
+    ShtArmDebugoverlay,
+    ShtArmOverlaysection,
+    ShtAarch64Attributes,
+    ShtAarch64AuthRelr,
+    ShtAarch64MemtagGlobalsStatic,
+    ShtAarch64MemtagGlobalsDynamic,
+    ShtX8664Unwind,
+    ShtMipsLiblist,
+    ShtMipsMsym,
+    ShtMipsConflict,
+    ShtMipsGptab,
+    ShtMipsUcode,
+    ShtMipsDebug,
+    ShtMipsReginfo,
+    ShtMipsOptions,
+    ShtMipsDwarf,
+    ShtMipsAbiflags,
+    ShtMipsXhash,
+    ShtRiscvAttributes,
+    ShtLoos(u32),
+    ShtLoproc(u32),
+    ShtLouser(u32),
+    ShtUnknown(u32),
 }
 
 const SHT_LOOS: u32 = 0x60000000;
+const SHT_HIOS: u32 = 0x6FFFFFFF;
+const SHT_LOPROC: u32 = 0x70000000;
+const SHT_HIPROC: u32 = 0x7FFFFFFF;
+const SHT_LOUSER: u32 = 0x80000000;
 
 impl SectionHeaderType {
-    pub fn from_u32(value: &[u8;4], is_little_endian: bool) -> Option<SectionHeaderType>  {
+    // Processor specific values overlap between architectures, so they are only named for the machine that defines them.
+    // Values nothing defines are kept as unknown instead of failing the whole section header
+    pub fn from_u32(value: &[u8;4], machine: Machine, is_little_endian: bool) -> SectionHeaderType  {
         let value = to_u32_from_slice(value, is_little_endian); // Todo: check if section type description use endianness (rather not)
 
-        match value {
-            0x0 => Some(SectionHeaderType::ShtNull),
-            0x1 => Some(SectionHeaderType::ShtProgbits),
-            0x2 => Some(SectionHeaderType::ShtSymtab),
-            0x3 => Some(SectionHeaderType::ShtStrtab),
-            0x4 => Some(SectionHeaderType::ShtRela),
-            0x5 => Some(SectionHeaderType::ShtHash),
-            0x6 => Some(SectionHeaderType::ShtDynamic),
-            0x7 => Some(SectionHeaderType::ShtNote),
-            0x8 => Some(SectionHeaderType::ShtNobits),
-            0x9 => Some(SectionHeaderType::ShtRel),
-            0x0A => Some(SectionHeaderType::ShtShlib),
-            0x0B => Some(SectionHeaderType::ShtDynsym),
-            0x0E => Some(SectionHeaderType::ShtInitArray),
-            0x0F => Some(SectionHeaderType::ShtFiniArray),
-            0x10 => Some(SectionHeaderType::ShtPreinitArray),
-            0x11 => Some(SectionHeaderType::ShtGroup),
-            0x12 => Some(SectionHeaderType::ShtSymtabShndx),
-            0x13 => Some(SectionHeaderType::ShtNum),
-            _ => {
-                if value >= SHT_LOOS {
-                    Some(SectionHeaderType::ShtLoos(value))
-                } else {
-                    None
-                }
-            },
+        match (value, machine) {
+            (0x0, _) => SectionHeaderType::ShtNull,
+            (0x1, _) => SectionHeaderType::ShtProgbits,
+            (0x2, _) => SectionHeaderType::ShtSymtab,
+            (0x3, _) => SectionHeaderType::ShtStrtab,
+            (0x4, _) => SectionHeaderType::ShtRela,
+            (0x5, _) => SectionHeaderType::ShtHash,
+            (0x6, _) => SectionHeaderType::ShtDynamic,
+            (0x7, _) => SectionHeaderType::ShtNote,
+            (0x8, _) => SectionHeaderType::ShtNobits,
+            (0x9, _) => SectionHeaderType::ShtRel,
+            (0x0A, _) => SectionHeaderType::ShtShlib,
+            (0x0B, _) => SectionHeaderType::ShtDynsym,
+            (0x0E, _) => SectionHeaderType::ShtInitArray,
+            (0x0F, _) => SectionHeaderType::ShtFiniArray,
+            (0x10, _) => SectionHeaderType::ShtPreinitArray,
+            (0x11, _) => SectionHeaderType::ShtGroup,
+            (0x12, _) => SectionHeaderType::ShtSymtabShndx,
+            (0x13, _) => SectionHeaderType::ShtRelr,
+            (0x60000001, _) => SectionHeaderType::ShtAndroidRel,
+            (0x60000002, _) => SectionHeaderType::ShtAndroidRela,
+            (0x6FFF4C00, _) => SectionHeaderType::ShtLlvmOdrtab,
+            (0x6FFF4C01, _) => SectionHeaderType::ShtLlvmLinkerOptions,
+            (0x6FFF4C03, _) => SectionHeaderType::ShtLlvmAddrsig,
+            (0x6FFF4C04, _) => SectionHeaderType::ShtLlvmDependentLibraries,
+            (0x6FFF4C05, _) => SectionHeaderType::ShtLlvmSympart,
+            (0x6FFF4C06, _) => SectionHeaderType::ShtLlvmPartEhdr,
+            (0x6FFF4C07, _) => SectionHeaderType::ShtLlvmPartPhdr,
+            (0x6FFF4C08, _) => SectionHeaderType::ShtLlvmBbAddrMapV0,
+            (0x6FFF4C09, _) => SectionHeaderType::ShtLlvmCallGraphProfile,
+            (0x6FFF4C0A, _) => SectionHeaderType::ShtLlvmBbAddrMap,
+            (0x6FFF4C0B, _) => SectionHeaderType::ShtLlvmOffloading,
+            (0x6FFF4C0C, _) => SectionHeaderType::ShtLlvmLto,
+            (0x6FFFFF00, _) => SectionHeaderType::ShtAndroidRelr,
+            (0x6FFFFFF4, _) => SectionHeaderType::ShtGnuSframe,
+            (0x6FFFFFF5, _) => SectionHeaderType::ShtGnuAttributes,
+            (0x6FFFFFF6, _) => SectionHeaderType::ShtGnuHash,
+            (0x6FFFFFF7, _) => SectionHeaderType::ShtGnuLiblist,
+            (0x6FFFFFF8, _) => SectionHeaderType::ShtChecksum,
+            (0x6FFFFFFA, _) => SectionHeaderType::ShtSunwMove,
+            (0x6FFFFFFB, _) => SectionHeaderType::ShtSunwComdat,
+            (0x6FFFFFFC, _) => SectionHeaderType::ShtSunwSyminfo,
+            (0x6FFFFFFD, _) => SectionHeaderType::ShtGnuVerdef,
+            (0x6FFFFFFE, _) => SectionHeaderType::ShtGnuVerneed,
+            (0x6FFFFFFF, _) => SectionHeaderType::ShtGnuVersym,
+            (0x70000001, Machine::EMARM) => SectionHeaderType::ShtArmExidx,
+            (0x70000002, Machine::EMARM) => SectionHeaderType::ShtArmPreemptmap,
+            (0x70000003, Machine::EMARM) => SectionHeaderType::ShtArmAttributes,
+            (0x70000004, Machine::EMARM) => SectionHeaderType::ShtArmDebugoverlay,
+            (0x70000005, Machine::EMARM) => SectionHeaderType::ShtArmOverlaysection,
+            (0x70000003, Machine::EMAARCH64) => SectionHeaderType::ShtAarch64Attributes,
+            (0x70000004, Machine::EMAARCH64) => SectionHeaderType::ShtAarch64AuthRelr,
+            (0x70000007, Machine::EMAARCH64) => SectionHeaderType::ShtAarch64MemtagGlobalsStatic,
+            (0x70000008, Machine::EMAARCH64) => SectionHeaderType::ShtAarch64MemtagGlobalsDynamic,
+            (0x70000001, Machine::EMX8664) => SectionHeaderType::ShtX8664Unwind,
+            (0x70000000, Machine::EMMIPS) => SectionHeaderType::ShtMipsLiblist,
+            (0x70000001, Machine::EMMIPS) => SectionHeaderType::ShtMipsMsym,
+            (0x70000002, Machine::EMMIPS) => SectionHeaderType::ShtMipsConflict,
+            (0x70000003, Machine::EMMIPS) => SectionHeaderType::ShtMipsGptab,
+            (0x70000004, Machine::EMMIPS) => SectionHeaderType::ShtMipsUcode,
+            (0x70000005, Machine::EMMIPS) => SectionHeaderType::ShtMipsDebug,
+            (0x70000006, Machine::EMMIPS) => SectionHeaderType::ShtMipsReginfo,
+            (0x7000000D, Machine::EMMIPS) => SectionHeaderType::ShtMipsOptions,
+            (0x7000001E, Machine::EMMIPS) => SectionHeaderType::ShtMipsDwarf,
+            (0x7000002A, Machine::EMMIPS) => SectionHeaderType::ShtMipsAbiflags,
+            (0x7000002B, Machine::EMMIPS) => SectionHeaderType::ShtMipsXhash,
+            (0x70000003, Machine::EMRISCV) => SectionHeaderType::ShtRiscvAttributes,
+            (SHT_LOOS..=SHT_HIOS, _) => SectionHeaderType::ShtLoos(value),
+            (SHT_LOPROC..=SHT_HIPROC, _) => SectionHeaderType::ShtLoproc(value),
+            (SHT_LOUSER.., _) => SectionHeaderType::ShtLouser(value),
+            _ => SectionHeaderType::ShtUnknown(value),
         }
     }
 
@@ -66,6 +169,9 @@ impl fmt::Display for SectionHeaderType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let num: u32 = match self {
             SectionHeaderType::ShtLoos(num)     => *num,
+            SectionHeaderType::ShtLoproc(num)   => *num,
+            SectionHeaderType::ShtLouser(num)   => *num,
+            SectionHeaderType::ShtUnknown(num)  => *num,
             _ => 0,
         };
         let description =  match self {
@@ -86,12 +192,63 @@ impl fmt::Display for SectionHeaderType {
             SectionHeaderType::ShtPreinitArray  => "(PREINITARRAY) Array of pre-constructors",
             SectionHeaderType::ShtGroup         => "(GROUP) Section group",
             SectionHeaderType::ShtSymtabShndx   => "(SYMTABSHNDX) Extended section indices",
-            SectionHeaderType::ShtNum           => "(NUM) Number of defined types",
+            SectionHeaderType::ShtRelr          => "(RELR) Relative relocation entries, compressed",
+            SectionHeaderType::ShtAndroidRel    => "(ANDROID_REL) Packed relocation entries, no addends",
+            SectionHeaderType::ShtAndroidRela   => "(ANDROID_RELA) Packed relocation entries with addends",
+            SectionHeaderType::ShtLlvmOdrtab    => "(LLVM_ODRTAB) LLVM ODR table",
+            SectionHeaderType::ShtLlvmLinkerOptions         => "(LLVM_LINKER_OPTIONS) Linker options",
+            SectionHeaderType::ShtLlvmAddrsig               => "(LLVM_ADDRSIG) Address-significant symbols",
+            SectionHeaderType::ShtLlvmDependentLibraries    => "(LLVM_DEPENDENT_LIBRARIES) Dependent libraries",
+            SectionHeaderType::ShtLlvmSympart               => "(LLVM_SYMPART) Symbol partition specification",
+            SectionHeaderType::ShtLlvmPartEhdr              => "(LLVM_PART_EHDR) Partition ELF header",
+            SectionHeaderType::ShtLlvmPartPhdr              => "(LLVM_PART_PHDR) Partition program headers",
+            SectionHeaderType::ShtLlvmBbAddrMapV0           => "(LLVM_BB_ADDR_MAP_V0) Basic block address map, version 0",
+            SectionHeaderType::ShtLlvmCallGraphProfile      => "(LLVM_CALL_GRAPH_PROFILE) Call graph profile",
+            SectionHeaderType::ShtLlvmBbAddrMap             => "(LLVM_BB_ADDR_MAP) Basic block address map",
+            SectionHeaderType::ShtLlvmOffloading            => "(LLVM_OFFLOADING) Offloading data",
+            SectionHeaderType::ShtLlvmLto                   => "(LLVM_LTO) LTO bitcode",
+            SectionHeaderType::ShtAndroidRelr   => "(ANDROID_RELR) Relative relocation entries, compressed",
+            SectionHeaderType::ShtGnuSframe     => "(GNU_SFRAME) Stack trace information",
+            SectionHeaderType::ShtGnuAttributes => "(GNU_ATTRIBUTES) Object attributes",
+            SectionHeaderType::ShtGnuHash       => "(GNU_HASH) GNU-style hash table",
+            SectionHeaderType::ShtGnuLiblist    => "(GNU_LIBLIST) Prelink library list",
+            SectionHeaderType::ShtChecksum      => "(CHECKSUM) Checksum for DSO content",
+            SectionHeaderType::ShtSunwMove      => "(SUNW_MOVE) Sun move entries",
+            SectionHeaderType::ShtSunwComdat    => "(SUNW_COMDAT) Sun COMDAT section",
+            SectionHeaderType::ShtSunwSyminfo   => "(SUNW_SYMINFO) Sun symbol information",
+            SectionHeaderType::ShtGnuVerdef     => "(VERDEF) Version definition section",
+            SectionHeaderType::ShtGnuVerneed    => "(VERNEED) Version needs section",
+            SectionHeaderType::ShtGnuVersym     => "(VERSYM) Version symbol table",
+            SectionHeaderType::ShtArmExidx          => "(ARM_EXIDX) Exception index table",
+            SectionHeaderType::ShtArmPreemptmap     => "(ARM_PREEMPTMAP) BPABI DLL dynamic linking pre-emption map",
+            SectionHeaderType::ShtArmAttributes     => "(ARM_ATTRIBUTES) Object file compatibility attributes",
+            SectionHeaderType::ShtArmDebugoverlay   => "(ARM_DEBUGOVERLAY) Debug overlay",
+            SectionHeaderType::ShtArmOverlaysection => "(ARM_OVERLAYSECTION) Overlay section",
+            SectionHeaderType::ShtAarch64Attributes             => "(AARCH64_ATTRIBUTES) Build attributes",
+            SectionHeaderType::ShtAarch64AuthRelr               => "(AARCH64_AUTH_RELR) Signed relative relocations, compressed",
+            SectionHeaderType::ShtAarch64MemtagGlobalsStatic    => "(AARCH64_MEMTAG_GLOBALS_STATIC) Tagged globals",
+            SectionHeaderType::ShtAarch64MemtagGlobalsDynamic   => "(AARCH64_MEMTAG_GLOBALS_DYNAMIC) Tagged globals descriptors",
+            SectionHeaderType::ShtX8664Unwind   => "(X86_64_UNWIND) Unwind information",
+            SectionHeaderType::ShtMipsLiblist   => "(MIPS_LIBLIST) Shared objects used in link",
+            SectionHeaderType::ShtMipsMsym      => "(MIPS_MSYM) Minimal symbol table",
+            SectionHeaderType::ShtMipsConflict  => "(MIPS_CONFLICT) Conflicting symbols",
+            SectionHeaderType::ShtMipsGptab     => "(MIPS_GPTAB) Global data area sizes",
+            SectionHeaderType::ShtMipsUcode     => "(MIPS_UCODE) Reserved for SGI/MIPS compilers",
+            SectionHeaderType::ShtMipsDebug     => "(MIPS_DEBUG) MIPS ECOFF debugging information",
+            SectionHeaderType::ShtMipsReginfo   => "(MIPS_REGINFO) Register usage information",
+            SectionHeaderType::ShtMipsOptions   => "(MIPS_OPTIONS) Miscellaneous options",
+            SectionHeaderType::ShtMipsDwarf     => "(MIPS_DWARF) DWARF debugging information",
+            SectionHeaderType::ShtMipsAbiflags  => "(MIPS_ABIFLAGS) ABI flags",
+            SectionHeaderType::ShtMipsXhash     => "(MIPS_XHASH) GNU-style hash table with xlat",
+            SectionHeaderType::ShtRiscvAttributes   => "(RISCV_ATTRIBUTES) RISC-V attributes",
             SectionHeaderType::ShtLoos(_)       => "(OS) (OS specific)",
+            SectionHeaderType::ShtLoproc(_)     => "(PROC) (Processor specific)",
+            SectionHeaderType::ShtLouser(_)     => "(USER) (Application specific)",
+            SectionHeaderType::ShtUnknown(_)    => "(UNKNOWN) (Unknown section type)",
 
         };        
 
-        if matches!(self, SectionHeaderType::ShtLoos(_))
+        if matches!(self, SectionHeaderType::ShtLoos(_) | SectionHeaderType::ShtLoproc(_) | SectionHeaderType::ShtLouser(_) | SectionHeaderType::ShtUnknown(_))
         {
             write!(f, "{} (0x{:X})", description, num)
         }
@@ -104,7 +261,6 @@ impl fmt::Display for SectionHeaderType {
 
 
 
-
 // 0x0	SHT_NULL	Section header table entry unused
 // 0x1	SHT_PROGBITS	Program data
 // 0x2	SHT_SYMTAB	Symbol table
@@ -122,5 +278,29 @@ impl fmt::Display for SectionHeaderType {
 // 0x10	SHT_PREINIT_ARRAY	Array of pre-constructors
 // 0x11	SHT_GROUP	Section group
 // 0x12	SHT_SYMTAB_SHNDX	Extended section indices
-// 0x13	SHT_NUM	Number of defined types.
+// 0x13	SHT_RELR	Relative relocation entries, compressed.
 // 0x60000000	SHT_LOOS	Start OS-specific.
+// 0x6FFFFFF6	SHT_GNU_HASH	GNU-style hash table.
+// 0x6FFFFFFD	SHT_GNU_verdef	Version definition section.
+// 0x6FFFFFFE	SHT_GNU_verneed	Version needs section.
+// 0x6FFFFFFF	SHT_GNU_versym	Version symbol table.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_u32_should_name_processor_types_per_machine() {
+        let value = 0x70000001u32.to_le_bytes();
+        assert!(matches!(SectionHeaderType::from_u32(&value, Machine::EMX8664, true), SectionHeaderType::ShtX8664Unwind));
+        assert!(matches!(SectionHeaderType::from_u32(&value, Machine::EMARM, true), SectionHeaderType::ShtArmExidx));
+        assert!(matches!(SectionHeaderType::from_u32(&value, Machine::EMRISCV, true), SectionHeaderType::ShtLoproc(0x70000001)));
+    }
+
+    #[test]
+    fn from_u32_should_keep_undefined_values() {
+        assert!(matches!(SectionHeaderType::from_u32(&0x14u32.to_be_bytes(), Machine::EMX8664, false), SectionHeaderType::ShtUnknown(0x14)));
+        assert!(matches!(SectionHeaderType::from_u32(&0x80000001u32.to_le_bytes(), Machine::EMX8664, true), SectionHeaderType::ShtLouser(0x80000001)));
+        assert_eq!(format!("{}", SectionHeaderType::ShtUnknown(0x14)), "(UNKNOWN) (Unknown section type) (0x14)");
+    }
+}
@@ -0,0 +1,145 @@
+use crate::structs::programheader::{ProgramHeader, ProgramHeaderEntry};
+use crate::structs::sectionheader::{SectionHeader, SectionHeaderEntry};
+use crate::structs::sectionheadertype::SectionHeaderType;
+use crate::structs::segmenttype::SegmentType;
+use crate::termcolors;
+
+#[derive(Debug)]
+struct SegmentExtent {
+    offset: u64,
+    file_size: u64,
+    address: u64,
+    memory_size: u64,
+}
+
+#[derive(Debug)]
+struct SectionExtent {
+    offset: u64,
+    size: u64,
+    address: u64,
+    is_allocated: bool,
+    is_tls: bool,
+    is_nobits: bool,
+}
+
+#[derive(Debug)]
+pub struct SegmentMapping {
+    segments: Vec<Vec<String>>,
+}
+
+impl SegmentMapping {
+    pub fn build(program_header: &ProgramHeader, section_header: &SectionHeader) -> SegmentMapping {
+        let segments = program_header.entries()
+            .map(|segment| {
+                section_header.entries()
+                    .skip(1)
+                    .filter(|section| section_in_segment(&section_extent(section), &segment_extent(&segment), segment.segment_type()))
+                    .map(|section| section.name().to_string())
+                    .collect()
+            })
+            .collect();
+
+        SegmentMapping { segments }
+    }
+
+    pub fn print(&self) {
+        println!("{}Section to segment mapping:{}", termcolors::purple(), termcolors::default());
+        println!("{}Segment\t{}Sections{}", termcolors::red(), termcolors::white(), termcolors::default());
+        for (index, sections) in self.segments.iter().enumerate() {
+            println!("{}{index:3}\t{}{}{}", termcolors::red(), termcolors::white(), sections.join(" "), termcolors::default());
+        }
+    }
+}
+
+fn segment_extent(segment: &ProgramHeaderEntry) -> SegmentExtent {
+    SegmentExtent {
+        offset: segment.offset().to_u64().unwrap(),
+        file_size: segment.file_size().to_u64().unwrap(),
+        address: segment.virtual_address().to_u64().unwrap(),
+        memory_size: segment.memory_size().to_u64().unwrap(),
+    }
+}
+
+fn section_extent(section: &SectionHeaderEntry) -> SectionExtent {
+    SectionExtent {
+        offset: section.offset().to_u64().unwrap(),
+        size: section.size().to_u64().unwrap(),
+        address: section.address().to_u64().unwrap(),
+        is_allocated: section.is_allocated(),
+        is_tls: section.is_tls(),
+        is_nobits: matches!(section.section_type(), SectionHeaderType::ShtNobits),
+    }
+}
+
+// Same rules as readelf: NOBITS sections only need an address inside the segment, and .tbss belongs to PT_TLS
+// alone since it takes no space in the image, where the following sections overlap its addresses
+fn section_in_segment(section: &SectionExtent, segment: &SegmentExtent, segment_type: &SegmentType) -> bool {
+    let is_tls_segment = matches!(segment_type, SegmentType::PtTls);
+    if section.is_nobits && section.is_tls && !is_tls_segment {
+        return false;
+    }
+
+    let tls_allowed = if section.is_tls {
+        matches!(segment_type, SegmentType::PtTls | SegmentType::PtGnuRelro | SegmentType::PtLoad)
+    } else {
+        !matches!(segment_type, SegmentType::PtTls | SegmentType::PtPhdr)
+    };
+    let needs_allocation = matches!(segment_type,
+        SegmentType::PtLoad | SegmentType::PtDynamic | SegmentType::PtGnuEhFrame
+        | SegmentType::PtGnuStack | SegmentType::PtGnuRelro | SegmentType::PtGnuSframe);
+    if !tls_allowed || (needs_allocation && !section.is_allocated) {
+        return false;
+    }
+
+    let in_file = section.is_nobits
+        || (section.offset >= segment.offset
+            && section.offset - segment.offset <= segment.file_size.wrapping_sub(1)
+            && section.offset - segment.offset + section.size <= segment.file_size);
+    let in_memory = !section.is_allocated
+        || (section.address >= segment.address
+            && section.address - segment.address <= segment.memory_size.wrapping_sub(1)
+            && section.address - segment.address + section.size <= segment.memory_size);
+    if !in_file || !in_memory {
+        return false;
+    }
+
+    // Empty sections sitting on either edge of PT_DYNAMIC or PT_NOTE are not considered part of them
+    if matches!(segment_type, SegmentType::PtDynamic | SegmentType::PtNote) && section.size == 0 && segment.memory_size != 0 {
+        let strictly_in_file = section.is_nobits
+            || (section.offset > segment.offset && section.offset - segment.offset < segment.file_size);
+        let strictly_in_memory = !section.is_allocated
+            || (section.address > segment.address && section.address - segment.address < segment.memory_size);
+        return strictly_in_file && strictly_in_memory;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(offset: u64, size: u64, address: u64, is_tls: bool, is_nobits: bool) -> SectionExtent {
+        SectionExtent { offset, size, address, is_allocated: true, is_tls, is_nobits }
+    }
+
+    #[test]
+    fn section_in_segment_should_handle_tbss() {
+        let tls = SegmentExtent { offset: 0x2de8, file_size: 0x10, address: 0x3de8, memory_size: 0x28 };
+        let load = SegmentExtent { offset: 0x2de8, file_size: 0x250, address: 0x3de8, memory_size: 0x260 };
+        let tbss = section(0x2df8, 0x18, 0x3df8, true, true);
+        let data = section(0x2df8, 0x240, 0x3df8, false, false);
+
+        assert!(section_in_segment(&tbss, &tls, &SegmentType::PtTls));
+        assert!(!section_in_segment(&tbss, &load, &SegmentType::PtLoad));
+        assert!(section_in_segment(&data, &load, &SegmentType::PtLoad));
+        assert!(!section_in_segment(&data, &tls, &SegmentType::PtTls));
+    }
+
+    #[test]
+    fn section_in_segment_should_require_file_contents_only_for_progbits() {
+        let load = SegmentExtent { offset: 0x1000, file_size: 0x100, address: 0x401000, memory_size: 0x300 };
+        assert!(section_in_segment(&section(0x1100, 0x200, 0x401100, false, true), &load, &SegmentType::PtLoad));
+        assert!(!section_in_segment(&section(0x1100, 0x200, 0x401100, false, false), &load, &SegmentType::PtLoad));
+    }
+}
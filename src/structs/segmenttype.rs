@@ -1,6 +1,7 @@
 use crate::bits::to_u32_from_slice;
+use crate::structs::machine::Machine;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SegmentType {
     PtNull,
     PtLoad,
@@ -10,8 +11,29 @@ pub enum SegmentType {
     PtShlib,
     PtPhdr,
     PtTls,
+    PtGnuEhFrame,
+    PtGnuStack,
+    PtGnuRelro,
+    PtGnuProperty,
+    PtGnuSframe,
+    PtSunwUnwind,
+    PtSunwBss,
+    PtSunwStack,
+    PtSunwDtrace,
+    PtSunwCap,
+    PtOpenbsdMutable,
+    PtOpenbsdRandomize,
+    PtOpenbsdWxneeded,
+    PtOpenbsdNobtcfi,
+    PtOpenbsdSyscalls,
+    PtOpenbsdBootdata,
+    PtArmExidx,
+    PtMipsAbiflags,
+    PtRiscvAttributes,
+    PtAarch64MemtagMte,
     PtOs(u32),
     PtProc(u32),
+    PtUnknown(u32),
 }
 
 const PT_LOOS: u32 = 0x60000000;
@@ -20,21 +42,42 @@ const PT_LOPROC: u32 = 0x70000000;
 const PT_HIPROC: u32 = 0x7FFFFFFF;
 
 impl SegmentType {
-    pub fn from_u32(value: &[u8;4], is_little_endian: bool) -> Option<SegmentType> {
+    // Processor specific values overlap between architectures, so they are only named for the machine that defines them
+    pub fn from_u32(value: &[u8;4], machine: Machine, is_little_endian: bool) -> SegmentType {
         let value = to_u32_from_slice(value, is_little_endian);    // Todo: check if segment type description use endianness (rather not)
 
-        match value {
-            0x00000000 => Some(SegmentType::PtNull),
-            0x00000001 => Some(SegmentType::PtLoad),
-            0x00000002 => Some(SegmentType::PtDynamic),
-            0x00000003 => Some(SegmentType::PtInterp),
-            0x00000004 => Some(SegmentType::PtNote),
-            0x00000005 => Some(SegmentType::PtShlib),
-            0x00000006 => Some(SegmentType::PtPhdr),
-            0x00000007 => Some(SegmentType::PtTls),
-            PT_LOOS..=PT_HIOS => Some(SegmentType::PtOs(value)),
-            PT_LOPROC..=PT_HIPROC => Some(SegmentType::PtProc(value)),
-            _ => None,
+        match (value, machine) {
+            (0x00000000, _) => SegmentType::PtNull,
+            (0x00000001, _) => SegmentType::PtLoad,
+            (0x00000002, _) => SegmentType::PtDynamic,
+            (0x00000003, _) => SegmentType::PtInterp,
+            (0x00000004, _) => SegmentType::PtNote,
+            (0x00000005, _) => SegmentType::PtShlib,
+            (0x00000006, _) => SegmentType::PtPhdr,
+            (0x00000007, _) => SegmentType::PtTls,
+            (0x6474E550, _) => SegmentType::PtGnuEhFrame,
+            (0x6474E551, _) => SegmentType::PtGnuStack,
+            (0x6474E552, _) => SegmentType::PtGnuRelro,
+            (0x6474E553, _) => SegmentType::PtGnuProperty,
+            (0x6474E554, _) => SegmentType::PtGnuSframe,
+            (0x6464E550, _) => SegmentType::PtSunwUnwind,
+            (0x6FFFFFFA, _) => SegmentType::PtSunwBss,
+            (0x6FFFFFFB, _) => SegmentType::PtSunwStack,
+            (0x6FFFFFFC, _) => SegmentType::PtSunwDtrace,
+            (0x6FFFFFFD, _) => SegmentType::PtSunwCap,
+            (0x65A3DBE5, _) => SegmentType::PtOpenbsdMutable,
+            (0x65A3DBE6, _) => SegmentType::PtOpenbsdRandomize,
+            (0x65A3DBE7, _) => SegmentType::PtOpenbsdWxneeded,
+            (0x65A3DBE8, _) => SegmentType::PtOpenbsdNobtcfi,
+            (0x65A3DBE9, _) => SegmentType::PtOpenbsdSyscalls,
+            (0x65A41BE6, _) => SegmentType::PtOpenbsdBootdata,
+            (0x70000001, Machine::EMARM) => SegmentType::PtArmExidx,
+            (0x70000003, Machine::EMMIPS) => SegmentType::PtMipsAbiflags,
+            (0x70000003, Machine::EMRISCV) => SegmentType::PtRiscvAttributes,
+            (0x70000002, Machine::EMAARCH64) => SegmentType::PtAarch64MemtagMte,
+            (PT_LOOS..=PT_HIOS, _) => SegmentType::PtOs(value),
+            (PT_LOPROC..=PT_HIPROC, _) => SegmentType::PtProc(value),
+            _ => SegmentType::PtUnknown(value),
         }
     }
 }
@@ -46,6 +89,7 @@ impl fmt::Display for SegmentType {
         let num: u32 = match self {
             SegmentType::PtOs(num)     => *num,
             SegmentType::PtProc(num)   => *num,
+            SegmentType::PtUnknown(num) => *num,
             _ => 0,
         };
         let description =  match self {
@@ -57,17 +101,63 @@ impl fmt::Display for SegmentType {
             SegmentType::PtShlib    => "Reserved",
             SegmentType::PtPhdr     => "Segment containing program header table itself",
             SegmentType::PtTls      => "Thread-Local Storage template",
+            SegmentType::PtGnuEhFrame       => "GNU_EH_FRAME (Exception handling frame lookup table)",
+            SegmentType::PtGnuStack         => "GNU_STACK (Stack executability)",
+            SegmentType::PtGnuRelro         => "GNU_RELRO (Read-only after relocation)",
+            SegmentType::PtGnuProperty      => "GNU_PROPERTY (GNU program properties)",
+            SegmentType::PtGnuSframe        => "GNU_SFRAME (Stack trace information)",
+            SegmentType::PtSunwUnwind       => "SUNW_UNWIND (Stack unwind tables)",
+            SegmentType::PtSunwBss          => "SUNW_BSS (Sun specific bss)",
+            SegmentType::PtSunwStack        => "SUNW_STACK (Stack segment)",
+            SegmentType::PtSunwDtrace       => "SUNW_DTRACE (DTrace reserved)",
+            SegmentType::PtSunwCap          => "SUNW_CAP (Capability requirements)",
+            SegmentType::PtOpenbsdMutable   => "OPENBSD_MUTABLE (Mutable .bss)",
+            SegmentType::PtOpenbsdRandomize => "OPENBSD_RANDOMIZE (Fill with random data)",
+            SegmentType::PtOpenbsdWxneeded  => "OPENBSD_WXNEEDED (Program does W^X violations)",
+            SegmentType::PtOpenbsdNobtcfi   => "OPENBSD_NOBTCFI (No branch target CFI)",
+            SegmentType::PtOpenbsdSyscalls  => "OPENBSD_SYSCALLS (System call sites)",
+            SegmentType::PtOpenbsdBootdata  => "OPENBSD_BOOTDATA (Section for boot arguments)",
+            SegmentType::PtArmExidx         => "ARM_EXIDX (Exception unwind tables)",
+            SegmentType::PtMipsAbiflags     => "MIPS_ABIFLAGS (ABI flags)",
+            SegmentType::PtRiscvAttributes  => "RISCV_ATTRIBUTES (RISC-V attributes)",
+            SegmentType::PtAarch64MemtagMte => "AARCH64_MEMTAG_MTE (MTE memory tags)",
             SegmentType::PtOs(_)    => "OS (OS specific)",
             SegmentType::PtProc(_)  => "PROC (Proc specific)",
+            SegmentType::PtUnknown(_) => "<unknown>",
         };        
 
         if matches!(self, SegmentType::PtOs(_)) || matches!(self, SegmentType::PtProc(_))
         {
             write!(f, "{} (0x{:X})", description, num)
         }
+        else if matches!(self, SegmentType::PtUnknown(_)) {
+            // Same form readelf uses for types outside every known range
+            write!(f, "{}: 0x{:x}", description, num)
+        }
         else {
             write!(f, "{}", description)
         }
 
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_u32_should_name_processor_types_per_machine() {
+        let value = 0x70000003u32.to_le_bytes();
+        assert!(matches!(SegmentType::from_u32(&value, Machine::EMRISCV, true), SegmentType::PtRiscvAttributes));
+        assert!(matches!(SegmentType::from_u32(&value, Machine::EMMIPS, true), SegmentType::PtMipsAbiflags));
+        assert!(matches!(SegmentType::from_u32(&value, Machine::EMX8664, true), SegmentType::PtProc(0x70000003)));
+        assert!(matches!(SegmentType::from_u32(&0x6474E551u32.to_be_bytes(), Machine::EMX8664, false), SegmentType::PtGnuStack));
+    }
+
+    #[test]
+    fn from_u32_should_keep_undefined_values() {
+        let segment_type = SegmentType::from_u32(&0x12345u32.to_le_bytes(), Machine::EMX8664, true);
+        assert!(matches!(segment_type, SegmentType::PtUnknown(0x12345)));
+        assert_eq!(format!("{}", segment_type), "<unknown>: 0x12345");
+    }
+}
@@ -0,0 +1,55 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolBinding {
+    StbLocal,
+    StbGlobal,
+    StbWeak,
+    StbGnuUnique,
+    StbOs(u8),
+    StbProc(u8),
+    StbUnknown(u8),
+}
+
+const STB_GNU_UNIQUE: u8 = 10; // Same value as STB_LOOS
+const STB_HIOS: u8 = 12;
+const STB_LOPROC: u8 = 13;
+const STB_HIPROC: u8 = 15;
+
+impl SymbolBinding {
+    pub fn from_u8(value: u8) -> SymbolBinding {
+        match value {
+            0 => SymbolBinding::StbLocal,
+            1 => SymbolBinding::StbGlobal,
+            2 => SymbolBinding::StbWeak,
+            STB_GNU_UNIQUE => SymbolBinding::StbGnuUnique,
+            11..=STB_HIOS => SymbolBinding::StbOs(value),
+            STB_LOPROC..=STB_HIPROC => SymbolBinding::StbProc(value),
+            _ => SymbolBinding::StbUnknown(value),
+        }
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for SymbolBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            SymbolBinding::StbLocal     => String::from("LOCAL"),
+            SymbolBinding::StbGlobal    => String::from("GLOBAL"),
+            SymbolBinding::StbWeak      => String::from("WEAK"),
+            SymbolBinding::StbGnuUnique => String::from("UNIQUE"),
+            SymbolBinding::StbOs(num)   => format!("OS({})", num),
+            SymbolBinding::StbProc(num) => format!("PROC({})", num),
+            SymbolBinding::StbUnknown(num) => format!("<unknown>: {}", num),
+        };
+
+        f.pad(&description)
+    }
+}
+
+// 0	STB_LOCAL	Local symbol, not visible outside the object file
+// 1	STB_GLOBAL	Global symbol, visible to all object files
+// 2	STB_WEAK	Global symbol with lower precedence
+// 10	STB_LOOS	Start OS-specific (STB_GNU_UNIQUE)
+// 12	STB_HIOS	End OS-specific
+// 13	STB_LOPROC	Start processor-specific
+// 15	STB_HIPROC	End processor-specific
@@ -0,0 +1,415 @@
+use crate::bits::*;
+use crate::elffile::Source;
+use crate::structs::sectionheader::{SectionHeader, SectionHeaderEntry, string_until_null};
+use crate::structs::sectionheadertype::SectionHeaderType;
+use crate::structs::word::Word;
+use crate::termcolors;
+use crate::types;
+
+use super::symbolbinding::SymbolBinding;
+use super::symboltype::SymbolType;
+use super::symbolvisibility::SymbolVisibility;
+use super::versions::{SymbolVersion, VersionDefinitionSection, VersionNeedSection, VersionSymbolSection};
+
+pub const SHN_UNDEF: types::Elf32Half = 0x0000;
+pub const SHN_LORESERVE: types::Elf32Half = 0xFF00;
+pub const SHN_ABS: types::Elf32Half = 0xFFF1;
+pub const SHN_COMMON: types::Elf32Half = 0xFFF2;
+pub const SHN_XINDEX: types::Elf32Half = 0xFFFF;
+
+const SYMBOL32_SIZE: usize = 0x10;
+const SYMBOL64_SIZE: usize = 0x18;
+
+#[derive(Debug)]
+pub struct SymbolEntry {
+    st_name_str: String,
+    st_value: Word,
+    st_size: Word,
+    st_bind: SymbolBinding,
+    st_type: SymbolType,
+    st_visibility: SymbolVisibility,
+    st_shndx: types::Elf32Half,
+    st_shndx_ext: Option<types::Elf32Word>,
+    st_version: Option<SymbolVersion>,
+}
+
+impl SymbolEntry {
+    pub fn print(&self) {
+        let value_width = match self.st_value {
+            Word::Bits32(_) => 8,
+            Word::Bits64(_) => 16,
+        };
+
+        println!("{}{:0value_width$x} {}{:5} {}{:7} {}{:6} {}{:8} {}{:>4} {}{}",
+            termcolors::green(),
+            self.st_value.to_u64().unwrap(),
+            termcolors::red(),
+            self.st_size.to_u64().unwrap(),
+            termcolors::yellow(),
+            self.st_type,
+            termcolors::cyan(),
+            self.st_bind,
+            termcolors::gray(),
+            self.st_visibility,
+            termcolors::purple(),
+            match self.st_shndx_ext {
+                Some(index) => index.to_string(),
+                None => section_index_to_string(self.st_shndx),
+            },
+            termcolors::white(),
+            self.versioned_name());
+    }
+
+    pub fn name(&self) -> &String {
+        &self.st_name_str
+    }
+
+    // Follows the binutils convention: name@@VERSION for the default definition, name@VERSION otherwise
+    pub fn versioned_name(&self) -> String {
+        match &self.st_version {
+            Some(version) if version.needed || version.hidden => format!("{}@{}", self.st_name_str, version.name),
+            Some(version) => format!("{}@@{}", self.st_name_str, version.name),
+            None => self.st_name_str.clone(),
+        }
+    }
+
+    pub fn value(&self) -> Word {
+        self.st_value
+    }
+
+    pub fn size(&self) -> Word {
+        self.st_size
+    }
+
+    pub fn symbol_type(&self) -> &SymbolType {
+        &self.st_type
+    }
+
+    pub fn section_index(&self) -> types::Elf32Half {
+        self.st_shndx
+    }
+
+    // Section the symbol is defined in, following SHN_XINDEX to the extended index. None for undefined,
+    // absolute and common symbols
+    pub fn defined_section(&self) -> Option<usize> {
+        match self.st_shndx {
+            SHN_UNDEF => None,
+            SHN_XINDEX => self.st_shndx_ext.map(|index| index as usize),
+            index if index >= SHN_LORESERVE => None,
+            index => Some(index as usize),
+        }
+    }
+
+    pub fn version(&self) -> Option<&SymbolVersion> {
+        self.st_version.as_ref()
+    }
+}
+
+#[derive(Debug)]
+pub struct SymbolTable {
+    name: String,
+    entries: Vec<SymbolEntry>,
+}
+
+impl SymbolTable {
+    // Only the table and its string table are read from source, so it works the same on mapped and streamed files
+    pub fn build(source: Source, section: &SectionHeaderEntry, string_table: &SectionHeaderEntry, is_32bit: bool, is_little_endian: bool) -> Result<SymbolTable, &'static str> {
+        if !matches!(section.section_type(), SectionHeaderType::ShtSymtab | SectionHeaderType::ShtDynsym) {
+            return Err("Section is not a symbol table");
+        }
+
+        let table_size = section.size().to_u64()? as usize;
+        let payload = source.read_at(section.offset().to_u64()?, table_size as u64).map_err(|_| "Symbol table too short")?;
+        let strings = source.read_at(string_table.offset().to_u64()?, string_table.size().to_u64()?).map_err(|_| "Symbol string table too short")?;
+
+        let symbol_size = if is_32bit { SYMBOL32_SIZE } else { SYMBOL64_SIZE };
+        let entry_size = match section.entry_size().to_u64()? as usize {
+            0 => symbol_size,
+            size if size < symbol_size => return Err("Symbol table entries are too small"),
+            size => size,
+        };
+
+        let mut entries: Vec<SymbolEntry> = Vec::new();
+
+        for i in 0..table_size / entry_size {
+            let entry_offset = i * entry_size;
+            let st_name = to_u32_from_slice(&payload[entry_offset..entry_offset+4].try_into().unwrap(), is_little_endian);
+            let st_value;
+            let st_size;
+            let st_info;
+            let st_other;
+            let st_shndx;
+
+            if is_32bit {
+                st_value = Word::Bits32(to_u32_from_slice(&payload[entry_offset+0x04..entry_offset+0x08].try_into().unwrap(), is_little_endian));
+                st_size = Word::Bits32(to_u32_from_slice(&payload[entry_offset+0x08..entry_offset+0x0C].try_into().unwrap(), is_little_endian));
+                st_info = payload[entry_offset+0x0C];
+                st_other = payload[entry_offset+0x0D];
+                st_shndx = to_u16_from_slice(&payload[entry_offset+0x0E..entry_offset+0x10].try_into().unwrap(), is_little_endian);
+            } else {
+                st_info = payload[entry_offset+0x04];
+                st_other = payload[entry_offset+0x05];
+                st_shndx = to_u16_from_slice(&payload[entry_offset+0x06..entry_offset+0x08].try_into().unwrap(), is_little_endian);
+                st_value = Word::Bits64(to_u64_from_slice(&payload[entry_offset+0x08..entry_offset+0x10].try_into().unwrap(), is_little_endian));
+                st_size = Word::Bits64(to_u64_from_slice(&payload[entry_offset+0x10..entry_offset+0x18].try_into().unwrap(), is_little_endian));
+            }
+
+            let st_name_str = if (st_name as usize) < strings.len() {
+                string_until_null(&strings[st_name as usize..])
+            } else {
+                String::from("<corrupt>")
+            };
+
+            entries.push(SymbolEntry{
+                st_name_str,
+                st_value,
+                st_size,
+                st_bind: SymbolBinding::from_u8(st_info >> 4),
+                st_type: SymbolType::from_u8(st_info & 0xF),
+                st_visibility: SymbolVisibility::from_u8(st_other),
+                st_shndx,
+                st_shndx_ext: None,
+                st_version: None});
+        }
+
+        Ok(SymbolTable{name: section.name().to_string(), entries})
+    }
+
+    pub fn print(&self) {
+        println!("{}Symbol table '{}' contains {} entries:{}", termcolors::purple(), self.name, self.entries.len(), termcolors::default());
+        println!("{}  Num:\t{}Value            {}Size  {}Type    {}Bind   {}Vis       {}Ndx {}Name",
+            termcolors::red(),
+            termcolors::green(),
+            termcolors::red(),
+            termcolors::yellow(),
+            termcolors::cyan(),
+            termcolors::gray(),
+            termcolors::purple(),
+            termcolors::white());
+        for (index, entry) in self.entries.iter().enumerate() {
+            print!("{}{index:5}:\t", termcolors::red());
+            entry.print();
+        }
+        print!("{}", termcolors::default());
+    }
+
+    pub fn apply_versions(&mut self, versym: &VersionSymbolSection, definitions: Option<&VersionDefinitionSection>, needs: Option<&VersionNeedSection>) {
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            entry.st_version = versym.symbol_version(index, definitions, needs);
+        }
+    }
+
+    // Symbols whose st_shndx is SHN_XINDEX keep their real section index in a parallel SHT_SYMTAB_SHNDX table
+    pub fn apply_extended_indices(&mut self, source: Source, section: &SectionHeaderEntry, is_little_endian: bool) -> Result<(), &'static str> {
+        if !matches!(section.section_type(), SectionHeaderType::ShtSymtabShndx) {
+            return Err("Section is not an extended section index table");
+        }
+
+        let payload = source.read_at(section.offset().to_u64()?, section.size().to_u64()?)
+            .map_err(|_| "Extended section index table too short")?;
+
+        for (index, entry) in self.entries.iter_mut().enumerate().filter(|(_, entry)| entry.st_shndx == SHN_XINDEX) {
+            let entry_offset = index * 4;
+            if entry_offset + 4 > payload.len() {
+                return Err("Extended section index table has too few entries");
+            }
+            entry.st_shndx_ext = Some(to_u32_from_slice(&payload[entry_offset..entry_offset+4].try_into().unwrap(), is_little_endian));
+        }
+
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[SymbolEntry] {
+        &self.entries
+    }
+
+    // Section symbols have no name of their own, readelf shows relocations against them with the section's name
+    pub fn name_section_symbols(&mut self, section_header: &SectionHeader) {
+        for entry in self.entries.iter_mut().filter(|entry| matches!(entry.st_type, SymbolType::SttSection) && entry.st_name_str.is_empty()) {
+            let index = entry.st_shndx_ext.map_or(entry.st_shndx as usize, |index| index as usize);
+            if let Some(section) = section_header.get(index) {
+                entry.st_name_str = section.name().to_string();
+            }
+        }
+    }
+
+    // Innermost defined symbol covering address, with the distance from its start. Symbol values in relocatable
+    // files are section offsets, so callers pass the section the address belongs to
+    pub fn symbol_at_address(&self, address: u64, section: Option<usize>) -> Option<(&SymbolEntry, u64)> {
+        let mut best: Option<&SymbolEntry> = None;
+        for symbol in self.entries.iter() {
+            let Some(index) = symbol.defined_section() else { continue };
+            if symbol.st_name_str.is_empty()
+                || matches!(symbol.st_type, SymbolType::SttSection | SymbolType::SttFile | SymbolType::SttTls)
+                || section.is_some_and(|section| section != index) {
+                continue;
+            }
+
+            let value = symbol.st_value.to_u64().unwrap();
+            let size = symbol.st_size.to_u64().unwrap();
+            if address != value && !(address > value && address - value < size) {
+                continue;
+            }
+            let is_better = match best {
+                None => true,
+                Some(current) => {
+                    let current_value = current.st_value.to_u64().unwrap();
+                    value > current_value || (value == current_value && size > current.st_size.to_u64().unwrap())
+                },
+            };
+            if is_better {
+                best = Some(symbol);
+            }
+        }
+        best.map(|symbol| (symbol, address - symbol.st_value.to_u64().unwrap()))
+    }
+}
+
+pub fn section_index_to_string(index: types::Elf32Half) -> String {
+    match index {
+        SHN_UNDEF => String::from("UND"),
+        SHN_ABS => String::from("ABS"),
+        SHN_COMMON => String::from("COM"),
+        SHN_XINDEX => String::from("XIDX"),
+        _ => index.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::machine::Machine;
+
+    #[test]
+    fn section_index_to_string_should_name_reserved_indices() {
+        assert_eq!(section_index_to_string(SHN_UNDEF), "UND");
+        assert_eq!(section_index_to_string(SHN_ABS), "ABS");
+        assert_eq!(section_index_to_string(SHN_COMMON), "COM");
+        assert_eq!(section_index_to_string(14), "14");
+    }
+
+    #[test]
+    fn symbol_entry_should_decorate_name_with_version() {
+        let mut entry = SymbolEntry{
+            st_name_str: String::from("puts"),
+            st_value: Word::Bits64(0),
+            st_size: Word::Bits64(0),
+            st_bind: SymbolBinding::StbGlobal,
+            st_type: SymbolType::SttFunc,
+            st_visibility: SymbolVisibility::StvDefault,
+            st_shndx: SHN_UNDEF,
+            st_shndx_ext: None,
+            st_version: None};
+        assert_eq!(entry.versioned_name(), "puts");
+
+        entry.st_version = Some(SymbolVersion{name: String::from("GLIBC_2.2.5"), hidden: false, needed: true});
+        assert_eq!(entry.versioned_name(), "puts@GLIBC_2.2.5");
+
+        entry.st_version = Some(SymbolVersion{name: String::from("MYLIB_1.0"), hidden: false, needed: false});
+        assert_eq!(entry.versioned_name(), "puts@@MYLIB_1.0");
+
+        entry.st_version = Some(SymbolVersion{name: String::from("MYLIB_1.0"), hidden: true, needed: false});
+        assert_eq!(entry.versioned_name(), "puts@MYLIB_1.0");
+    }
+
+    #[test]
+    fn symbol_at_address_should_pick_innermost_symbol() {
+        let symbol = |name: &str, value: u64, size: u64, st_type: SymbolType, st_shndx: types::Elf32Half| SymbolEntry{
+            st_name_str: String::from(name),
+            st_value: Word::Bits64(value),
+            st_size: Word::Bits64(size),
+            st_bind: SymbolBinding::StbGlobal,
+            st_type,
+            st_visibility: SymbolVisibility::StvDefault,
+            st_shndx,
+            st_shndx_ext: None,
+            st_version: None};
+        let table = SymbolTable{name: String::from(".symtab"), entries: vec![
+            symbol("", 0, 0, SymbolType::SttNotype, SHN_UNDEF),
+            symbol(".text", 0x1000, 0, SymbolType::SttSection, 14),
+            symbol("outer", 0x1000, 0x100, SymbolType::SttFunc, 14),
+            symbol("inner", 0x1040, 0x10, SymbolType::SttFunc, 14),
+            symbol("puts", 0x1050, 0, SymbolType::SttFunc, SHN_UNDEF),
+        ]};
+
+        let name = |address: u64, section: Option<usize>| table.symbol_at_address(address, section).map(|(symbol, offset)| (symbol.name().clone(), offset));
+        assert_eq!(name(0x1000, None), Some((String::from("outer"), 0)));
+        assert_eq!(name(0x1044, None), Some((String::from("inner"), 4)));
+        assert_eq!(name(0x1050, None), Some((String::from("outer"), 0x50)));
+        assert_eq!(name(0x1100, None), None);
+        assert_eq!(name(0x1044, Some(15)), None);
+    }
+
+    #[test]
+    fn build_should_reject_entries_smaller_than_a_symbol() {
+        let mut header = [0u8; 0x40];
+        header[0x04..0x08].copy_from_slice(&2u32.to_le_bytes()); // SHT_SYMTAB
+        header[0x20..0x28].copy_from_slice(&0x30u64.to_le_bytes());
+        header[0x38..0x40].copy_from_slice(&0x10u64.to_le_bytes());
+        let section = SectionHeaderEntry::build(&header, 0, Machine::EMX8664, false, true);
+        let payload = [0u8; 0x40];
+
+        assert_eq!(SymbolTable::build(Source::Memory(&payload), &section, &section, false, true).unwrap_err(), "Symbol table entries are too small");
+        assert_eq!(SymbolTable::build(Source::Memory(&payload[..0x20]), &section, &section, false, true).unwrap_err(), "Symbol table too short");
+    }
+
+    #[test]
+    fn name_section_symbols_should_use_section_names() {
+        let mut table = [0u8; 0x80];
+        table[0x40..0x44].copy_from_slice(&1u32.to_le_bytes());
+        let section_header = SectionHeader::from_table(&table, 0x40, b"\0.text.f\0", Source::Memory(&[]), Machine::EMX8664, false, true);
+        let symbol = |st_type: SymbolType, st_shndx: types::Elf32Half| SymbolEntry{
+            st_name_str: String::new(),
+            st_value: Word::Bits64(0),
+            st_size: Word::Bits64(0),
+            st_bind: SymbolBinding::StbLocal,
+            st_type,
+            st_visibility: SymbolVisibility::StvDefault,
+            st_shndx,
+            st_shndx_ext: None,
+            st_version: None};
+        let mut table = SymbolTable{name: String::from(".symtab"), entries: vec![symbol(SymbolType::SttNotype, 1), symbol(SymbolType::SttSection, 1)]};
+
+        table.name_section_symbols(&section_header);
+        assert_eq!(table.entries()[0].name(), "");
+        assert_eq!(table.entries()[1].name(), ".text.f");
+    }
+
+    #[test]
+    fn defined_section_should_follow_extended_index() {
+        let mut symbol = SymbolEntry{
+            st_name_str: String::from("f"),
+            st_value: Word::Bits64(0),
+            st_size: Word::Bits64(0),
+            st_bind: SymbolBinding::StbGlobal,
+            st_type: SymbolType::SttFunc,
+            st_visibility: SymbolVisibility::StvDefault,
+            st_shndx: 3,
+            st_shndx_ext: None,
+            st_version: None};
+        assert_eq!(symbol.defined_section(), Some(3));
+
+        symbol.st_shndx = SHN_XINDEX;
+        assert_eq!(symbol.defined_section(), None);
+        symbol.st_shndx_ext = Some(0x10000);
+        assert_eq!(symbol.defined_section(), Some(0x10000));
+
+        symbol.st_shndx = SHN_ABS;
+        assert_eq!(symbol.defined_section(), None);
+    }
+
+    #[test]
+    fn symbol_binding_and_type_should_be_decoded_from_info() {
+        let info: u8 = 0x12;
+        assert_eq!(SymbolBinding::from_u8(info >> 4), SymbolBinding::StbGlobal);
+        assert_eq!(SymbolType::from_u8(info & 0xF), SymbolType::SttFunc);
+        assert_eq!(SymbolBinding::from_u8(5), SymbolBinding::StbUnknown(5));
+        assert_eq!(SymbolType::from_u8(8).to_string(), "<unknown>: 8");
+    }
+
+    #[test]
+    fn symbol_visibility_should_use_only_lowest_bits() {
+        assert_eq!(SymbolVisibility::from_u8(0xF2), SymbolVisibility::StvHidden);
+        assert_eq!(SymbolVisibility::from_u8(0x00), SymbolVisibility::StvDefault);
+    }
+}
@@ -0,0 +1,71 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolType {
+    SttNotype,
+    SttObject,
+    SttFunc,
+    SttSection,
+    SttFile,
+    SttCommon,
+    SttTls,
+    SttGnuIfunc,
+    SttOs(u8),
+    SttProc(u8),
+    SttUnknown(u8),
+}
+
+const STT_GNU_IFUNC: u8 = 10; // Same value as STT_LOOS
+const STT_HIOS: u8 = 12;
+const STT_LOPROC: u8 = 13;
+const STT_HIPROC: u8 = 15;
+
+impl SymbolType {
+    pub fn from_u8(value: u8) -> SymbolType {
+        match value {
+            0 => SymbolType::SttNotype,
+            1 => SymbolType::SttObject,
+            2 => SymbolType::SttFunc,
+            3 => SymbolType::SttSection,
+            4 => SymbolType::SttFile,
+            5 => SymbolType::SttCommon,
+            6 => SymbolType::SttTls,
+            STT_GNU_IFUNC => SymbolType::SttGnuIfunc,
+            11..=STT_HIOS => SymbolType::SttOs(value),
+            STT_LOPROC..=STT_HIPROC => SymbolType::SttProc(value),
+            _ => SymbolType::SttUnknown(value),
+        }
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for SymbolType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            SymbolType::SttNotype     => String::from("NOTYPE"),
+            SymbolType::SttObject     => String::from("OBJECT"),
+            SymbolType::SttFunc       => String::from("FUNC"),
+            SymbolType::SttSection    => String::from("SECTION"),
+            SymbolType::SttFile       => String::from("FILE"),
+            SymbolType::SttCommon     => String::from("COMMON"),
+            SymbolType::SttTls        => String::from("TLS"),
+            SymbolType::SttGnuIfunc   => String::from("IFUNC"),
+            SymbolType::SttOs(num)    => format!("OS({})", num),
+            SymbolType::SttProc(num)  => format!("PROC({})", num),
+            SymbolType::SttUnknown(num) => format!("<unknown>: {}", num),
+        };
+
+        f.pad(&description)
+    }
+}
+
+// 0	STT_NOTYPE	Symbol type is unspecified
+// 1	STT_OBJECT	Symbol is a data object
+// 2	STT_FUNC	Symbol is a code object
+// 3	STT_SECTION	Symbol associated with a section
+// 4	STT_FILE	Symbol's name is file name
+// 5	STT_COMMON	Symbol is a common data object
+// 6	STT_TLS	Symbol is thread-local data object
+// 10	STT_LOOS	Start OS-specific (STT_GNU_IFUNC)
+// 12	STT_HIOS	End OS-specific
+// 13	STT_LOPROC	Start processor-specific
+// 15	STT_HIPROC	End processor-specific
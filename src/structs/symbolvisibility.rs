@@ -0,0 +1,32 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolVisibility {
+    StvDefault,
+    StvInternal,
+    StvHidden,
+    StvProtected,
+}
+
+impl SymbolVisibility {
+    pub fn from_u8(value: u8) -> SymbolVisibility {
+        // Only the two lowest bits of st_other carry the visibility
+        match value & 0x3 {
+            0 => SymbolVisibility::StvDefault,
+            1 => SymbolVisibility::StvInternal,
+            2 => SymbolVisibility::StvHidden,
+            _ => SymbolVisibility::StvProtected,
+        }
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for SymbolVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            SymbolVisibility::StvDefault   => "DEFAULT",
+            SymbolVisibility::StvInternal  => "INTERNAL",
+            SymbolVisibility::StvHidden    => "HIDDEN",
+            SymbolVisibility::StvProtected => "PROTECTED",
+        })
+    }
+}
@@ -0,0 +1,352 @@
+use crate::bits::*;
+use crate::elffile::Source;
+use crate::structs::sectionheader::{SectionHeaderEntry, string_until_null};
+use crate::structs::sectionheadertype::SectionHeaderType;
+use crate::termcolors;
+
+use std::borrow::Cow;
+
+pub const VER_NDX_LOCAL: u16 = 0;
+pub const VER_NDX_GLOBAL: u16 = 1;
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+pub const VERSYM_VERSION: u16 = 0x7FFF;
+
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+pub const VER_FLG_INFO: u16 = 0x4;
+
+#[derive(Debug)]
+pub struct VersionDefinition {
+    vd_version: u16,
+    vd_flags: u16,
+    vd_ndx: u16,
+    vd_names: Vec<String>,
+}
+
+impl VersionDefinition {
+    pub fn print(&self) {
+        println!("  {}Rev: {}  Flags: {}  Index: {}  Cnt: {}  {}Name: {}",
+            termcolors::gray(),
+            self.vd_version,
+            version_flags_to_string(self.vd_flags),
+            self.vd_ndx,
+            self.vd_names.len(),
+            termcolors::white(),
+            self.vd_names.first().map(String::as_str).unwrap_or(""));
+
+        for (index, parent) in self.vd_names.iter().enumerate().skip(1) {
+            println!("  {}  Parent {}: {}{}", termcolors::gray(), index, termcolors::white(), parent);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct VersionDefinitionSection {
+    name: String,
+    entries: Vec<VersionDefinition>,
+}
+
+impl VersionDefinitionSection {
+    pub fn build(source: Source, section: &SectionHeaderEntry, string_table: &SectionHeaderEntry, is_little_endian: bool) -> Result<VersionDefinitionSection, &'static str> {
+        if !matches!(section.section_type(), SectionHeaderType::ShtGnuVerdef) {
+            return Err("Section is not a version definition section");
+        }
+
+        let payload = section_data(source, section)?;
+        let strings = section_data(source, string_table)?;
+        let end = payload.len();
+        let half = |offset: usize| to_u16_from_slice(&payload[offset..offset+2].try_into().unwrap(), is_little_endian);
+        let word = |offset: usize| to_u32_from_slice(&payload[offset..offset+4].try_into().unwrap(), is_little_endian) as usize;
+
+        let mut entries: Vec<VersionDefinition> = Vec::new();
+        let mut definition_offset = 0;
+
+        // Elf_Verdef is 20 bytes and each Elf_Verdaux is 8 bytes
+        while definition_offset + 20 <= end {
+            let vd_cnt = half(definition_offset + 6);
+            let vd_aux = word(definition_offset + 12);
+            let vd_next = word(definition_offset + 16);
+
+            let mut vd_names: Vec<String> = Vec::new();
+            let mut aux_offset = definition_offset + vd_aux;
+            for _ in 0..vd_cnt {
+                if aux_offset + 8 > end {
+                    return Err("Version definition auxiliary entry exceeds its section");
+                }
+                vd_names.push(read_string(&strings, word(aux_offset)));
+                aux_offset += word(aux_offset + 4);
+            }
+
+            entries.push(VersionDefinition{
+                vd_version: half(definition_offset),
+                vd_flags: half(definition_offset + 2),
+                vd_ndx: half(definition_offset + 4),
+                vd_names});
+
+            if vd_next == 0 {
+                break;
+            }
+            definition_offset += vd_next;
+        }
+
+        Ok(VersionDefinitionSection{name: section.name().to_string(), entries})
+    }
+
+    pub fn print(&self) {
+        println!("{}Version definition section '{}' contains {} entries:{}", termcolors::purple(), self.name, self.entries.len(), termcolors::default());
+        for entry in self.entries.iter() {
+            entry.print();
+        }
+        print!("{}", termcolors::default());
+    }
+
+    fn version_name(&self, index: u16) -> Option<&String> {
+        self.entries.iter()
+            .find(|entry| entry.vd_ndx == index)
+            .and_then(|entry| entry.vd_names.first())
+    }
+}
+
+#[derive(Debug)]
+pub struct VersionNeedAux {
+    vna_flags: u16,
+    vna_other: u16,
+    vna_name_str: String,
+}
+
+#[derive(Debug)]
+pub struct VersionNeed {
+    vn_version: u16,
+    vn_file_str: String,
+    entries: Vec<VersionNeedAux>,
+}
+
+impl VersionNeed {
+    pub fn print(&self) {
+        println!("  {}Version: {}  {}File: {}  {}Cnt: {}",
+            termcolors::gray(),
+            self.vn_version,
+            termcolors::green(),
+            self.vn_file_str,
+            termcolors::gray(),
+            self.entries.len());
+
+        for entry in self.entries.iter() {
+            println!("    {}Name: {}  {}Flags: {}  Version: {}",
+                termcolors::white(),
+                entry.vna_name_str,
+                termcolors::gray(),
+                version_flags_to_string(entry.vna_flags),
+                entry.vna_other);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct VersionNeedSection {
+    name: String,
+    entries: Vec<VersionNeed>,
+}
+
+impl VersionNeedSection {
+    pub fn build(source: Source, section: &SectionHeaderEntry, string_table: &SectionHeaderEntry, is_little_endian: bool) -> Result<VersionNeedSection, &'static str> {
+        if !matches!(section.section_type(), SectionHeaderType::ShtGnuVerneed) {
+            return Err("Section is not a version needs section");
+        }
+
+        let payload = section_data(source, section)?;
+        let strings = section_data(source, string_table)?;
+        let end = payload.len();
+        let half = |offset: usize| to_u16_from_slice(&payload[offset..offset+2].try_into().unwrap(), is_little_endian);
+        let word = |offset: usize| to_u32_from_slice(&payload[offset..offset+4].try_into().unwrap(), is_little_endian) as usize;
+
+        let mut entries: Vec<VersionNeed> = Vec::new();
+        let mut need_offset = 0;
+
+        // Elf_Verneed and Elf_Vernaux are both 16 bytes
+        while need_offset + 16 <= end {
+            let vn_cnt = half(need_offset + 2);
+            let vn_aux = word(need_offset + 8);
+            let vn_next = word(need_offset + 12);
+
+            let mut auxiliaries: Vec<VersionNeedAux> = Vec::new();
+            let mut aux_offset = need_offset + vn_aux;
+            for _ in 0..vn_cnt {
+                if aux_offset + 16 > end {
+                    return Err("Version needs auxiliary entry exceeds its section");
+                }
+                auxiliaries.push(VersionNeedAux{
+                    vna_flags: half(aux_offset + 4),
+                    vna_other: half(aux_offset + 6),
+                    vna_name_str: read_string(&strings, word(aux_offset + 8))});
+                aux_offset += word(aux_offset + 12);
+            }
+
+            entries.push(VersionNeed{
+                vn_version: half(need_offset),
+                vn_file_str: read_string(&strings, word(need_offset + 4)),
+                entries: auxiliaries});
+
+            if vn_next == 0 {
+                break;
+            }
+            need_offset += vn_next;
+        }
+
+        Ok(VersionNeedSection{name: section.name().to_string(), entries})
+    }
+
+    pub fn print(&self) {
+        println!("{}Version needs section '{}' contains {} entries:{}", termcolors::purple(), self.name, self.entries.len(), termcolors::default());
+        for entry in self.entries.iter() {
+            entry.print();
+        }
+        print!("{}", termcolors::default());
+    }
+
+    fn version_name(&self, index: u16) -> Option<&String> {
+        self.entries.iter()
+            .flat_map(|entry| entry.entries.iter())
+            .find(|aux| aux.vna_other == index)
+            .map(|aux| &aux.vna_name_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolVersion {
+    pub name: String,
+    pub hidden: bool,
+    pub needed: bool,
+}
+
+#[derive(Debug)]
+pub struct VersionSymbolSection {
+    name: String,
+    entries: Vec<u16>,
+}
+
+impl VersionSymbolSection {
+    pub fn build(source: Source, section: &SectionHeaderEntry, is_little_endian: bool) -> Result<VersionSymbolSection, &'static str> {
+        if !matches!(section.section_type(), SectionHeaderType::ShtGnuVersym) {
+            return Err("Section is not a version symbol table");
+        }
+
+        let entries = section_data(source, section)?.chunks_exact(2)
+            .map(|chunk| to_u16_from_slice(chunk.try_into().unwrap(), is_little_endian))
+            .collect();
+
+        Ok(VersionSymbolSection{name: section.name().to_string(), entries})
+    }
+
+    pub fn print(&self, definitions: Option<&VersionDefinitionSection>, needs: Option<&VersionNeedSection>) {
+        println!("{}Version symbols section '{}' contains {} entries:{}", termcolors::purple(), self.name, self.entries.len(), termcolors::default());
+        for (row, chunk) in self.entries.chunks(4).enumerate() {
+            print!("  {}{:03x}:", termcolors::red(), row * 4);
+            for value in chunk {
+                let name = match value & VERSYM_VERSION {
+                    VER_NDX_LOCAL => String::from("*local*"),
+                    VER_NDX_GLOBAL => String::from("*global*"),
+                    _ => self.version(value & VERSYM_VERSION, definitions, needs).map(|version| version.name).unwrap_or(String::from("???")),
+                };
+                let hidden = if value & VERSYM_HIDDEN != 0 { "h" } else { " " };
+                print!(" {}{:3x}{}{}{:15}", termcolors::gray(), value & VERSYM_VERSION, hidden, termcolors::white(), format!("({})", name));
+            }
+            println!();
+        }
+        print!("{}", termcolors::default());
+    }
+
+    pub fn symbol_version(&self, symbol_index: usize, definitions: Option<&VersionDefinitionSection>, needs: Option<&VersionNeedSection>) -> Option<SymbolVersion> {
+        let value = *self.entries.get(symbol_index)?;
+        let mut version = self.version(value & VERSYM_VERSION, definitions, needs)?;
+        version.hidden = value & VERSYM_HIDDEN != 0;
+        Some(version)
+    }
+
+    fn version(&self, index: u16, definitions: Option<&VersionDefinitionSection>, needs: Option<&VersionNeedSection>) -> Option<SymbolVersion> {
+        if index == VER_NDX_LOCAL || index == VER_NDX_GLOBAL {
+            return None;
+        }
+
+        if let Some(name) = needs.and_then(|needs| needs.version_name(index)) {
+            return Some(SymbolVersion{name: name.clone(), hidden: false, needed: true});
+        }
+
+        definitions
+            .and_then(|definitions| definitions.version_name(index))
+            .map(|name| SymbolVersion{name: name.clone(), hidden: false, needed: false})
+    }
+}
+
+fn section_data<'data>(source: Source<'data>, section: &SectionHeaderEntry) -> Result<Cow<'data, [u8]>, &'static str> {
+    source.read_at(section.offset().to_u64()?, section.size().to_u64()?).map_err(|_| "Versioning section too short")
+}
+
+fn read_string(strings: &[u8], offset: usize) -> String {
+    if offset < strings.len() {
+        string_until_null(&strings[offset..])
+    } else {
+        String::from("<corrupt>")
+    }
+}
+
+fn version_flags_to_string(flags: u16) -> String {
+    if flags == 0 {
+        return String::from("none");
+    }
+
+    let mut result: Vec<&str> = Vec::new();
+
+    if flags & VER_FLG_BASE != 0 {
+        result.push("BASE");
+    }
+
+    if flags & VER_FLG_WEAK != 0 {
+        result.push("WEAK");
+    }
+
+    if flags & VER_FLG_INFO != 0 {
+        result.push("INFO");
+    }
+
+    let unknown = flags & !(VER_FLG_BASE | VER_FLG_WEAK | VER_FLG_INFO);
+    let unknown_str = format!("<unknown: {:x}>", unknown);
+    if unknown != 0 {
+        result.push(&unknown_str);
+    }
+
+    result.join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_flags_to_string_should_list_flags() {
+        assert_eq!(version_flags_to_string(0), "none");
+        assert_eq!(version_flags_to_string(VER_FLG_BASE), "BASE");
+        assert_eq!(version_flags_to_string(VER_FLG_BASE | VER_FLG_WEAK | 0x10), "BASE | WEAK | <unknown: 10>");
+    }
+
+    #[test]
+    fn symbol_version_should_prefer_needed_versions_and_mark_hidden() {
+        let needs = VersionNeedSection{name: String::from(".gnu.version_r"), entries: vec![VersionNeed{
+            vn_version: 1,
+            vn_file_str: String::from("libc.so.6"),
+            entries: vec![VersionNeedAux{vna_flags: 0, vna_other: 2, vna_name_str: String::from("GLIBC_2.34")}]}]};
+        let definitions = VersionDefinitionSection{name: String::from(".gnu.version_d"), entries: vec![VersionDefinition{
+            vd_version: 1,
+            vd_flags: 0,
+            vd_ndx: 3,
+            vd_names: vec![String::from("MYLIB_1.0")]}]};
+        let versym = VersionSymbolSection{name: String::from(".gnu.version"), entries: vec![0, 2, 1, 0x8003]};
+
+        assert_eq!(versym.symbol_version(0, Some(&definitions), Some(&needs)), None);
+        assert_eq!(versym.symbol_version(1, Some(&definitions), Some(&needs)),
+            Some(SymbolVersion{name: String::from("GLIBC_2.34"), hidden: false, needed: true}));
+        assert_eq!(versym.symbol_version(2, Some(&definitions), Some(&needs)), None);
+        assert_eq!(versym.symbol_version(3, Some(&definitions), Some(&needs)),
+            Some(SymbolVersion{name: String::from("MYLIB_1.0"), hidden: true, needed: false}));
+    }
+}
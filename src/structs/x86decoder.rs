@@ -0,0 +1,1498 @@
+// x86-64 instruction decoder producing Intel syntax in the style of objdump -M intel
+
+const REGISTERS_64: [&str; 16] = ["rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi",
+    "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15"];
+const REGISTERS_32: [&str; 16] = ["eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi",
+    "r8d", "r9d", "r10d", "r11d", "r12d", "r13d", "r14d", "r15d"];
+const REGISTERS_16: [&str; 16] = ["ax", "cx", "dx", "bx", "sp", "bp", "si", "di",
+    "r8w", "r9w", "r10w", "r11w", "r12w", "r13w", "r14w", "r15w"];
+const REGISTERS_8: [&str; 16] = ["al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil",
+    "r8b", "r9b", "r10b", "r11b", "r12b", "r13b", "r14b", "r15b"];
+const REGISTERS_8_LEGACY: [&str; 8] = ["al", "cl", "dl", "bl", "ah", "ch", "dh", "bh"];
+const SEGMENT_REGISTERS: [&str; 8] = ["es", "cs", "ss", "ds", "fs", "gs", "?", "?"];
+
+const CONDITIONS: [&str; 16] = ["o", "no", "b", "ae", "e", "ne", "be", "a", "s", "ns", "p", "np", "l", "ge", "le", "g"];
+const ARITHMETIC: [&str; 8] = ["add", "or", "adc", "sbb", "and", "sub", "xor", "cmp"];
+const SHIFTS: [&str; 8] = ["rol", "ror", "rcl", "rcr", "shl", "shr", "shl", "sar"];
+const COMPARE_PREDICATES: [&str; 32] = ["eq", "lt", "le", "unord", "neq", "nlt", "nle", "ord",
+    "eq_uq", "nge", "ngt", "false", "neq_oq", "ge", "gt", "true",
+    "eq_os", "lt_oq", "le_oq", "unord_s", "neq_us", "nlt_uq", "nle_uq", "ord_s",
+    "eq_us", "nge_uq", "ngt_uq", "false_os", "neq_os", "ge_oq", "gt_oq", "true_us"];
+
+const REX_W: u8 = 0x8;
+const REX_R: u8 = 0x4;
+const REX_X: u8 = 0x2;
+const REX_B: u8 = 0x1;
+
+const USED_OPERAND_SIZE: u8 = 0x1;
+const USED_ADDRESS_SIZE: u8 = 0x2;
+const USED_SEGMENT: u8 = 0x4;
+const USED_REPEAT: u8 = 0x8;
+
+// Longest legal x86 instruction
+const MAX_INSTRUCTION_LENGTH: usize = 15;
+
+#[derive(Debug, PartialEq)]
+pub struct Instruction {
+    pub address: u64,
+    pub length: usize,
+    pub mnemonic: String,
+    pub operands: String,
+    // Destination of a direct jump or call
+    pub target: Option<u64>,
+    // Address of a rip-relative memory operand
+    pub reference: Option<u64>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mandatory {
+    None,
+    OperandSize,
+    Rep,
+    Repne,
+}
+
+#[derive(Clone, Copy)]
+struct Vex {
+    long: bool,
+    vvvv: u8,
+    pp: Mandatory,
+}
+
+#[derive(Clone)]
+struct ModRm {
+    mode: u8,
+    reg: u8,
+    rm: u8,
+    memory: String,
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    address: u64,
+    prefixes: Vec<u8>,
+    rex: u8,
+    has_rex: bool,
+    vex: Option<Vex>,
+    mmx: bool,
+    mandatory_66: bool,
+    used: u8,
+    modrm: Option<ModRm>,
+    rip_displacement: Option<i64>,
+    target: Option<u64>,
+    string_word: Option<&'static str>,
+    is_branch: bool,
+    is_indirect_branch: bool,
+}
+
+// Decodes a single instruction; undecodable bytes come back as a one byte "(bad)"
+pub fn decode(bytes: &[u8], address: u64) -> Instruction {
+    let bytes = &bytes[..bytes.len().min(MAX_INSTRUCTION_LENGTH)];
+    let mut decoder = Decoder {
+        bytes,
+        position: 0,
+        address,
+        prefixes: Vec::new(),
+        rex: 0,
+        has_rex: false,
+        vex: None,
+        mmx: false,
+        mandatory_66: false,
+        used: 0,
+        modrm: None,
+        rip_displacement: None,
+        target: None,
+        string_word: None,
+        is_branch: false,
+        is_indirect_branch: false,
+    };
+
+    match decoder.decode() {
+        Some((mnemonic, operands)) => decoder.finish(mnemonic, operands),
+        None => Instruction {
+            address,
+            length: 1,
+            mnemonic: String::from("(bad)"),
+            operands: String::new(),
+            target: None,
+            reference: None,
+        },
+    }
+}
+
+impl Decoder<'_> {
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.bytes.get(self.position)?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn next_signed(&mut self, size: usize) -> Option<i64> {
+        let bytes = self.bytes.get(self.position..self.position + size)?;
+        self.position += size;
+        let mut value = 0u64;
+        for (index, byte) in bytes.iter().enumerate() {
+            value |= (*byte as u64) << (8 * index);
+        }
+        let shift = 64 - 8 * size as u32;
+        Some(((value << shift) as i64) >> shift)
+    }
+
+    fn has_prefix(&self, prefix: u8) -> bool {
+        self.prefixes.contains(&prefix)
+    }
+
+    fn rex_w(&self) -> bool {
+        self.rex & REX_W != 0
+    }
+
+    fn operand_size(&mut self) -> u16 {
+        if self.rex_w() {
+            64
+        } else if self.has_prefix(0x66) && !self.mandatory_66 {
+            self.used |= USED_OPERAND_SIZE;
+            16
+        } else {
+            32
+        }
+    }
+
+    // Stack operations and near branches default to 64 bits
+    fn stack_size(&mut self) -> u16 {
+        if self.has_prefix(0x66) && !self.mandatory_66 && !self.rex_w() {
+            self.used |= USED_OPERAND_SIZE;
+            16
+        } else {
+            64
+        }
+    }
+
+    fn mandatory_prefix(&self) -> Mandatory {
+        if let Some(vex) = self.vex {
+            return vex.pp;
+        }
+        match self.prefixes.iter().rev().find(|prefix| matches!(prefix, 0xF2 | 0xF3)) {
+            Some(0xF3) => Mandatory::Rep,
+            Some(_) => Mandatory::Repne,
+            None if self.has_prefix(0x66) => Mandatory::OperandSize,
+            None => Mandatory::None,
+        }
+    }
+
+    fn decode(&mut self) -> Option<(String, Vec<String>)> {
+        let mut opcode = self.next()?;
+        while matches!(opcode, 0x66 | 0x67 | 0xF0 | 0xF2 | 0xF3 | 0x2E | 0x36 | 0x3E | 0x26 | 0x64 | 0x65) {
+            self.prefixes.push(opcode);
+            opcode = self.next()?;
+        }
+
+        if (0x40..=0x4F).contains(&opcode) {
+            self.rex = opcode & 0xF;
+            self.has_rex = true;
+            opcode = self.next()?;
+        }
+
+        match opcode {
+            0xC4 | 0xC5 => {
+                if self.has_rex || self.prefixes.iter().any(|prefix| matches!(prefix, 0x66 | 0xF2 | 0xF3 | 0xF0)) {
+                    return None;
+                }
+                let first = self.next()?;
+                let (map, second) = if opcode == 0xC5 {
+                    self.rex = if first & 0x80 == 0 { REX_R } else { 0 };
+                    (1, first)
+                } else {
+                    self.rex = (!first >> 5) & 0x7;
+                    let second = self.next()?;
+                    self.rex |= if second & 0x80 != 0 { REX_W } else { 0 };
+                    (first & 0x1F, second)
+                };
+                self.vex = Some(Vex {
+                    long: second & 0x4 != 0,
+                    vvvv: (!second >> 3) & 0xF,
+                    pp: [Mandatory::None, Mandatory::OperandSize, Mandatory::Rep, Mandatory::Repne][(second & 0x3) as usize],
+                });
+                let opcode = self.next()?;
+                match map {
+                    1 => self.two_byte(opcode),
+                    2 => self.three_byte(opcode, false),
+                    3 => self.three_byte(opcode, true),
+                    _ => None,
+                }
+            },
+            0x0F => match self.next()? {
+                0x38 => {
+                    let opcode = self.next()?;
+                    self.three_byte(opcode, false)
+                },
+                0x3A => {
+                    let opcode = self.next()?;
+                    self.three_byte(opcode, true)
+                },
+                opcode => self.two_byte(opcode),
+            },
+            _ => self.one_byte(opcode),
+        }
+    }
+
+    fn modrm(&mut self) -> Option<ModRm> {
+        if let Some(modrm) = &self.modrm {
+            return Some(modrm.clone());
+        }
+
+        let byte = self.next()?;
+        let mode = byte >> 6;
+        let reg = ((byte >> 3) & 0x7) | if self.rex & REX_R != 0 { 0x8 } else { 0 };
+        let mut rm = byte & 0x7;
+
+        let memory = if mode == 3 {
+            rm |= if self.rex & REX_B != 0 { 0x8 } else { 0 };
+            String::new()
+        } else {
+            self.memory_operand(mode, rm)?
+        };
+
+        let modrm = ModRm { mode, reg, rm, memory };
+        self.modrm = Some(modrm.clone());
+        Some(modrm)
+    }
+
+    fn memory_operand(&mut self, mode: u8, rm: u8) -> Option<String> {
+        let registers = if self.has_prefix(0x67) {
+            self.used |= USED_ADDRESS_SIZE;
+            &REGISTERS_32
+        } else {
+            &REGISTERS_64
+        };
+        let rex = self.rex;
+        let extend = |bit: u8| if rex & bit != 0 { 0x8 } else { 0 };
+
+        let mut base = None;
+        let mut index = None;
+        let mut is_rip = false;
+        let mut has_displacement = mode != 0;
+
+        if rm == 4 {
+            let sib = self.next()?;
+            let index_number = ((sib >> 3) & 0x7) | extend(REX_X);
+            if index_number != 4 {
+                index = Some((registers[index_number as usize], 1 << (sib >> 6)));
+            }
+            if sib & 0x7 == 5 && mode == 0 {
+                has_displacement = true;
+            } else {
+                base = Some(registers[((sib & 0x7) | extend(REX_B)) as usize]);
+            }
+        } else if rm == 5 && mode == 0 {
+            is_rip = true;
+            has_displacement = true;
+        } else {
+            base = Some(registers[(rm | extend(REX_B)) as usize]);
+        }
+
+        let displacement = match mode {
+            1 => self.next_signed(1)?,
+            0 if !has_displacement => 0,
+            _ => self.next_signed(4)?,
+        };
+
+        let segment = self.prefixes.iter().rev().find(|prefix| matches!(prefix, 0x64 | 0x65)).map(|prefix| {
+            if *prefix == 0x64 { "fs" } else { "gs" }
+        });
+        if segment.is_some() {
+            self.used |= USED_SEGMENT;
+        }
+
+        if is_rip {
+            // objdump shows negative rip displacements as their 64-bit two's complement
+            self.rip_displacement = Some(displacement);
+            let rip = if self.has_prefix(0x67) { "eip" } else { "rip" };
+            return Some(format!("{}[{}+0x{:x}]", segment.map(|s| format!("{}:", s)).unwrap_or_default(), rip, displacement as u64));
+        }
+
+        if base.is_none() && index.is_none() {
+            return Some(format!("{}:0x{:x}", segment.unwrap_or("ds"), displacement as u64));
+        }
+
+        let mut text = segment.map(|s| format!("{}:[", s)).unwrap_or(String::from("["));
+        if let Some(base) = base {
+            text += base;
+        }
+        if let Some((index, scale)) = index {
+            if base.is_some() {
+                text += "+";
+            }
+            text += &format!("{}*{}", index, scale);
+        }
+        if has_displacement {
+            let sign = if displacement < 0 { '-' } else { '+' };
+            text += &format!("{}0x{:x}", sign, displacement.unsigned_abs());
+        }
+        Some(text + "]")
+    }
+
+    fn one_byte(&mut self, opcode: u8) -> Option<(String, Vec<String>)> {
+        let spec: String = match opcode {
+            0x00..=0x3F if opcode & 0x7 < 6 => {
+                let form = ["Eb,Gb", "Ev,Gv", "Gb,Eb", "Gv,Ev", "AL,Ib", "rAX,Iz"][(opcode & 0x7) as usize];
+                format!("{} {}", ARITHMETIC[(opcode >> 3) as usize], form)
+            },
+            0x50..=0x57 => String::from("push Zq"),
+            0x58..=0x5F => String::from("pop Zq"),
+            0x63 => String::from("movsxd Gv,Ed"),
+            0x68 => String::from("push Izq"),
+            0x69 => String::from("imul Gv,Ev,Iz"),
+            0x6A => String::from("push Ibq"),
+            0x6B => String::from("imul Gv,Ev,Ibs"),
+            0x70..=0x7F => format!("j{} Jb", CONDITIONS[(opcode & 0xF) as usize]),
+            0x80 => format!("{} Eb,Ib", ARITHMETIC[(self.modrm()?.reg & 0x7) as usize]),
+            0x81 => format!("{} Ev,Iz", ARITHMETIC[(self.modrm()?.reg & 0x7) as usize]),
+            0x83 => format!("{} Ev,Ibs", ARITHMETIC[(self.modrm()?.reg & 0x7) as usize]),
+            0x84 => String::from("test Eb,Gb"),
+            0x85 => String::from("test Ev,Gv"),
+            0x86 => String::from("xchg Eb,Gb"),
+            0x87 => String::from("xchg Ev,Gv"),
+            0x88 => String::from("mov Eb,Gb"),
+            0x89 => String::from("mov Ev,Gv"),
+            0x8A => String::from("mov Gb,Eb"),
+            0x8B => String::from("mov Gv,Ev"),
+            0x8C => String::from("mov Ev,Sw"),
+            0x8D => String::from("lea Gv,M"),
+            0x8E => String::from("mov Sw,Ew"),
+            0x8F if self.modrm()?.reg & 0x7 == 0 => String::from("pop Eq"),
+            0x90 if self.rex & REX_B != 0 => String::from("xchg Zv,rAX"),
+            0x90 if self.has_prefix(0xF3) => {
+                self.used |= USED_REPEAT;
+                String::from("pause")
+            },
+            0x90 if self.has_prefix(0x66) => String::from("xchg Zv,rAX"),
+            0x90 => String::from("nop"),
+            0x91..=0x97 => String::from("xchg Zv,rAX"),
+            0x98 => String::from("cbw|cwde|cdqe"),
+            0x99 => String::from("cwd|cdq|cqo"),
+            0x9B if matches!(self.bytes.get(self.position), Some(0xD9 | 0xDB | 0xDD | 0xDF)) => {
+                // fwait followed by a non-waiting control instruction is shown as the waiting form
+                let position = self.position;
+                let opcode = self.next()?;
+                if let Some((name, operands)) = self.x87(opcode)
+                    && matches!(name.as_str(), "fnstenv" | "fnstcw" | "fnsave" | "fnstsw" | "fnclex" | "fninit") {
+                    return Some((name.replacen("fn", "f", 1), operands));
+                }
+                self.position = position;
+                self.modrm = None;
+                self.rip_displacement = None;
+                String::from("fwait")
+            },
+            0x9B => String::from("fwait"),
+            0x9C => String::from("pushf"),
+            0x9D => String::from("popf"),
+            0x9E => String::from("sahf"),
+            0x9F => String::from("lahf"),
+            0xA0 => String::from("movabs AL,Ob"),
+            0xA1 => String::from("movabs rAX,Ov"),
+            0xA2 => String::from("movabs Ob,AL"),
+            0xA3 => String::from("movabs Ov,rAX"),
+            0xA4 => String::from("movs Yb,Xb"),
+            0xA5 => String::from("movs Yv,Xv"),
+            0xA6 => String::from("cmps Xb,Yb"),
+            0xA7 => String::from("cmps Xv,Yv"),
+            0xA8 => String::from("test AL,Ib"),
+            0xA9 => String::from("test rAX,Iz"),
+            0xAA => String::from("stos Yb,AL"),
+            0xAB => String::from("stos Yv,rAX"),
+            0xAC => String::from("lods AL,Xb"),
+            0xAD => String::from("lods rAX,Xv"),
+            0xAE => String::from("scas AL,Yb"),
+            0xAF => String::from("scas rAX,Yv"),
+            0xB0..=0xB7 => String::from("mov Zb,Ib"),
+            0xB8..=0xBF if self.rex_w() => String::from("movabs Zv,Iv"),
+            0xB8..=0xBF => String::from("mov Zv,Iv"),
+            0xC0 => format!("{} Eb,Ib", SHIFTS[(self.modrm()?.reg & 0x7) as usize]),
+            0xC1 => format!("{} Ev,Ib", SHIFTS[(self.modrm()?.reg & 0x7) as usize]),
+            0xC2 => String::from("ret Iw"),
+            0xC3 => String::from("ret"),
+            0xC6 if self.modrm()?.reg & 0x7 == 0 => String::from("mov Eb,Ib"),
+            0xC7 if self.modrm()?.reg & 0x7 == 0 => String::from("mov Ev,Iz"),
+            0xC8 => String::from("enter Iw,Ib"),
+            0xC9 => String::from("leave"),
+            0xCC => String::from("int3"),
+            0xCD => String::from("int Ib"),
+            0xD0 => format!("{} Eb,1", SHIFTS[(self.modrm()?.reg & 0x7) as usize]),
+            0xD1 => format!("{} Ev,1", SHIFTS[(self.modrm()?.reg & 0x7) as usize]),
+            0xD2 => format!("{} Eb,CL", SHIFTS[(self.modrm()?.reg & 0x7) as usize]),
+            0xD3 => format!("{} Ev,CL", SHIFTS[(self.modrm()?.reg & 0x7) as usize]),
+            0xD8..=0xDF => return self.x87(opcode),
+            0xE0 => String::from("loopne Jb"),
+            0xE1 => String::from("loope Jb"),
+            0xE2 => String::from("loop Jb"),
+            0xE3 => String::from("jrcxz Jb"),
+            0xE4 => String::from("in AL,Ib"),
+            0xE6 => String::from("out Ib,AL"),
+            0xE8 => String::from("call Jz"),
+            0xE9 => String::from("jmp Jz"),
+            0xEB => String::from("jmp Jb"),
+            0xEC => String::from("in AL,DX"),
+            0xEE => String::from("out DX,AL"),
+            0xF4 => String::from("hlt"),
+            0xF5 => String::from("cmc"),
+            0xF6 | 0xF7 => {
+                let operand = if opcode == 0xF6 { "Eb" } else { "Ev" };
+                match self.modrm()?.reg & 0x7 {
+                    0 | 1 => format!("test {},{}", operand, if opcode == 0xF6 { "Ib" } else { "Iz" }),
+                    reg => format!("{} {}", ["", "", "not", "neg", "mul", "imul", "div", "idiv"][reg as usize], operand),
+                }
+            },
+            0xF8 => String::from("clc"),
+            0xF9 => String::from("stc"),
+            0xFA => String::from("cli"),
+            0xFB => String::from("sti"),
+            0xFC => String::from("cld"),
+            0xFD => String::from("std"),
+            0xFE => match self.modrm()?.reg & 0x7 {
+                0 => String::from("inc Eb"),
+                1 => String::from("dec Eb"),
+                _ => return None,
+            },
+            0xFF => match self.modrm()?.reg & 0x7 {
+                0 => String::from("inc Ev"),
+                1 => String::from("dec Ev"),
+                2 => String::from("call Eq"),
+                4 => String::from("jmp Eq"),
+                6 => String::from("push Eq"),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        if matches!(opcode, 0xA4..=0xA7 | 0xAA..=0xAF) {
+            self.string_word = Some(if matches!(opcode, 0xA6 | 0xA7 | 0xAE | 0xAF) { "repz" } else { "rep" });
+        }
+        self.is_branch = matches!(opcode, 0x70..=0x7F | 0xC2 | 0xC3 | 0xE8 | 0xE9 | 0xEB);
+        if opcode == 0xFF && matches!(self.modrm()?.reg & 0x7, 2 | 4) {
+            self.is_branch = true;
+            self.is_indirect_branch = true;
+        }
+
+        self.execute(&spec)
+    }
+
+    fn two_byte(&mut self, opcode: u8) -> Option<(String, Vec<String>)> {
+        let prefix = self.mandatory_prefix();
+
+        // Instructions without vector operands
+        let spec: Option<String> = match (opcode, prefix) {
+            (0x01, _) if self.vex.is_none() => match self.modrm()?.mode {
+                3 => {
+                    let byte = self.bytes[self.position - 1];
+                    Some(String::from(match byte {
+                        0xD0 => "xgetbv",
+                        0xD5 => "xend",
+                        0xD6 => "xtest",
+                        0xF8 => "swapgs",
+                        0xF9 => "rdtscp",
+                        _ => return None,
+                    }))
+                },
+                _ => None,
+            },
+            (0x05, _) => Some(String::from("syscall")),
+            (0x0B, _) => Some(String::from("ud2")),
+            (0x0D, _) if self.modrm()?.mode != 3 => Some(String::from(["prefetch Mb", "prefetchw Mb", "prefetchwt1 Mb", "prefetch Mb", "prefetch Mb", "prefetch Mb", "prefetch Mb", "prefetch Mb"][(self.modrm()?.reg & 0x7) as usize])),
+            (0x18, _) if self.modrm()?.mode != 3 && self.modrm()?.reg & 0x7 < 4 =>
+                Some(format!("{} Mb", ["prefetchnta", "prefetcht0", "prefetcht1", "prefetcht2"][(self.modrm()?.reg & 0x7) as usize])),
+            (0x1E, Mandatory::Rep) if self.bytes.get(self.position) == Some(&0xFA) => {
+                self.position += 1;
+                self.used |= USED_REPEAT;
+                Some(String::from("endbr64"))
+            },
+            (0x1E, Mandatory::Rep) if self.bytes.get(self.position) == Some(&0xFB) => {
+                self.position += 1;
+                self.used |= USED_REPEAT;
+                Some(String::from("endbr32"))
+            },
+            (0x1E, Mandatory::Rep) if self.modrm()?.mode == 3 && self.modrm()?.reg & 0x7 == 1 => {
+                self.used |= USED_REPEAT;
+                Some(String::from("rdsspd|rdsspq Ey"))
+            },
+            (0x1F, _) if self.vex.is_none() => Some(String::from("nop Ev")),
+            (0x31, _) => Some(String::from("rdtsc")),
+            (0x40..=0x4F, _) => Some(format!("cmov{} Gv,Ev", CONDITIONS[(opcode & 0xF) as usize])),
+            (0x80..=0x8F, _) => {
+                self.is_branch = true;
+                Some(format!("j{} Jz", CONDITIONS[(opcode & 0xF) as usize]))
+            },
+            (0x90..=0x9F, _) => Some(format!("set{} Eb", CONDITIONS[(opcode & 0xF) as usize])),
+            (0xA2, _) => Some(String::from("cpuid")),
+            (0xA3, _) => Some(String::from("bt Ev,Gv")),
+            (0xA4, _) => Some(String::from("shld Ev,Gv,Ib")),
+            (0xA5, _) => Some(String::from("shld Ev,Gv,CL")),
+            (0xAB, _) => Some(String::from("bts Ev,Gv")),
+            (0xAC, _) => Some(String::from("shrd Ev,Gv,Ib")),
+            (0xAD, _) => Some(String::from("shrd Ev,Gv,CL")),
+            (0xAE, _) => self.group_0fae(),
+            (0xAF, _) => Some(String::from("imul Gv,Ev")),
+            (0xB0, _) => Some(String::from("cmpxchg Eb,Gb")),
+            (0xB1, _) => Some(String::from("cmpxchg Ev,Gv")),
+            (0xB3, _) => Some(String::from("btr Ev,Gv")),
+            (0xB6, _) => Some(String::from("movzx Gv,Eb")),
+            (0xB7, _) => Some(String::from("movzx Gv,Ew")),
+            (0xB8, Mandatory::Rep) => {
+                self.used |= USED_REPEAT;
+                Some(String::from("popcnt Gv,Ev"))
+            },
+            (0xBA, _) if self.modrm()?.reg & 0x7 >= 4 => Some(format!("{} Ev,Ib", ["bt", "bts", "btr", "btc"][(self.modrm()?.reg & 0x3) as usize])),
+            (0xBB, _) => Some(String::from("btc Ev,Gv")),
+            (0xBC, Mandatory::Rep) => {
+                self.used |= USED_REPEAT;
+                Some(String::from("tzcnt Gv,Ev"))
+            },
+            (0xBD, Mandatory::Rep) => {
+                self.used |= USED_REPEAT;
+                Some(String::from("lzcnt Gv,Ev"))
+            },
+            (0xBC, _) => Some(String::from("bsf Gv,Ev")),
+            (0xBD, _) => Some(String::from("bsr Gv,Ev")),
+            (0xBE, _) => Some(String::from("movsx Gv,Eb")),
+            (0xBF, _) => Some(String::from("movsx Gv,Ew")),
+            (0xC0, _) => Some(String::from("xadd Eb,Gb")),
+            (0xC1, _) => Some(String::from("xadd Ev,Gv")),
+            (0xC3, Mandatory::None) => Some(String::from("movnti My,Gy")),
+            (0xC7, _) => match (self.modrm()?.mode, self.modrm()?.reg & 0x7) {
+                (0..=2, 1) if self.rex_w() => Some(String::from("cmpxchg16b Mo")),
+                (0..=2, 1) => Some(String::from("cmpxchg8b Mq")),
+                (3, 6) => Some(String::from("rdrand Ev")),
+                (3, 7) => Some(String::from("rdseed Ev")),
+                _ => return None,
+            },
+            (0xC8..=0xCF, _) => Some(String::from("bswap Zy")),
+            _ => None,
+        };
+
+        if let Some(spec) = spec {
+            if self.vex.is_some() && spec != "vldmxcsr Md" && spec != "vstmxcsr Md" {
+                return None;
+            }
+            return self.execute(&spec);
+        }
+
+        match (opcode, self.vex) {
+            (0x77, Some(vex)) => return self.execute(if vex.long { "vzeroall" } else { "vzeroupper" }),
+            (0x77, None) => return self.execute("emms"),
+            _ => (),
+        }
+
+        if let Some(spec) = vector_two_byte(opcode, prefix).or_else(|| self.shift_group(opcode, prefix)) {
+            return self.execute_vector(spec.as_str(), prefix);
+        }
+
+        // Legacy MMX forms share their mnemonics with the 66 prefixed SSE2 forms
+        if self.vex.is_none() && prefix == Mandatory::None && matches!(opcode, 0x60..=0x6B | 0x6E..=0x7F | 0xD1..=0xFE)
+            && let Some(spec) = vector_two_byte(opcode, Mandatory::OperandSize).or_else(|| self.shift_group(opcode, Mandatory::OperandSize)) {
+            self.mmx = true;
+            return self.execute(spec.as_str());
+        }
+
+        // A mandatory prefix that selects nothing is an ordinary prefix after all
+        if prefix != Mandatory::None && self.vex.is_none()
+            && let Some(spec) = vector_two_byte(opcode, Mandatory::None) {
+            return self.execute(spec.as_str());
+        }
+        None
+    }
+
+    fn group_0fae(&mut self) -> Option<String> {
+        let modrm = self.modrm()?;
+        let reg = modrm.reg & 0x7;
+        if modrm.mode == 3 && reg == 5 && self.mandatory_prefix() == Mandatory::Rep {
+            self.used |= USED_REPEAT;
+            return Some(String::from("incsspd|incsspq Ey"));
+        }
+        if modrm.mode == 3 {
+            return match (reg, self.vex.is_some()) {
+                (5, false) => Some(String::from("lfence")),
+                (6, false) => Some(String::from("mfence")),
+                (7, false) => Some(String::from("sfence")),
+                _ => None,
+            };
+        }
+        match (reg, self.vex.is_some()) {
+            (0, false) => Some(String::from("fxsave M")),
+            (1, false) => Some(String::from("fxrstor M")),
+            (2, false) => Some(String::from("ldmxcsr Md")),
+            (3, false) => Some(String::from("stmxcsr Md")),
+            (2, true) => Some(String::from("vldmxcsr Md")),
+            (3, true) => Some(String::from("vstmxcsr Md")),
+            (4, false) => Some(String::from("xsave M")),
+            (5, false) => Some(String::from("xrstor M")),
+            (6, false) => Some(String::from("xsaveopt M")),
+            (7, false) => Some(String::from("clflush Mb")),
+            _ => None,
+        }
+    }
+
+    // Shifts by immediate, where VEX encodes the destination in vvvv
+    fn shift_group(&mut self, opcode: u8, prefix: Mandatory) -> Option<String> {
+        if prefix != Mandatory::OperandSize || !matches!(opcode, 0x71..=0x73) {
+            return None;
+        }
+        let name = match (opcode, self.modrm()?.reg & 0x7) {
+            (0x71, 2) => "psrlw",
+            (0x71, 4) => "psraw",
+            (0x71, 6) => "psllw",
+            (0x72, 2) => "psrld",
+            (0x72, 4) => "psrad",
+            (0x72, 6) => "pslld",
+            (0x73, 2) => "psrlq",
+            (0x73, 3) if !self.mmx => "psrldq",
+            (0x73, 6) => "psllq",
+            (0x73, 7) if !self.mmx => "pslldq",
+            _ => return None,
+        };
+        Some(format!("{} Hx,Ux,Ib", name))
+    }
+
+    fn three_byte(&mut self, opcode: u8, is_3a: bool) -> Option<(String, Vec<String>)> {
+        let prefix = self.mandatory_prefix();
+
+        if !is_3a {
+            let spec = match (opcode, prefix, self.vex.is_some()) {
+                (0xF0, Mandatory::None, false) => Some("movbe Gv,Mv"),
+                (0xF1, Mandatory::None, false) => Some("movbe Mv,Gv"),
+                (0xF0, Mandatory::Repne, false) => Some("crc32 Gy,Eb"),
+                (0xF1, Mandatory::Repne, false) => Some("crc32 Gy,Ev"),
+                (0xF2, Mandatory::None, true) => Some("!andn Gy,By,Ey"),
+                (0xF3, Mandatory::None, true) => match self.modrm()?.reg & 0x7 {
+                    1 => Some("!blsr By,Ey"),
+                    2 => Some("!blsmsk By,Ey"),
+                    3 => Some("!blsi By,Ey"),
+                    _ => return None,
+                },
+                (0xF5, Mandatory::None, true) => Some("!bzhi Gy,Ey,By"),
+                (0xF5, Mandatory::Rep, true) => Some("!pext Gy,By,Ey"),
+                (0xF5, Mandatory::Repne, true) => Some("!pdep Gy,By,Ey"),
+                (0xF6, Mandatory::Repne, true) => Some("!mulx Gy,By,Ey"),
+                (0xF7, Mandatory::None, true) => Some("!bextr Gy,Ey,By"),
+                (0xF7, Mandatory::OperandSize, true) => Some("!shlx Gy,Ey,By"),
+                (0xF7, Mandatory::Rep, true) => Some("!sarx Gy,Ey,By"),
+                (0xF7, Mandatory::Repne, true) => Some("!shrx Gy,Ey,By"),
+                _ => None,
+            };
+            if let Some(spec) = spec {
+                if prefix != Mandatory::None {
+                    self.used |= if prefix == Mandatory::OperandSize { USED_OPERAND_SIZE } else { USED_REPEAT };
+                    self.mandatory_66 = prefix == Mandatory::OperandSize;
+                }
+                return self.execute(spec);
+            }
+        } else if let (0xF0, Mandatory::Repne, true) = (opcode, prefix, self.vex.is_some()) {
+            return self.execute("!rorx Gy,Ey,Ib");
+        }
+
+        if prefix != Mandatory::OperandSize {
+            return None;
+        }
+
+        let spec = if is_3a {
+            vector_three_byte_3a(opcode).map(String::from)
+        } else {
+            vector_three_byte_38(opcode).map(String::from).or_else(|| fused_multiply_add(opcode, self.rex_w()))
+        }?;
+        self.execute_vector(&spec, prefix)
+    }
+
+    fn execute_vector(&mut self, spec: &str, prefix: Mandatory) -> Option<(String, Vec<String>)> {
+        match prefix {
+            Mandatory::OperandSize => {
+                self.mandatory_66 = true;
+                self.used |= USED_OPERAND_SIZE;
+            },
+            Mandatory::Repne | Mandatory::Rep => self.used |= USED_REPEAT,
+            Mandatory::None => (),
+        }
+        self.execute(spec)
+    }
+
+    fn x87(&mut self, opcode: u8) -> Option<(String, Vec<String>)> {
+        let modrm = self.modrm()?;
+        let reg = (modrm.reg & 0x7) as usize;
+        let group = (opcode - 0xD8) as usize;
+
+        if modrm.mode != 3 {
+            const MEMORY_FORMS: [[(&str, u16); 8]; 8] = [
+                [("fadd", 32), ("fmul", 32), ("fcom", 32), ("fcomp", 32), ("fsub", 32), ("fsubr", 32), ("fdiv", 32), ("fdivr", 32)],
+                [("fld", 32), ("", 0), ("fst", 32), ("fstp", 32), ("fldenv", 0), ("fldcw", 16), ("fnstenv", 0), ("fnstcw", 16)],
+                [("fiadd", 32), ("fimul", 32), ("ficom", 32), ("ficomp", 32), ("fisub", 32), ("fisubr", 32), ("fidiv", 32), ("fidivr", 32)],
+                [("fild", 32), ("fisttp", 32), ("fist", 32), ("fistp", 32), ("", 0), ("fld", 80), ("", 0), ("fstp", 80)],
+                [("fadd", 64), ("fmul", 64), ("fcom", 64), ("fcomp", 64), ("fsub", 64), ("fsubr", 64), ("fdiv", 64), ("fdivr", 64)],
+                [("fld", 64), ("fisttp", 64), ("fst", 64), ("fstp", 64), ("frstor", 0), ("", 0), ("fnsave", 0), ("fnstsw", 16)],
+                [("fiadd", 16), ("fimul", 16), ("ficom", 16), ("ficomp", 16), ("fisub", 16), ("fisubr", 16), ("fidiv", 16), ("fidivr", 16)],
+                [("fild", 16), ("fisttp", 16), ("fist", 16), ("fistp", 16), ("fbld", 80), ("fild", 64), ("fbstp", 80), ("fistp", 64)],
+            ];
+            let (name, bits) = MEMORY_FORMS[group][reg];
+            if name.is_empty() {
+                return None;
+            }
+            return Some((String::from(name), vec![self.sized_memory(&modrm, bits)]));
+        }
+
+        let stack = format!("st({})", modrm.rm & 0x7);
+        let byte = self.bytes[self.position - 1];
+        let (name, operands): (&str, Vec<String>) = match (group, reg) {
+            (0, _) => (["fadd", "fmul", "fcom", "fcomp", "fsub", "fsubr", "fdiv", "fdivr"][reg],
+                if reg == 2 || reg == 3 { vec![stack] } else { vec![String::from("st"), stack] }),
+            (1, 0) => ("fld", vec![stack]),
+            (1, 1) => ("fxch", vec![stack]),
+            (1, _) => (match byte {
+                0xD0 => "fnop",
+                0xE0 => "fchs",
+                0xE1 => "fabs",
+                0xE4 => "ftst",
+                0xE5 => "fxam",
+                0xE8 => "fld1",
+                0xE9 => "fldl2t",
+                0xEA => "fldl2e",
+                0xEB => "fldpi",
+                0xEC => "fldlg2",
+                0xED => "fldln2",
+                0xEE => "fldz",
+                0xF0 => "f2xm1",
+                0xF1 => "fyl2x",
+                0xF2 => "fptan",
+                0xF3 => "fpatan",
+                0xF4 => "fxtract",
+                0xF5 => "fprem1",
+                0xF6 => "fdecstp",
+                0xF7 => "fincstp",
+                0xF8 => "fprem",
+                0xF9 => "fyl2xp1",
+                0xFA => "fsqrt",
+                0xFB => "fsincos",
+                0xFC => "frndint",
+                0xFD => "fscale",
+                0xFE => "fsin",
+                0xFF => "fcos",
+                _ => return None,
+            }, Vec::new()),
+            (2, 0..=3) => (["fcmovb", "fcmove", "fcmovbe", "fcmovu"][reg], vec![String::from("st"), stack]),
+            (2, 5) if byte == 0xE9 => ("fucompp", Vec::new()),
+            (3, 0..=3) => (["fcmovnb", "fcmovne", "fcmovnbe", "fcmovnu"][reg], vec![String::from("st"), stack]),
+            (3, 4) if byte == 0xE2 => ("fnclex", Vec::new()),
+            (3, 4) if byte == 0xE3 => ("fninit", Vec::new()),
+            (3, 5) => ("fucomi", vec![String::from("st"), stack]),
+            (3, 6) => ("fcomi", vec![String::from("st"), stack]),
+            // Like objdump, follow the AT&T assemblers that swapped the reversed forms of these
+            (4, 0 | 1 | 4..=7) => (["fadd", "fmul", "", "", "fsubr", "fsub", "fdivr", "fdiv"][reg], vec![stack, String::from("st")]),
+            (5, 0) => ("ffree", vec![stack]),
+            (5, 2) => ("fst", vec![stack]),
+            (5, 3) => ("fstp", vec![stack]),
+            (5, 4) => ("fucom", vec![stack]),
+            (5, 5) => ("fucomp", vec![stack]),
+            (6, 3) if byte == 0xD9 => ("fcompp", Vec::new()),
+            (6, 0 | 1 | 4..=7) => (["faddp", "fmulp", "", "", "fsubrp", "fsubp", "fdivrp", "fdivp"][reg], vec![stack, String::from("st")]),
+            (7, 0) => ("ffreep", vec![stack]),
+            (7, 4) if byte == 0xE0 => ("fnstsw", vec![String::from("ax")]),
+            (7, 5) => ("fucomip", vec![String::from("st"), stack]),
+            (7, 6) => ("fcomip", vec![String::from("st"), stack]),
+            _ => return None,
+        };
+        Some((String::from(name), operands))
+    }
+
+    // Runs a table entry of the form "mnemonic Op,Op,...", see operand() for the operand codes
+    fn execute(&mut self, spec: &str) -> Option<(String, Vec<String>)> {
+        let (mnemonic, operands) = spec.split_once(' ').unwrap_or((spec, ""));
+        let codes: Vec<&str> = operands.split(',').filter(|code| !code.is_empty()).collect();
+
+        let is_vex_only = mnemonic.starts_with('!') || mnemonic.starts_with('v');
+        let is_vector = codes.iter().any(|code| matches!(code.as_bytes()[0], b'V' | b'W' | b'H' | b'U' | b'L'));
+        if self.vex.is_some() && !is_vex_only && !is_vector {
+            return None;
+        }
+        if self.vex.is_none() && is_vex_only {
+            return None;
+        }
+
+        let mut texts = Vec::new();
+        for code in codes.iter() {
+            if code.starts_with('H') {
+                if self.vex.is_none() {
+                    continue;
+                }
+                if *code == "Hr" && self.modrm()?.mode != 3 {
+                    continue;
+                }
+            }
+            texts.push(self.operand(code)?);
+        }
+
+        let mut mnemonic = self.resolve_mnemonic(mnemonic.trim_start_matches('!'))?;
+
+        // Comparison predicates are folded into the mnemonic
+        if let Some(stem) = mnemonic.strip_prefix("cmp").filter(|stem| matches!(*stem, "ps" | "pd" | "ss" | "sd")) {
+            let predicate = self.next()? as usize;
+            let limit = if self.vex.is_some() { 32 } else { 8 };
+            if predicate < limit {
+                mnemonic = format!("cmp{}{}", COMPARE_PREDICATES[predicate], stem);
+            } else {
+                texts.push(format!("0x{:x}", predicate));
+            }
+        }
+        if mnemonic == "pclmulqdq" {
+            let selector = self.next()?;
+            match selector {
+                0x00 => mnemonic = String::from("pclmullqlqdq"),
+                0x01 => mnemonic = String::from("pclmulhqlqdq"),
+                0x10 => mnemonic = String::from("pclmullqhqdq"),
+                0x11 => mnemonic = String::from("pclmulhqhqdq"),
+                _ => texts.push(format!("0x{:x}", selector)),
+            }
+        }
+
+        if self.vex.is_some() && !is_vex_only {
+            mnemonic.insert(0, 'v');
+        }
+        Some((mnemonic, texts))
+    }
+
+    // "a|b|c" picks by operand size, "a|b" by REX.W and "a/b" by memory or register operand
+    fn resolve_mnemonic(&mut self, mnemonic: &str) -> Option<String> {
+        let sizes: Vec<&str> = mnemonic.split('|').collect();
+        let mnemonic = match sizes.len() {
+            3 => sizes[[16, 32, 64].iter().position(|size| *size == self.operand_size())?],
+            2 => sizes[self.rex_w() as usize],
+            _ => mnemonic,
+        };
+        Some(String::from(match mnemonic.split_once('/') {
+            Some((memory, register)) => if self.modrm()?.mode == 3 { register } else { memory },
+            None => mnemonic,
+        }))
+    }
+
+    fn gpr(&self, bits: u16, number: u8) -> &'static str {
+        match bits {
+            8 if !self.has_rex && number < 8 => REGISTERS_8_LEGACY[number as usize],
+            8 => REGISTERS_8[number as usize],
+            16 => REGISTERS_16[number as usize],
+            32 => REGISTERS_32[number as usize],
+            _ => REGISTERS_64[number as usize],
+        }
+    }
+
+    fn sized_memory(&self, modrm: &ModRm, bits: u16) -> String {
+        let size = match bits {
+            8 => "BYTE PTR ",
+            16 => "WORD PTR ",
+            32 => "DWORD PTR ",
+            64 => "QWORD PTR ",
+            80 => "TBYTE PTR ",
+            128 => "XMMWORD PTR ",
+            256 => "YMMWORD PTR ",
+            _ => "",
+        };
+        format!("{}{}", size, modrm.memory)
+    }
+
+    fn integer_size(&mut self, code: &str) -> Option<(u16, u16)> {
+        // (register bits, memory bits)
+        Some(match code {
+            "b" => (8, 8),
+            "w" => (16, 16),
+            "d" => (32, 32),
+            "q" => (64, 64),
+            "o" => (128, 128),
+            "v" => {
+                let size = self.operand_size();
+                (size, size)
+            },
+            "y" => if self.rex_w() { (64, 64) } else { (32, 32) },
+            "dw" => (32, 16),
+            "db" => (32, 8),
+            "" => (64, 0),
+            _ => return None,
+        })
+    }
+
+    fn vector_register(&self, code: &str, number: u8) -> String {
+        if self.mmx {
+            return format!("mm{}", number & 0x7);
+        }
+        let long = self.vex.map(|vex| vex.long).unwrap_or(false);
+        match code {
+            "x" if long => format!("ymm{}", number),
+            "qq" => format!("ymm{}", number),
+            _ => format!("xmm{}", number),
+        }
+    }
+
+    fn vector_memory_bits(&self, code: &str) -> u16 {
+        let long = self.vex.map(|vex| vex.long).unwrap_or(false);
+        match code {
+            "x" | "dq" if self.mmx => 64,
+            "x" => if long { 256 } else { 128 },
+            "dq" => 128,
+            "qq" => 256,
+            "q" => 64,
+            "d" => 32,
+            "w" => 16,
+            "b" => 8,
+            "h" => if long { 128 } else { 64 },
+            "f" => if long { 64 } else { 32 },
+            "e" => if long { 32 } else { 16 },
+            _ => 0,
+        }
+    }
+
+    fn immediate(&mut self, size: usize, bits: u16) -> Option<String> {
+        let value = self.next_signed(size)? as u64;
+        let mask = if bits >= 64 { u64::MAX } else { (1u64 << bits) - 1 };
+        Some(format!("0x{:x}", value & mask))
+    }
+
+    fn relative(&mut self, size: usize) -> Option<String> {
+        let displacement = self.next_signed(size)?;
+        let target = self.address.wrapping_add(self.position as u64).wrapping_add(displacement as u64);
+        self.target = Some(target);
+        Some(format!("{:x}", target))
+    }
+
+    // Operand codes: a letter for where the operand lives followed by its size
+    //   E/G   ModRM rm/reg general purpose register or memory (b, w, d, q, v = operand size, y = 32/64 by REX.W)
+    //   M     ModRM memory only
+    //   V/W/U ModRM reg/rm vector register, W may be memory (x = xmm/ymm by VEX.L, dq = xmm, qq = ymm,
+    //         q/d/w/b = memory size of a scalar, h/f/e = half, quarter and eighth of the vector)
+    //   H/B   VEX.vvvv vector or general purpose register, Hr only for the register form
+    //   L     register in the upper four bits of an immediate byte
+    //   Z     register in the low three bits of the opcode
+    //   I/J   immediates and relative branch targets
+    fn operand(&mut self, code: &str) -> Option<String> {
+        let (kind, size) = code.split_at(1);
+        match (kind, size) {
+            ("A", "L") => Some(String::from("al")),
+            ("C", "L") => Some(String::from("cl")),
+            ("D", "X") => Some(String::from("dx")),
+            ("1", _) => Some(String::from("1")),
+            ("r", "AX") => {
+                let size = self.operand_size();
+                Some(String::from(self.gpr(size, 0)))
+            },
+            ("E", _) => {
+                let modrm = self.modrm()?;
+                let (register, memory) = self.integer_size(size)?;
+                if modrm.mode == 3 {
+                    Some(String::from(self.gpr(register, modrm.rm)))
+                } else {
+                    Some(self.sized_memory(&modrm, memory))
+                }
+            },
+            ("G", _) => {
+                let modrm = self.modrm()?;
+                let (register, _) = self.integer_size(size)?;
+                Some(String::from(self.gpr(register, modrm.reg)))
+            },
+            ("M", _) => {
+                let modrm = self.modrm()?;
+                if modrm.mode == 3 {
+                    return None;
+                }
+                let bits = match self.integer_size(size) {
+                    Some((_, bits)) => bits,
+                    None => self.vector_memory_bits(size),
+                };
+                Some(self.sized_memory(&modrm, bits))
+            },
+            ("Z", _) => {
+                let number = (self.bytes[self.position - 1] & 0x7) | if self.rex & REX_B != 0 { 0x8 } else { 0 };
+                let bits = match size {
+                    "b" => 8,
+                    "q" => self.stack_size(),
+                    "y" => if self.rex_w() { 64 } else { 32 },
+                    _ => self.operand_size(),
+                };
+                Some(String::from(self.gpr(bits, number)))
+            },
+            ("S", "w") => Some(String::from(SEGMENT_REGISTERS[(self.modrm()?.reg & 0x7) as usize])),
+            ("O", _) => {
+                let address = self.next_signed(8)? as u64;
+                Some(format!("ds:0x{:x}", address))
+            },
+            ("X", "0") => Some(String::from("xmm0")),
+            ("X", _) | ("Y", _) => {
+                let (_, bits) = self.integer_size(size)?;
+                let modrm = ModRm {
+                    mode: 0,
+                    reg: 0,
+                    rm: 0,
+                    memory: String::from(if kind == "X" { "ds:[rsi]" } else { "es:[rdi]" }),
+                };
+                Some(self.sized_memory(&modrm, bits))
+            },
+            ("I", "b") => self.immediate(1, 8),
+            ("I", "w") => self.immediate(2, 16),
+            ("I", "bs") => {
+                let size = self.operand_size();
+                self.immediate(1, size)
+            },
+            ("I", "bq") => {
+                let size = self.stack_size();
+                self.immediate(1, size)
+            },
+            ("I", "z") => {
+                let size = self.operand_size();
+                self.immediate(if size == 16 { 2 } else { 4 }, size)
+            },
+            ("I", "zq") => {
+                let size = self.stack_size();
+                self.immediate(if size == 16 { 2 } else { 4 }, size)
+            },
+            ("I", "v") => {
+                let size = self.operand_size();
+                self.immediate((size / 8) as usize, size)
+            },
+            ("J", "b") => self.relative(1),
+            ("J", "z") => self.relative(4),
+            ("V", _) => {
+                let modrm = self.modrm()?;
+                Some(self.vector_register(size, if self.mmx { modrm.reg & 0x7 } else { modrm.reg }))
+            },
+            ("W", _) | ("U", _) => {
+                let modrm = self.modrm()?;
+                if modrm.mode == 3 {
+                    Some(self.vector_register(size, modrm.rm))
+                } else if kind == "U" {
+                    None
+                } else {
+                    Some(self.sized_memory(&modrm, self.vector_memory_bits(size)))
+                }
+            },
+            ("H", _) => {
+                let vex = self.vex?;
+                Some(self.vector_register(if size == "r" { "dq" } else { size }, vex.vvvv))
+            },
+            ("B", "y") => {
+                let vex = self.vex?;
+                Some(String::from(self.gpr(if self.rex_w() { 64 } else { 32 }, vex.vvvv)))
+            },
+            ("L", _) => {
+                let register = self.next()? >> 4;
+                Some(self.vector_register(size, register))
+            },
+            _ => None,
+        }
+    }
+
+    fn finish(&mut self, mnemonic: String, operands: Vec<String>) -> Instruction {
+        let mut words = Vec::new();
+        let last_66 = self.prefixes.iter().rposition(|prefix| *prefix == 0x66);
+        let last_repeat = self.prefixes.iter().rposition(|prefix| matches!(prefix, 0xF2 | 0xF3));
+
+        for (index, prefix) in self.prefixes.iter().enumerate() {
+            let word = match prefix {
+                0x66 if self.used & USED_OPERAND_SIZE != 0 && Some(index) == last_66 => continue,
+                0x66 => "data16",
+                0x67 if self.used & USED_ADDRESS_SIZE != 0 => continue,
+                0x67 => "addr32",
+                0xF0 => "lock",
+                0xF2 | 0xF3 if Some(index) == last_repeat && self.used & USED_REPEAT != 0 => continue,
+                0xF3 if Some(index) == last_repeat && self.string_word.is_some() => self.string_word.unwrap(),
+                0xF2 if Some(index) == last_repeat && self.string_word.is_some() => "repnz",
+                0xF2 if Some(index) == last_repeat && self.is_branch => "bnd",
+                0xF3 => "repz",
+                0xF2 => "repnz",
+                0x3E if self.is_indirect_branch => "notrack",
+                0x2E => "cs",
+                0x36 => "ss",
+                0x3E => "ds",
+                0x26 => "es",
+                0x64 | 0x65 if self.used & USED_SEGMENT != 0 => continue,
+                0x64 => "fs",
+                _ => "gs",
+            };
+            words.push(word);
+        }
+        words.push(&mnemonic);
+
+        Instruction {
+            address: self.address,
+            length: self.position,
+            mnemonic: words.join(" "),
+            operands: operands.join(","),
+            target: self.target,
+            reference: self.rip_displacement.map(|displacement| {
+                self.address.wrapping_add(self.position as u64).wrapping_add(displacement as u64)
+            }),
+        }
+    }
+}
+
+fn vector_two_byte(opcode: u8, prefix: Mandatory) -> Option<String> {
+    use Mandatory::*;
+
+    let arithmetic = match opcode {
+        0x51 => Some("sqrt"),
+        0x58 => Some("add"),
+        0x59 => Some("mul"),
+        0x5C => Some("sub"),
+        0x5D => Some("min"),
+        0x5E => Some("div"),
+        0x5F => Some("max"),
+        _ => Option::None,
+    };
+    if let Some(name) = arithmetic {
+        return Some(match prefix {
+            None if opcode == 0x51 => format!("{}ps Vx,Wx", name),
+            OperandSize if opcode == 0x51 => format!("{}pd Vx,Wx", name),
+            None => format!("{}ps Vx,Hx,Wx", name),
+            OperandSize => format!("{}pd Vx,Hx,Wx", name),
+            Rep => format!("{}ss Vdq,Hdq,Wd", name),
+            Repne => format!("{}sd Vdq,Hdq,Wq", name),
+        });
+    }
+
+    let spec = match (opcode, prefix) {
+        (0x10, None) => "movups Vx,Wx",
+        (0x10, OperandSize) => "movupd Vx,Wx",
+        (0x10, Rep) => "movss Vdq,Hr,Wd",
+        (0x10, Repne) => "movsd Vdq,Hr,Wq",
+        (0x11, None) => "movups Wx,Vx",
+        (0x11, OperandSize) => "movupd Wx,Vx",
+        (0x11, Rep) => "movss Wd,Hr,Vdq",
+        (0x11, Repne) => "movsd Wq,Hr,Vdq",
+        (0x12, None) => "movlps/movhlps Vdq,Hdq,Wq",
+        (0x12, OperandSize) => "movlpd Vdq,Hdq,Mq",
+        (0x12, Rep) => "movsldup Vx,Wx",
+        (0x12, Repne) => "movddup Vx,Wq",
+        (0x13, None) => "movlps Mq,Vdq",
+        (0x13, OperandSize) => "movlpd Mq,Vdq",
+        (0x14, None) => "unpcklps Vx,Hx,Wx",
+        (0x14, OperandSize) => "unpcklpd Vx,Hx,Wx",
+        (0x15, None) => "unpckhps Vx,Hx,Wx",
+        (0x15, OperandSize) => "unpckhpd Vx,Hx,Wx",
+        (0x16, None) => "movhps/movlhps Vdq,Hdq,Wq",
+        (0x16, OperandSize) => "movhpd Vdq,Hdq,Mq",
+        (0x16, Rep) => "movshdup Vx,Wx",
+        (0x17, None) => "movhps Mq,Vdq",
+        (0x17, OperandSize) => "movhpd Mq,Vdq",
+        (0x28, None) => "movaps Vx,Wx",
+        (0x28, OperandSize) => "movapd Vx,Wx",
+        (0x29, None) => "movaps Wx,Vx",
+        (0x29, OperandSize) => "movapd Wx,Vx",
+        (0x2A, Rep) => "cvtsi2ss Vdq,Hdq,Ey",
+        (0x2A, Repne) => "cvtsi2sd Vdq,Hdq,Ey",
+        (0x2B, None) => "movntps Mx,Vx",
+        (0x2B, OperandSize) => "movntpd Mx,Vx",
+        (0x2C, Rep) => "cvttss2si Gy,Wd",
+        (0x2C, Repne) => "cvttsd2si Gy,Wq",
+        (0x2D, Rep) => "cvtss2si Gy,Wd",
+        (0x2D, Repne) => "cvtsd2si Gy,Wq",
+        (0x2E, None) => "ucomiss Vdq,Wd",
+        (0x2E, OperandSize) => "ucomisd Vdq,Wq",
+        (0x2F, None) => "comiss Vdq,Wd",
+        (0x2F, OperandSize) => "comisd Vdq,Wq",
+        (0x50, None) => "movmskps Gd,Ux",
+        (0x50, OperandSize) => "movmskpd Gd,Ux",
+        (0x52, None) => "rsqrtps Vx,Wx",
+        (0x52, Rep) => "rsqrtss Vdq,Hdq,Wd",
+        (0x53, None) => "rcpps Vx,Wx",
+        (0x53, Rep) => "rcpss Vdq,Hdq,Wd",
+        (0x54, None) => "andps Vx,Hx,Wx",
+        (0x54, OperandSize) => "andpd Vx,Hx,Wx",
+        (0x55, None) => "andnps Vx,Hx,Wx",
+        (0x55, OperandSize) => "andnpd Vx,Hx,Wx",
+        (0x56, None) => "orps Vx,Hx,Wx",
+        (0x56, OperandSize) => "orpd Vx,Hx,Wx",
+        (0x57, None) => "xorps Vx,Hx,Wx",
+        (0x57, OperandSize) => "xorpd Vx,Hx,Wx",
+        (0x5A, None) => "cvtps2pd Vx,Wh",
+        (0x5A, OperandSize) => "cvtpd2ps Vdq,Wx",
+        (0x5A, Rep) => "cvtss2sd Vdq,Hdq,Wd",
+        (0x5A, Repne) => "cvtsd2ss Vdq,Hdq,Wq",
+        (0x5B, None) => "cvtdq2ps Vx,Wx",
+        (0x5B, OperandSize) => "cvtps2dq Vx,Wx",
+        (0x5B, Rep) => "cvttps2dq Vx,Wx",
+        (0x60, OperandSize) => "punpcklbw Vx,Hx,Wx",
+        (0x61, OperandSize) => "punpcklwd Vx,Hx,Wx",
+        (0x62, OperandSize) => "punpckldq Vx,Hx,Wx",
+        (0x63, OperandSize) => "packsswb Vx,Hx,Wx",
+        (0x64, OperandSize) => "pcmpgtb Vx,Hx,Wx",
+        (0x65, OperandSize) => "pcmpgtw Vx,Hx,Wx",
+        (0x66, OperandSize) => "pcmpgtd Vx,Hx,Wx",
+        (0x67, OperandSize) => "packuswb Vx,Hx,Wx",
+        (0x68, OperandSize) => "punpckhbw Vx,Hx,Wx",
+        (0x69, OperandSize) => "punpckhwd Vx,Hx,Wx",
+        (0x6A, OperandSize) => "punpckhdq Vx,Hx,Wx",
+        (0x6B, OperandSize) => "packssdw Vx,Hx,Wx",
+        (0x6C, OperandSize) => "punpcklqdq Vx,Hx,Wx",
+        (0x6D, OperandSize) => "punpckhqdq Vx,Hx,Wx",
+        (0x6E, OperandSize) => "movd|movq Vdq,Ey",
+        (0x6F, OperandSize) => "movdqa Vx,Wx",
+        (0x6F, Rep) => "movdqu Vx,Wx",
+        (0x70, OperandSize) => "pshufd Vx,Wx,Ib",
+        (0x70, Rep) => "pshufhw Vx,Wx,Ib",
+        (0x70, Repne) => "pshuflw Vx,Wx,Ib",
+        (0x74, OperandSize) => "pcmpeqb Vx,Hx,Wx",
+        (0x75, OperandSize) => "pcmpeqw Vx,Hx,Wx",
+        (0x76, OperandSize) => "pcmpeqd Vx,Hx,Wx",
+        (0x7C, OperandSize) => "haddpd Vx,Hx,Wx",
+        (0x7C, Repne) => "haddps Vx,Hx,Wx",
+        (0x7D, OperandSize) => "hsubpd Vx,Hx,Wx",
+        (0x7D, Repne) => "hsubps Vx,Hx,Wx",
+        (0x7E, OperandSize) => "movd|movq Ey,Vdq",
+        (0x7E, Rep) => "movq Vdq,Wq",
+        (0x7F, OperandSize) => "movdqa Wx,Vx",
+        (0x7F, Rep) => "movdqu Wx,Vx",
+        (0xC2, None) => "cmpps Vx,Hx,Wx",
+        (0xC2, OperandSize) => "cmppd Vx,Hx,Wx",
+        (0xC2, Rep) => "cmpss Vdq,Hdq,Wd",
+        (0xC2, Repne) => "cmpsd Vdq,Hdq,Wq",
+        (0xC4, OperandSize) => "pinsrw Vdq,Hdq,Edw,Ib",
+        (0xC5, OperandSize) => "pextrw Gd,Udq,Ib",
+        (0xC6, None) => "shufps Vx,Hx,Wx,Ib",
+        (0xC6, OperandSize) => "shufpd Vx,Hx,Wx,Ib",
+        (0xD0, OperandSize) => "addsubpd Vx,Hx,Wx",
+        (0xD0, Repne) => "addsubps Vx,Hx,Wx",
+        (0xD1, OperandSize) => "psrlw Vx,Hx,Wdq",
+        (0xD2, OperandSize) => "psrld Vx,Hx,Wdq",
+        (0xD3, OperandSize) => "psrlq Vx,Hx,Wdq",
+        (0xD4, OperandSize) => "paddq Vx,Hx,Wx",
+        (0xD5, OperandSize) => "pmullw Vx,Hx,Wx",
+        (0xD6, OperandSize) => "movq Wq,Vdq",
+        (0xD7, OperandSize) => "pmovmskb Gd,Ux",
+        (0xD8, OperandSize) => "psubusb Vx,Hx,Wx",
+        (0xD9, OperandSize) => "psubusw Vx,Hx,Wx",
+        (0xDA, OperandSize) => "pminub Vx,Hx,Wx",
+        (0xDB, OperandSize) => "pand Vx,Hx,Wx",
+        (0xDC, OperandSize) => "paddusb Vx,Hx,Wx",
+        (0xDD, OperandSize) => "paddusw Vx,Hx,Wx",
+        (0xDE, OperandSize) => "pmaxub Vx,Hx,Wx",
+        (0xDF, OperandSize) => "pandn Vx,Hx,Wx",
+        (0xE0, OperandSize) => "pavgb Vx,Hx,Wx",
+        (0xE1, OperandSize) => "psraw Vx,Hx,Wdq",
+        (0xE2, OperandSize) => "psrad Vx,Hx,Wdq",
+        (0xE3, OperandSize) => "pavgw Vx,Hx,Wx",
+        (0xE4, OperandSize) => "pmulhuw Vx,Hx,Wx",
+        (0xE5, OperandSize) => "pmulhw Vx,Hx,Wx",
+        (0xE6, OperandSize) => "cvttpd2dq Vdq,Wx",
+        (0xE6, Rep) => "cvtdq2pd Vx,Wh",
+        (0xE6, Repne) => "cvtpd2dq Vdq,Wx",
+        (0xE7, OperandSize) => "movntdq Mx,Vx",
+        (0xE8, OperandSize) => "psubsb Vx,Hx,Wx",
+        (0xE9, OperandSize) => "psubsw Vx,Hx,Wx",
+        (0xEA, OperandSize) => "pminsw Vx,Hx,Wx",
+        (0xEB, OperandSize) => "por Vx,Hx,Wx",
+        (0xEC, OperandSize) => "paddsb Vx,Hx,Wx",
+        (0xED, OperandSize) => "paddsw Vx,Hx,Wx",
+        (0xEE, OperandSize) => "pmaxsw Vx,Hx,Wx",
+        (0xEF, OperandSize) => "pxor Vx,Hx,Wx",
+        (0xF0, Repne) => "lddqu Vx,Mx",
+        (0xF1, OperandSize) => "psllw Vx,Hx,Wdq",
+        (0xF2, OperandSize) => "pslld Vx,Hx,Wdq",
+        (0xF3, OperandSize) => "psllq Vx,Hx,Wdq",
+        (0xF4, OperandSize) => "pmuludq Vx,Hx,Wx",
+        (0xF5, OperandSize) => "pmaddwd Vx,Hx,Wx",
+        (0xF6, OperandSize) => "psadbw Vx,Hx,Wx",
+        (0xF7, OperandSize) => "maskmovdqu Vdq,Udq",
+        (0xF8, OperandSize) => "psubb Vx,Hx,Wx",
+        (0xF9, OperandSize) => "psubw Vx,Hx,Wx",
+        (0xFA, OperandSize) => "psubd Vx,Hx,Wx",
+        (0xFB, OperandSize) => "psubq Vx,Hx,Wx",
+        (0xFC, OperandSize) => "paddb Vx,Hx,Wx",
+        (0xFD, OperandSize) => "paddw Vx,Hx,Wx",
+        (0xFE, OperandSize) => "paddd Vx,Hx,Wx",
+        _ => return Option::None,
+    };
+    Some(String::from(spec))
+}
+
+fn vector_three_byte_38(opcode: u8) -> Option<&'static str> {
+    Some(match opcode {
+        0x00 => "pshufb Vx,Hx,Wx",
+        0x01 => "phaddw Vx,Hx,Wx",
+        0x02 => "phaddd Vx,Hx,Wx",
+        0x03 => "phaddsw Vx,Hx,Wx",
+        0x04 => "pmaddubsw Vx,Hx,Wx",
+        0x05 => "phsubw Vx,Hx,Wx",
+        0x06 => "phsubd Vx,Hx,Wx",
+        0x07 => "phsubsw Vx,Hx,Wx",
+        0x08 => "psignb Vx,Hx,Wx",
+        0x09 => "psignw Vx,Hx,Wx",
+        0x0A => "psignd Vx,Hx,Wx",
+        0x0B => "pmulhrsw Vx,Hx,Wx",
+        0x0C => "vpermilps Vx,Hx,Wx",
+        0x0D => "vpermilpd Vx,Hx,Wx",
+        0x0E => "vtestps Vx,Wx",
+        0x0F => "vtestpd Vx,Wx",
+        0x10 => "pblendvb Vdq,Wdq,X0",
+        0x13 => "vcvtph2ps Vx,Wh",
+        0x14 => "blendvps Vdq,Wdq,X0",
+        0x15 => "blendvpd Vdq,Wdq,X0",
+        0x16 => "vpermps Vqq,Hqq,Wqq",
+        0x17 => "ptest Vx,Wx",
+        0x18 => "vbroadcastss Vx,Wd",
+        0x19 => "vbroadcastsd Vqq,Wq",
+        0x1A => "vbroadcastf128 Vqq,Mdq",
+        0x1C => "pabsb Vx,Wx",
+        0x1D => "pabsw Vx,Wx",
+        0x1E => "pabsd Vx,Wx",
+        0x20 => "pmovsxbw Vx,Wh",
+        0x21 => "pmovsxbd Vx,Wf",
+        0x22 => "pmovsxbq Vx,We",
+        0x23 => "pmovsxwd Vx,Wh",
+        0x24 => "pmovsxwq Vx,Wf",
+        0x25 => "pmovsxdq Vx,Wh",
+        0x28 => "pmuldq Vx,Hx,Wx",
+        0x29 => "pcmpeqq Vx,Hx,Wx",
+        0x2A => "movntdqa Vx,Mx",
+        0x2B => "packusdw Vx,Hx,Wx",
+        0x2C => "vmaskmovps Vx,Hx,Mx",
+        0x2D => "vmaskmovpd Vx,Hx,Mx",
+        0x2E => "vmaskmovps Mx,Hx,Vx",
+        0x2F => "vmaskmovpd Mx,Hx,Vx",
+        0x30 => "pmovzxbw Vx,Wh",
+        0x31 => "pmovzxbd Vx,Wf",
+        0x32 => "pmovzxbq Vx,We",
+        0x33 => "pmovzxwd Vx,Wh",
+        0x34 => "pmovzxwq Vx,Wf",
+        0x35 => "pmovzxdq Vx,Wh",
+        0x36 => "vpermd Vqq,Hqq,Wqq",
+        0x37 => "pcmpgtq Vx,Hx,Wx",
+        0x38 => "pminsb Vx,Hx,Wx",
+        0x39 => "pminsd Vx,Hx,Wx",
+        0x3A => "pminuw Vx,Hx,Wx",
+        0x3B => "pminud Vx,Hx,Wx",
+        0x3C => "pmaxsb Vx,Hx,Wx",
+        0x3D => "pmaxsd Vx,Hx,Wx",
+        0x3E => "pmaxuw Vx,Hx,Wx",
+        0x3F => "pmaxud Vx,Hx,Wx",
+        0x40 => "pmulld Vx,Hx,Wx",
+        0x41 => "phminposuw Vdq,Wdq",
+        0x45 => "vpsrlvd|vpsrlvq Vx,Hx,Wx",
+        0x46 => "vpsravd Vx,Hx,Wx",
+        0x47 => "vpsllvd|vpsllvq Vx,Hx,Wx",
+        0x58 => "vpbroadcastd Vx,Wd",
+        0x59 => "vpbroadcastq Vx,Wq",
+        0x5A => "vbroadcasti128 Vqq,Mdq",
+        0x78 => "vpbroadcastb Vx,Wb",
+        0x79 => "vpbroadcastw Vx,Ww",
+        0x8C => "vpmaskmovd|vpmaskmovq Vx,Hx,Mx",
+        0x8E => "vpmaskmovd|vpmaskmovq Mx,Hx,Vx",
+        0xDB => "aesimc Vdq,Wdq",
+        0xDC => "aesenc Vx,Hx,Wx",
+        0xDD => "aesenclast Vx,Hx,Wx",
+        0xDE => "aesdec Vx,Hx,Wx",
+        0xDF => "aesdeclast Vx,Hx,Wx",
+        _ => return None,
+    })
+}
+
+fn vector_three_byte_3a(opcode: u8) -> Option<&'static str> {
+    Some(match opcode {
+        0x00 => "vpermq Vqq,Wqq,Ib",
+        0x01 => "vpermpd Vqq,Wqq,Ib",
+        0x02 => "vpblendd Vx,Hx,Wx,Ib",
+        0x04 => "vpermilps Vx,Wx,Ib",
+        0x05 => "vpermilpd Vx,Wx,Ib",
+        0x06 => "vperm2f128 Vqq,Hqq,Wqq,Ib",
+        0x08 => "roundps Vx,Wx,Ib",
+        0x09 => "roundpd Vx,Wx,Ib",
+        0x0A => "roundss Vdq,Hdq,Wd,Ib",
+        0x0B => "roundsd Vdq,Hdq,Wq,Ib",
+        0x0C => "blendps Vx,Hx,Wx,Ib",
+        0x0D => "blendpd Vx,Hx,Wx,Ib",
+        0x0E => "pblendw Vx,Hx,Wx,Ib",
+        0x0F => "palignr Vx,Hx,Wx,Ib",
+        0x14 => "pextrb Edb,Vdq,Ib",
+        0x15 => "pextrw Edw,Vdq,Ib",
+        0x16 => "pextrd|pextrq Ey,Vdq,Ib",
+        0x17 => "extractps Ed,Vdq,Ib",
+        0x18 => "vinsertf128 Vqq,Hqq,Wdq,Ib",
+        0x19 => "vextractf128 Wdq,Vqq,Ib",
+        0x1D => "vcvtps2ph Wh,Vx,Ib",
+        0x20 => "pinsrb Vdq,Hdq,Edb,Ib",
+        0x21 => "insertps Vdq,Hdq,Wd,Ib",
+        0x22 => "pinsrd|pinsrq Vdq,Hdq,Ey,Ib",
+        0x38 => "vinserti128 Vqq,Hqq,Wdq,Ib",
+        0x39 => "vextracti128 Wdq,Vqq,Ib",
+        0x40 => "dpps Vx,Hx,Wx,Ib",
+        0x41 => "dppd Vdq,Hdq,Wdq,Ib",
+        0x42 => "mpsadbw Vx,Hx,Wx,Ib",
+        0x44 => "pclmulqdq Vdq,Hdq,Wdq",
+        0x46 => "vperm2i128 Vqq,Hqq,Wqq,Ib",
+        0x4A => "vblendvps Vx,Hx,Wx,Lx",
+        0x4B => "vblendvpd Vx,Hx,Wx,Lx",
+        0x4C => "vpblendvb Vx,Hx,Wx,Lx",
+        0x60 => "pcmpestrm Vdq,Wdq,Ib",
+        0x61 => "pcmpestri Vdq,Wdq,Ib",
+        0x62 => "pcmpistrm Vdq,Wdq,Ib",
+        0x63 => "pcmpistri Vdq,Wdq,Ib",
+        0xDF => "aeskeygenassist Vdq,Wdq,Ib",
+        _ => return None,
+    })
+}
+
+// FMA3 opcodes encode the operand order (132, 213, 231) in the high nibble and the operation in the low one
+fn fused_multiply_add(opcode: u8, is_double: bool) -> Option<String> {
+    let order = match opcode >> 4 {
+        0x9 => "132",
+        0xA => "213",
+        0xB => "231",
+        _ => return None,
+    };
+    let (operation, is_scalar) = match opcode & 0xF {
+        0x6 => ("fmaddsub", false),
+        0x7 => ("fmsubadd", false),
+        0x8 => ("fmadd", false),
+        0x9 => ("fmadd", true),
+        0xA => ("fmsub", false),
+        0xB => ("fmsub", true),
+        0xC => ("fnmadd", false),
+        0xD => ("fnmadd", true),
+        0xE => ("fnmsub", false),
+        0xF => ("fnmsub", true),
+        _ => return None,
+    };
+    Some(match (is_scalar, is_double) {
+        (false, false) => format!("v{}{}ps Vx,Hx,Wx", operation, order),
+        (false, true) => format!("v{}{}pd Vx,Hx,Wx", operation, order),
+        (true, false) => format!("v{}{}ss Vdq,Hdq,Wd", operation, order),
+        (true, true) => format!("v{}{}sd Vdq,Hdq,Wq", operation, order),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(bytes: &[u8], address: u64) -> String {
+        let instruction = decode(bytes, address);
+        assert_eq!(instruction.length, bytes.len());
+        format!("{} {}", instruction.mnemonic, instruction.operands).trim_end().to_string()
+    }
+
+    #[test]
+    fn decode_should_match_objdump_intel_syntax() {
+        assert_eq!(text(&[0xF3, 0x0F, 0x1E, 0xFA], 0), "endbr64");
+        assert_eq!(text(&[0x48, 0x83, 0xEC, 0x08], 0), "sub rsp,0x8");
+        assert_eq!(text(&[0x48, 0x83, 0xE4, 0xF0], 0), "and rsp,0xfffffffffffffff0");
+        assert_eq!(text(&[0xC7, 0x45, 0xFC, 0x00, 0x00, 0x00, 0x00], 0), "mov DWORD PTR [rbp-0x4],0x0");
+        assert_eq!(text(&[0x66, 0x2E, 0x0F, 0x1F, 0x84, 0x00, 0x00, 0x00, 0x00, 0x00], 0), "cs nop WORD PTR [rax+rax*1+0x0]");
+        assert_eq!(text(&[0x64, 0x48, 0x8B, 0x04, 0x25, 0x28, 0x00, 0x00, 0x00], 0), "mov rax,QWORD PTR fs:0x28");
+        assert_eq!(text(&[0xF3, 0x48, 0xAB], 0), "rep stos QWORD PTR es:[rdi],rax");
+        assert_eq!(text(&[0xF2, 0x0F, 0x11, 0x84, 0xC5, 0xE0, 0xFD, 0xFF, 0xFF], 0), "movsd QWORD PTR [rbp+rax*8-0x220],xmm0");
+        assert_eq!(text(&[0xC5, 0xFD, 0xFE, 0x04, 0x07], 0), "vpaddd ymm0,ymm0,YMMWORD PTR [rdi+rax*1]");
+        assert_eq!(text(&[0xC4, 0xE2, 0xF1, 0xA9, 0xC2], 0), "vfmadd213sd xmm0,xmm1,xmm2");
+        assert_eq!(text(&[0xC5, 0xF8, 0x77], 0), "vzeroupper");
+        assert_eq!(decode(&[0x0F, 0xFF, 0x00], 0).mnemonic, "(bad)");
+    }
+
+    #[test]
+    fn decode_should_resolve_branch_targets_and_rip_references() {
+        let call = decode(&[0xE8, 0xF2, 0xFE, 0xFF, 0xFF], 0x1139);
+        assert_eq!(call.operands, "1030");
+        assert_eq!(call.target, Some(0x1030));
+
+        let load = decode(&[0x48, 0x8D, 0x05, 0xC4, 0x0E, 0x00, 0x00], 0x1139);
+        assert_eq!(load.operands, "rax,[rip+0xec4]");
+        assert_eq!(load.reference, Some(0x2004));
+    }
+}